@@ -0,0 +1,123 @@
+//! Conditional policies evaluated against attributes of the request.
+//!
+//! A model opts in by adding a `ctx` token to the request definition and a `cond` token
+//! to the policy definition, and by calling `matchCondition` in the matcher:
+//!
+//! ```text
+//! r = sub, dom, obj, act, ctx
+//! p = sub, dom, obj, act, cond
+//! m = ... && (p.cond == "" || matchCondition(r.ctx, p.cond))
+//! ```
+//!
+//! A condition is a list of clauses joined with `&&`, such as
+//! `ip(10.0.0.0/8) && time(09:00-18:00)`. Each clause calls a function registered with
+//! [`register_condition`]. Unknown functions and malformed clauses never match, so a
+//! broken condition denies access instead of granting it.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{OnceLock, RwLock},
+};
+
+use casbin::{
+    function_map::OperatorFunction,
+    rhai::{Dynamic, ImmutableString},
+    CoreApi,
+};
+use serde::{Deserialize, Serialize};
+
+/// Name of the matcher function evaluating `p.cond` against `r.ctx`.
+pub const MATCH_CONDITION: &str = "matchCondition";
+
+/// Request attributes passed to the matcher as `r.ctx`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestAttributes {
+    /// Client IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    /// Local time of the request, formatted as `%Y-%m-%dT%H:%M`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Selected request headers, keyed by lowercase name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Identifier of the request. Not part of `r.ctx`, as it would defeat the decision cache.
+    #[serde(skip)]
+    pub request_id: Option<String>,
+}
+
+impl RequestAttributes {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Serialized form used as the `ctx` request value.
+    pub fn to_context(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// A condition function receives the request attributes and the arguments of its clause.
+pub type ConditionFn = fn(&RequestAttributes, &str) -> bool;
+
+fn conditions() -> &'static RwLock<HashMap<String, ConditionFn>> {
+    static CONDITIONS: OnceLock<RwLock<HashMap<String, ConditionFn>>> = OnceLock::new();
+    CONDITIONS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers a condition function, replacing any function with the same name.
+pub fn register_condition(name: &str, f: ConditionFn) {
+    conditions().write().unwrap().insert(name.to_string(), f);
+}
+
+/// Evaluates `condition` against `attributes`; an empty condition always matches.
+pub fn evaluate_condition(condition: &str, attributes: &RequestAttributes) -> bool {
+    condition
+        .split("&&")
+        .map(str::trim)
+        .filter(|clause| !clause.is_empty())
+        .all(|clause| evaluate_clause(clause, attributes))
+}
+
+fn evaluate_clause(clause: &str, attributes: &RequestAttributes) -> bool {
+    let Some((name, args)) = clause
+        .strip_suffix(')')
+        .and_then(|clause| clause.split_once('('))
+    else {
+        return false;
+    };
+
+    let f = conditions().read().unwrap().get(name.trim()).copied();
+    f.is_some_and(|f| f(attributes, args.trim()))
+}
+
+fn match_condition(ctx: ImmutableString, condition: ImmutableString) -> Dynamic {
+    let attributes = serde_json::from_str::<RequestAttributes>(&ctx).unwrap_or_default();
+    Dynamic::from(evaluate_condition(&condition, &attributes))
+}
+
+/// Makes `matchCondition` available to the matcher of `enforcer`.
+pub fn register_condition_function(enforcer: &mut impl CoreApi) {
+    enforcer.add_function(MATCH_CONDITION, OperatorFunction::Arg2(match_condition));
+}
+
+/// Appends the request context to `rvals` when the request definition of the model has
+/// one more token than given, so models without a `ctx` token keep working unchanged.
+pub fn with_context(
+    enforcer: &impl CoreApi,
+    mut rvals: Vec<String>,
+    attributes: &RequestAttributes,
+) -> Vec<String> {
+    let tokens = enforcer
+        .get_model()
+        .get_model()
+        .get("r")
+        .and_then(|ast_map| ast_map.get("r"))
+        .map_or(0, |ast| ast.tokens.len());
+    if tokens == rvals.len() + 1 {
+        rvals.push(attributes.to_context());
+    }
+    rvals
+}
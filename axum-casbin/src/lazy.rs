@@ -0,0 +1,254 @@
+//! Loads the policies of each domain on first use instead of the whole policy at startup.
+//!
+//! The enforcer starts with a filtered (possibly empty) policy. Before a request in a
+//! domain is enforced, [`LazyDomainPolicies::pin`] merges the rules of that domain into
+//! the model and keeps it loaded until the enforcement is done. At most `capacity`
+//! domains are kept; the least recently used one is unloaded when another domain has
+//! to be loaded.
+//!
+//! Domains are expected at index 1 of `p` rules and index 2 of `g` rules, as in the
+//! `rbac_with_domains` model.
+
+use std::{collections::VecDeque, sync::Arc};
+
+#[cfg(feature = "runtime-async-std")]
+use async_std::sync::RwLock;
+use async_trait::async_trait;
+use casbin::{CachedApi, CachedEnforcer, CoreApi, EventData, Result as CasbinResult};
+use futures::lock::{Mutex, OwnedMutexGuard};
+#[cfg(feature = "runtime-tokio")]
+use tokio::sync::RwLock;
+
+const P_DOMAIN_INDEX: usize = 1;
+const G_DOMAIN_INDEX: usize = 2;
+
+/// Source of the policies of a single domain.
+#[async_trait]
+pub trait DomainPolicyLoader: Send + Sync + 'static {
+    /// Returns the `(ptype, rule)` pairs of `domain`.
+    async fn load_domain(&self, domain: &str) -> CasbinResult<Vec<(String, Vec<String>)>>;
+}
+
+pub struct LazyDomainPolicies {
+    loader: Arc<dyn DomainPolicyLoader>,
+    capacity: usize,
+    /// Loaded domains, least recently used first.
+    loaded: Arc<Mutex<VecDeque<String>>>,
+}
+
+/// Keeps the domains passed to [`LazyDomainPolicies::pin`] loaded until dropped.
+///
+/// No domain is loaded, evicted or reloaded while a guard is alive, so it should only
+/// be held around the enforcement or policy change that needs the domains.
+pub struct DomainGuard {
+    _loaded: OwnedMutexGuard<VecDeque<String>>,
+}
+
+impl LazyDomainPolicies {
+    pub fn new(loader: Arc<dyn DomainPolicyLoader>, capacity: usize) -> Self {
+        Self {
+            loader,
+            capacity: capacity.max(1),
+            loaded: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub async fn is_loaded(&self, domain: &str) -> bool {
+        self.loaded.lock().await.iter().any(|d| d == domain)
+    }
+
+    /// Loaded domains, least recently used first.
+    pub async fn loaded_domains(&self) -> Vec<String> {
+        self.loaded.lock().await.iter().cloned().collect()
+    }
+
+    /// Makes sure the policies of `domain` are in the model of `enforcer`, evicting the
+    /// least recently used domain when the cache is full.
+    ///
+    /// The domain may be evicted again as soon as this returns; use [`Self::pin`] to
+    /// keep it loaded while enforcing.
+    pub async fn ensure_loaded(
+        &self,
+        enforcer: &RwLock<CachedEnforcer>,
+        domain: &str,
+    ) -> CasbinResult<()> {
+        self.pin(enforcer, &[domain]).await.map(drop)
+    }
+
+    /// Loads the policies of `domains` like [`Self::ensure_loaded`] and keeps them loaded
+    /// until the returned guard is dropped.
+    ///
+    /// Pinned domains are never evicted to make room for each other, so the cache may
+    /// temporarily exceed its capacity.
+    pub async fn pin(
+        &self,
+        enforcer: &RwLock<CachedEnforcer>,
+        domains: &[&str],
+    ) -> CasbinResult<DomainGuard> {
+        // Held while loading so that concurrent requests do not load a domain twice.
+        let mut loaded = self.loaded.clone().lock_owned().await;
+        for domain in domains {
+            self.load(&mut loaded, enforcer, domain, domains).await?;
+        }
+        Ok(DomainGuard { _loaded: loaded })
+    }
+
+    async fn load(
+        &self,
+        loaded: &mut VecDeque<String>,
+        enforcer: &RwLock<CachedEnforcer>,
+        domain: &str,
+        pinned: &[&str],
+    ) -> CasbinResult<()> {
+        if let Some(pos) = loaded.iter().position(|d| d == domain) {
+            if let Some(d) = loaded.remove(pos) {
+                loaded.push_back(d);
+            }
+            return Ok(());
+        }
+
+        let rules = self.loader.load_domain(domain).await?;
+
+        let mut enforcer = enforcer.write().await;
+        while loaded.len() >= self.capacity {
+            let Some(pos) = loaded.iter().position(|d| !pinned.contains(&d.as_str())) else {
+                break;
+            };
+            if let Some(evicted) = loaded.remove(pos) {
+                unload_domain(&mut enforcer, &evicted)?;
+            }
+        }
+        merge_rules(&mut enforcer, rules)?;
+        enforcer.get_mut_cache().clear();
+        loaded.push_back(domain.to_string());
+
+        Ok(())
+    }
+
+    /// Replaces the policies in the model with freshly loaded rules of the loaded
+    /// domains, for when changes may have been missed.
+    ///
+    /// Unlike `load_policy`, this keeps the model limited to the loaded domains. If a
+    /// domain fails to load, all domains are unloaded and loaded again on next use.
+    pub async fn reload(&self, enforcer: &RwLock<CachedEnforcer>) -> CasbinResult<()> {
+        let mut loaded = self.loaded.lock().await;
+        let domains: Vec<String> = loaded.drain(..).collect();
+
+        let mut rules = Vec::new();
+        let mut result = Ok(());
+        for domain in &domains {
+            match self.loader.load_domain(domain).await {
+                Ok(domain_rules) => rules.extend(domain_rules),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+        }
+
+        let mut enforcer = enforcer.write().await;
+        enforcer.get_mut_model().clear_policy();
+        if result.is_ok() {
+            merge_rules(&mut enforcer, rules)?;
+            loaded.extend(domains);
+        }
+        enforcer.build_role_links()?;
+        enforcer.get_mut_cache().clear();
+
+        result
+    }
+
+    /// Unloads `domain` so that its policies are read again on next use.
+    pub async fn invalidate(
+        &self,
+        enforcer: &RwLock<CachedEnforcer>,
+        domain: &str,
+    ) -> CasbinResult<()> {
+        let mut loaded = self.loaded.lock().await;
+        if let Some(pos) = loaded.iter().position(|d| d == domain) {
+            loaded.remove(pos);
+            let mut enforcer = enforcer.write().await;
+            unload_domain(&mut enforcer, domain)?;
+            enforcer.get_mut_cache().clear();
+        }
+        Ok(())
+    }
+}
+
+fn merge_rules(
+    enforcer: &mut CachedEnforcer,
+    rules: Vec<(String, Vec<String>)>,
+) -> CasbinResult<()> {
+    let mut grouping: Vec<(String, Vec<Vec<String>>)> = Vec::new();
+    for (ptype, mut rule) in rules {
+        let Some(sec) = ptype.get(..1).map(str::to_string) else {
+            continue;
+        };
+        // Trailing empty fields are not stored, restore them to match the definition.
+        let tokens = enforcer
+            .get_model()
+            .get_model()
+            .get(&sec)
+            .and_then(|ast_map| ast_map.get(&ptype))
+            .map_or(0, |ast| ast.tokens.len());
+        if rule.len() < tokens {
+            rule.resize(tokens, String::new());
+        }
+        // Rules already in the model, e.g. received from another instance, are skipped.
+        if !enforcer
+            .get_mut_model()
+            .add_policy(&sec, &ptype, rule.clone())
+        {
+            continue;
+        }
+        if sec == "g" {
+            match grouping.iter_mut().find(|(p, _)| *p == ptype) {
+                Some((_, added)) => added.push(rule),
+                None => grouping.push((ptype, vec![rule])),
+            }
+        }
+    }
+
+    for (ptype, added) in grouping {
+        enforcer.build_incremental_role_links(EventData::AddPolicies(
+            "g".to_string(),
+            ptype,
+            added,
+        ))?;
+    }
+    Ok(())
+}
+
+fn unload_domain(enforcer: &mut CachedEnforcer, domain: &str) -> CasbinResult<()> {
+    let model = enforcer.get_mut_model();
+    let ptypes = |sec: &str| -> Vec<String> {
+        model
+            .get_model()
+            .get(sec)
+            .map(|ast_map| ast_map.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    let p_types = ptypes("p");
+    let g_types = ptypes("g");
+
+    for ptype in &p_types {
+        model.remove_filtered_policy("p", ptype, P_DOMAIN_INDEX, vec![domain.to_string()]);
+    }
+    let mut removed_grouping = false;
+    for ptype in &g_types {
+        let (removed, _) =
+            model.remove_filtered_policy("g", ptype, G_DOMAIN_INDEX, vec![domain.to_string()]);
+        removed_grouping |= removed;
+    }
+
+    // Removing links incrementally could drop links that other domains still need,
+    // so the role manager is rebuilt from the remaining rules.
+    if removed_grouping {
+        enforcer.build_role_links()?;
+    }
+    Ok(())
+}
@@ -1,4 +1,18 @@
 pub use casbin;
+pub use condition::{
+    evaluate_condition, register_condition, register_condition_function, with_context, ConditionFn,
+    RequestAttributes, MATCH_CONDITION,
+};
+pub use lazy::{DomainGuard, DomainPolicyLoader, LazyDomainPolicies};
 pub use middleware::{CasbinAxumLayer, CasbinAxumMiddleware, CasbinVals};
+#[cfg(feature = "runtime-tokio")]
+pub use watcher::{
+    apply_policy_change, reload_message, watch_policy_changes, MemoryPolicyChannel, PolicyChange,
+    PolicyChannel, PolicyChannelError, PolicyWatcher,
+};
 
+pub mod condition;
+pub mod lazy;
 pub mod middleware;
+#[cfg(feature = "runtime-tokio")]
+pub mod watcher;
@@ -21,22 +21,32 @@ use http_body_util::Full;
 use tokio::sync::RwLock;
 use tower::{Layer, Service};
 
-#[derive(Clone)]
+use crate::{
+    condition::{register_condition_function, with_context, RequestAttributes},
+    lazy::{DomainGuard, LazyDomainPolicies},
+};
+
+#[derive(Clone, Default)]
 pub struct CasbinVals {
     pub subject: Vec<String>,
     pub domain: Option<String>,
+    /// Attributes matched against conditional policies.
+    pub attributes: RequestAttributes,
 }
 
 #[derive(Clone)]
 pub struct CasbinAxumLayer {
     enforcer: Arc<RwLock<CachedEnforcer>>,
+    lazy_domains: Option<Arc<LazyDomainPolicies>>,
 }
 
 impl CasbinAxumLayer {
     pub async fn new<M: TryIntoModel, A: TryIntoAdapter>(m: M, a: A) -> CasbinResult<Self> {
-        let enforcer: CachedEnforcer = CachedEnforcer::new(m, a).await?;
+        let mut enforcer: CachedEnforcer = CachedEnforcer::new(m, a).await?;
+        register_condition_function(&mut enforcer);
         Ok(CasbinAxumLayer {
             enforcer: Arc::new(RwLock::new(enforcer)),
+            lazy_domains: None,
         })
     }
 
@@ -45,7 +55,38 @@ impl CasbinAxumLayer {
     }
 
     pub fn set_enforcer(e: Arc<RwLock<CachedEnforcer>>) -> CasbinAxumLayer {
-        CasbinAxumLayer { enforcer: e }
+        CasbinAxumLayer {
+            enforcer: e,
+            lazy_domains: None,
+        }
+    }
+
+    /// Loads the policies of a domain on its first request instead of up front.
+    pub fn with_lazy_domains(mut self, lazy_domains: LazyDomainPolicies) -> Self {
+        self.lazy_domains = Some(Arc::new(lazy_domains));
+        self
+    }
+
+    pub fn get_lazy_domains(&self) -> Option<Arc<LazyDomainPolicies>> {
+        self.lazy_domains.clone()
+    }
+
+    /// Makes sure the policies of `domain` are loaded, a no-op unless lazy loading is on.
+    pub async fn ensure_domain_loaded(&self, domain: &str) -> CasbinResult<()> {
+        match &self.lazy_domains {
+            Some(lazy_domains) => lazy_domains.ensure_loaded(&self.enforcer, domain).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Loads the policies of `domains` and keeps them loaded until the guard is dropped.
+    ///
+    /// Returns `None` unless lazy loading is on, in which case every domain is loaded.
+    pub async fn pin_domains(&self, domains: &[&str]) -> CasbinResult<Option<DomainGuard>> {
+        match &self.lazy_domains {
+            Some(lazy_domains) => lazy_domains.pin(&self.enforcer, domains).await.map(Some),
+            None => Ok(None),
+        }
     }
 }
 
@@ -55,6 +96,7 @@ impl<S> Layer<S> for CasbinAxumLayer {
     fn layer(&self, inner: S) -> Self::Service {
         CasbinAxumMiddleware {
             enforcer: self.enforcer.clone(),
+            lazy_domains: self.lazy_domains.clone(),
             inner,
         }
     }
@@ -78,6 +120,7 @@ impl DerefMut for CasbinAxumLayer {
 pub struct CasbinAxumMiddleware<S> {
     inner: S,
     enforcer: Arc<RwLock<CachedEnforcer>>,
+    lazy_domains: Option<Arc<LazyDomainPolicies>>,
 }
 
 impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for CasbinAxumMiddleware<S>
@@ -103,6 +146,7 @@ where
 
     fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
         let cloned_enforcer = self.enforcer.clone();
+        let lazy_domains = self.lazy_domains.clone();
         let not_ready_inner = self.inner.clone();
         let mut inner = std::mem::replace(&mut self.inner, not_ready_inner);
 
@@ -124,17 +168,34 @@ where
 
             if !vals.subject.is_empty() {
                 if let Some(domain) = vals.domain {
+                    // The domain stays pinned until enforcement is done, so that another
+                    // request cannot evict it in between.
+                    let pinned = match lazy_domains {
+                        Some(lazy_domains) => {
+                            match lazy_domains.pin(&cloned_enforcer, &[&domain]).await {
+                                Ok(guard) => Some(guard),
+                                Err(_) => {
+                                    return Ok(Response::builder()
+                                        .status(StatusCode::BAD_GATEWAY)
+                                        .body(body::Body::new(Full::from("We encountered an unexpected error while processing your request. Our team has been notified, and we are investigating the issue.")))
+                                        .unwrap());
+                                },
+                            }
+                        },
+                        None => None,
+                    };
+
                     let mut lock = cloned_enforcer.write().await;
                     let mut authorized = false;
                     let mut enforcement_error = false;
 
                     for sub in subject.iter() {
-                        match lock.enforce_mut(vec![
-                            sub.clone(),
-                            domain.clone(),
-                            path.clone(),
-                            action.clone(),
-                        ]) {
+                        let rvals = with_context(
+                            &*lock,
+                            vec![sub.clone(), domain.clone(), path.clone(), action.clone()],
+                            &vals.attributes,
+                        );
+                        match lock.enforce_mut(rvals) {
                             Ok(true) => {
                                 authorized = true;
                                 break;
@@ -148,6 +209,7 @@ where
                     }
 
                     drop(lock);
+                    drop(pinned);
 
                     if enforcement_error {
                         Ok(Response::builder()
@@ -168,7 +230,12 @@ where
                     let mut enforcement_error = false;
 
                     for sub in subject.iter() {
-                        match lock.enforce_mut(vec![sub.clone(), path.clone(), action.clone()]) {
+                        let rvals = with_context(
+                            &*lock,
+                            vec![sub.clone(), path.clone(), action.clone()],
+                            &vals.attributes,
+                        );
+                        match lock.enforce_mut(rvals) {
                             Ok(true) => {
                                 authorized = true;
                                 break;
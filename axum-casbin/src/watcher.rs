@@ -0,0 +1,281 @@
+//! Propagates policy changes between enforcers running in different processes.
+//!
+//! Every change made through the management API of an enforcer is published on a
+//! [`PolicyChannel`]. The other instances subscribed to the same channel apply the
+//! change to their in-memory model (or reload the policies when the change cannot be
+//! applied incrementally) and clear their decision cache. With lazy loading, only the
+//! loaded domains are reloaded.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use casbin::{CachedApi, CachedEnforcer, CoreApi, EventData, Watcher};
+use futures::{stream::BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, RwLock};
+
+use crate::lazy::LazyDomainPolicies;
+
+/// A policy change, as published to the other instances.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PolicyChange {
+    AddPolicies {
+        sec: String,
+        ptype: String,
+        rules: Vec<Vec<String>>,
+    },
+    RemovePolicies {
+        sec: String,
+        ptype: String,
+        rules: Vec<Vec<String>>,
+    },
+    /// The whole policy was replaced; receivers reload it from the adapter.
+    Reload,
+    ClearCache,
+}
+
+impl From<EventData> for PolicyChange {
+    fn from(data: EventData) -> Self {
+        match data {
+            EventData::AddPolicy(sec, ptype, rule) => PolicyChange::AddPolicies {
+                sec,
+                ptype,
+                rules: vec![rule],
+            },
+            EventData::AddPolicies(sec, ptype, rules) => {
+                PolicyChange::AddPolicies { sec, ptype, rules }
+            },
+            EventData::RemovePolicy(sec, ptype, rule) => PolicyChange::RemovePolicies {
+                sec,
+                ptype,
+                rules: vec![rule],
+            },
+            EventData::RemovePolicies(sec, ptype, rules)
+            | EventData::RemoveFilteredPolicy(sec, ptype, rules) => {
+                PolicyChange::RemovePolicies { sec, ptype, rules }
+            },
+            EventData::SavePolicy(_) | EventData::ClearPolicy => PolicyChange::Reload,
+            EventData::ClearCache => PolicyChange::ClearCache,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PolicyMessage {
+    /// Instance that made the change, so it can skip its own messages.
+    instance_id: String,
+    change: PolicyChange,
+}
+
+#[derive(Debug)]
+pub struct PolicyChannelError(pub String);
+
+impl fmt::Display for PolicyChannelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "policy channel error: {}", self.0)
+    }
+}
+
+impl std::error::Error for PolicyChannelError {}
+
+/// Transport used to broadcast policy changes between instances.
+#[async_trait]
+pub trait PolicyChannel: Send + Sync + 'static {
+    async fn publish(&self, payload: String) -> Result<(), PolicyChannelError>;
+
+    async fn subscribe(&self) -> Result<BoxStream<'static, String>, PolicyChannelError>;
+}
+
+/// In-process channel, for tests and single-process deployments.
+#[derive(Clone)]
+pub struct MemoryPolicyChannel {
+    sender: broadcast::Sender<String>,
+}
+
+impl MemoryPolicyChannel {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self { sender }
+    }
+}
+
+impl Default for MemoryPolicyChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PolicyChannel for MemoryPolicyChannel {
+    async fn publish(&self, payload: String) -> Result<(), PolicyChannelError> {
+        // Having no subscriber is not an error.
+        let _ = self.sender.send(payload);
+        Ok(())
+    }
+
+    async fn subscribe(&self) -> Result<BoxStream<'static, String>, PolicyChannelError> {
+        let receiver = self.sender.subscribe();
+        Ok(
+            futures::stream::unfold(receiver, |mut receiver| async move {
+                match receiver.recv().await {
+                    Ok(payload) => Some((payload, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        // Missed changes cannot be replayed, fall back to a full reload.
+                        Some((reload_message(), receiver))
+                    },
+                    Err(broadcast::error::RecvError::Closed) => None,
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+/// A message that makes every subscriber reload its policies.
+///
+/// Channels emit it when changes may have been missed, e.g. after reconnecting.
+pub fn reload_message() -> String {
+    let message = PolicyMessage {
+        instance_id: String::new(),
+        change: PolicyChange::Reload,
+    };
+    serde_json::to_string(&message).unwrap_or_default()
+}
+
+type UpdateCallback = Arc<Mutex<Option<Box<dyn FnMut() + Send + Sync>>>>;
+
+/// Casbin watcher that forwards local policy changes to the publisher task.
+pub struct PolicyWatcher {
+    sender: mpsc::UnboundedSender<PolicyChange>,
+    callback: UpdateCallback,
+}
+
+impl Watcher for PolicyWatcher {
+    fn set_update_callback(&mut self, cb: Box<dyn FnMut() + Send + Sync>) {
+        *self.callback.lock().unwrap() = Some(cb);
+    }
+
+    fn update(&mut self, d: EventData) {
+        // Called with the enforcer locked, so publishing happens on a separate task.
+        let _ = self.sender.send(d.into());
+    }
+}
+
+/// Publishes the policy changes of `enforcer` on `channel` and applies the changes
+/// published by other instances.
+///
+/// `lazy_domains` must be given when the enforcer loads policies per domain, so that
+/// reloads go through it instead of loading the whole policy.
+///
+/// Returns the identifier of this instance in the published messages.
+pub async fn watch_policy_changes(
+    enforcer: Arc<RwLock<CachedEnforcer>>,
+    channel: Arc<dyn PolicyChannel>,
+    lazy_domains: Option<Arc<LazyDomainPolicies>>,
+) -> Result<String, PolicyChannelError> {
+    let instance_id = new_instance_id();
+    let mut incoming = channel.subscribe().await?;
+
+    let (sender, mut outgoing) = mpsc::unbounded_channel::<PolicyChange>();
+    let callback: UpdateCallback = Arc::new(Mutex::new(None));
+    enforcer.write().await.set_watcher(Box::new(PolicyWatcher {
+        sender,
+        callback: callback.clone(),
+    }));
+
+    let publisher_channel = channel.clone();
+    let publisher_id = instance_id.clone();
+    tokio::spawn(async move {
+        while let Some(change) = outgoing.recv().await {
+            let message = PolicyMessage {
+                instance_id: publisher_id.clone(),
+                change,
+            };
+            if let Ok(payload) = serde_json::to_string(&message) {
+                let _ = publisher_channel.publish(payload).await;
+            }
+        }
+    });
+
+    let listener_id = instance_id.clone();
+    tokio::spawn(async move {
+        while let Some(payload) = incoming.next().await {
+            let Ok(message) = serde_json::from_str::<PolicyMessage>(&payload) else {
+                continue;
+            };
+            if message.instance_id == listener_id {
+                continue;
+            }
+
+            let mut guard = enforcer.write().await;
+            let applied = match (&lazy_domains, message.change) {
+                (Some(_), PolicyChange::Reload) => false,
+                (_, change) => apply_policy_change(&mut guard, change).await.is_ok(),
+            };
+            if applied {
+                guard.get_mut_cache().clear();
+                drop(guard);
+            } else if let Some(lazy_domains) = &lazy_domains {
+                // The lazy loader locks the enforcer itself.
+                drop(guard);
+                let _ = lazy_domains.reload(&enforcer).await;
+            } else {
+                let _ = guard.load_policy().await;
+                guard.get_mut_cache().clear();
+                drop(guard);
+            }
+
+            if let Some(cb) = callback.lock().unwrap().as_mut() {
+                cb();
+            }
+        }
+    });
+
+    Ok(instance_id)
+}
+
+/// Applies a change received from another instance to the in-memory model only;
+/// the instance that made the change has already persisted it through the adapter.
+///
+/// [`PolicyChange::Reload`] loads the whole policy; with lazy loading, use
+/// [`LazyDomainPolicies::reload`] instead.
+pub async fn apply_policy_change(
+    enforcer: &mut CachedEnforcer,
+    change: PolicyChange,
+) -> casbin::Result<()> {
+    match change {
+        PolicyChange::AddPolicies { sec, ptype, rules } => {
+            enforcer
+                .get_mut_model()
+                .add_policies(&sec, &ptype, rules.clone());
+            if sec == "g" {
+                enforcer.build_incremental_role_links(EventData::AddPolicies(sec, ptype, rules))?;
+            }
+        },
+        PolicyChange::RemovePolicies { sec, ptype, rules } => {
+            enforcer
+                .get_mut_model()
+                .remove_policies(&sec, &ptype, rules.clone());
+            if sec == "g" {
+                enforcer
+                    .build_incremental_role_links(EventData::RemovePolicies(sec, ptype, rules))?;
+            }
+        },
+        PolicyChange::Reload => enforcer.load_policy().await?,
+        PolicyChange::ClearCache => {},
+    }
+    Ok(())
+}
+
+fn new_instance_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!("{:x}-{:x}", std::process::id(), nanos)
+}
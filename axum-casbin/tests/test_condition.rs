@@ -0,0 +1,96 @@
+#![cfg(feature = "runtime-tokio")]
+
+use axum_casbin::{register_condition, with_context, CasbinAxumLayer, RequestAttributes};
+use casbin::{CoreApi, DefaultModel, MemoryAdapter, MgmtApi};
+
+fn ip_condition(attributes: &RequestAttributes, args: &str) -> bool {
+    attributes.ip.as_deref() == Some(args)
+}
+
+fn rule(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+fn attributes(ip: &str) -> RequestAttributes {
+    RequestAttributes {
+        ip: Some(ip.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_conditional_policies() {
+    register_condition("ip", ip_condition);
+
+    let model = DefaultModel::from_file("examples/rbac_with_domains_condition_model.conf")
+        .await
+        .unwrap();
+    let mut layer = CasbinAxumLayer::new(model, MemoryAdapter::default())
+        .await
+        .unwrap();
+    let enforcer = layer.get_enforcer();
+    let mut enforcer = enforcer.write().await;
+
+    enforcer
+        .add_policy(rule(&["admin", "d1", "/open", "GET", ""]))
+        .await
+        .unwrap();
+    enforcer
+        .add_policy(rule(&["admin", "d1", "/office", "GET", "ip(10.0.0.1)"]))
+        .await
+        .unwrap();
+    enforcer
+        .add_policy(rule(&["admin", "d1", "/broken", "GET", "unknown(1)"]))
+        .await
+        .unwrap();
+    enforcer
+        .add_grouping_policy(rule(&["alice", "admin", "d1"]))
+        .await
+        .unwrap();
+
+    let mut enforce = |path: &str, ip: &str| {
+        let rvals = with_context(
+            &*enforcer,
+            rule(&["alice", "d1", path, "GET"]),
+            &attributes(ip),
+        );
+        enforcer.enforce_mut(rvals).unwrap()
+    };
+
+    // Policies without a condition match regardless of the request attributes.
+    assert!(enforce("/open", "10.0.0.1"));
+    assert!(enforce("/open", "10.0.0.2"));
+    assert!(enforce("/office", "10.0.0.1"));
+    assert!(!enforce("/office", "10.0.0.2"));
+    // Unknown condition functions never match.
+    assert!(!enforce("/broken", "10.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_model_without_context() {
+    let model = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+        .await
+        .unwrap();
+    let mut layer = CasbinAxumLayer::new(model, MemoryAdapter::default())
+        .await
+        .unwrap();
+    let enforcer = layer.get_enforcer();
+    let mut enforcer = enforcer.write().await;
+
+    enforcer
+        .add_policy(rule(&["admin", "d1", "/open", "GET"]))
+        .await
+        .unwrap();
+    enforcer
+        .add_grouping_policy(rule(&["alice", "admin", "d1"]))
+        .await
+        .unwrap();
+
+    let rvals = with_context(
+        &*enforcer,
+        rule(&["alice", "d1", "/open", "GET"]),
+        &attributes("10.0.0.1"),
+    );
+    assert_eq!(rvals.len(), 4);
+    assert!(enforcer.enforce(rvals).unwrap());
+}
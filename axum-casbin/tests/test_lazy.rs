@@ -0,0 +1,180 @@
+#![cfg(feature = "runtime-tokio")]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use axum_casbin::{CasbinAxumLayer, DomainPolicyLoader, LazyDomainPolicies};
+use casbin::{CoreApi, DefaultModel, MemoryAdapter, MgmtApi, Result as CasbinResult};
+
+/// Every domain has an `admin` role granted to `alice`, with one policy per domain.
+struct FixtureLoader {
+    loads: AtomicUsize,
+}
+
+#[async_trait]
+impl DomainPolicyLoader for FixtureLoader {
+    async fn load_domain(&self, domain: &str) -> CasbinResult<Vec<(String, Vec<String>)>> {
+        self.loads.fetch_add(1, Ordering::SeqCst);
+        let rule = |v: [&str; 4]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Ok(vec![
+            (
+                "p".to_string(),
+                rule(["admin", domain, &format!("/{}/data", domain), "GET"]),
+            ),
+            (
+                "g".to_string(),
+                vec!["alice".to_string(), "admin".to_string(), domain.to_string()],
+            ),
+        ])
+    }
+}
+
+async fn new_layer(capacity: usize) -> (CasbinAxumLayer, Arc<FixtureLoader>) {
+    let model = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+        .await
+        .unwrap();
+    let loader = Arc::new(FixtureLoader {
+        loads: AtomicUsize::new(0),
+    });
+    let layer = CasbinAxumLayer::new(model, MemoryAdapter::default())
+        .await
+        .unwrap()
+        .with_lazy_domains(LazyDomainPolicies::new(loader.clone(), capacity));
+    (layer, loader)
+}
+
+async fn enforce(layer: &mut CasbinAxumLayer, domain: &str, path: &str) -> bool {
+    layer.ensure_domain_loaded(domain).await.unwrap();
+    layer
+        .get_enforcer()
+        .write()
+        .await
+        .enforce_mut(vec![
+            "alice".to_string(),
+            domain.to_string(),
+            path.to_string(),
+            "GET".to_string(),
+        ])
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_domains_load_on_first_use() {
+    let (mut layer, loader) = new_layer(8).await;
+    assert!(layer
+        .get_enforcer()
+        .read()
+        .await
+        .get_all_policy()
+        .is_empty());
+
+    assert!(enforce(&mut layer, "d1", "/d1/data").await);
+    assert!(enforce(&mut layer, "d1", "/d1/data").await);
+    assert_eq!(loader.loads.load(Ordering::SeqCst), 1);
+
+    // Policies of one domain never grant access in another.
+    assert!(!enforce(&mut layer, "d2", "/d1/data").await);
+    assert!(enforce(&mut layer, "d2", "/d2/data").await);
+    assert_eq!(loader.loads.load(Ordering::SeqCst), 2);
+    assert_eq!(layer.get_enforcer().read().await.get_all_policy().len(), 2);
+}
+
+#[tokio::test]
+async fn test_least_recently_used_domain_is_evicted() {
+    let (mut layer, loader) = new_layer(2).await;
+    let lazy = layer.get_lazy_domains().unwrap();
+
+    enforce(&mut layer, "d1", "/d1/data").await;
+    enforce(&mut layer, "d2", "/d2/data").await;
+    // d1 becomes the most recently used domain.
+    enforce(&mut layer, "d1", "/d1/data").await;
+    enforce(&mut layer, "d3", "/d3/data").await;
+
+    assert_eq!(lazy.loaded_domains().await, vec!["d1", "d3"]);
+    let enforcer = layer.get_enforcer();
+    {
+        let enforcer = enforcer.read().await;
+        assert!(enforcer
+            .get_filtered_policy(1, vec!["d2".to_string()])
+            .is_empty());
+        assert!(enforcer
+            .get_filtered_grouping_policy(2, vec!["d2".to_string()])
+            .is_empty());
+        assert!(enforcer.has_grouping_policy(vec![
+            "alice".to_string(),
+            "admin".to_string(),
+            "d1".to_string(),
+        ]));
+    }
+
+    // The evicted domain is loaded again on its next request.
+    assert!(enforce(&mut layer, "d2", "/d2/data").await);
+    assert_eq!(loader.loads.load(Ordering::SeqCst), 4);
+    assert_eq!(lazy.loaded_domains().await, vec!["d3", "d2"]);
+
+    lazy.invalidate(&enforcer, "d3").await.unwrap();
+    assert!(!lazy.is_loaded("d3").await);
+    assert!(enforce(&mut layer, "d3", "/d3/data").await);
+    assert_eq!(loader.loads.load(Ordering::SeqCst), 5);
+}
+
+#[tokio::test]
+async fn test_pinned_domains_are_not_evicted() {
+    let (mut layer, _) = new_layer(1).await;
+    let lazy = layer.get_lazy_domains().unwrap();
+    let enforcer = layer.get_enforcer();
+
+    // Pinning more domains than fit keeps all of them until the guard is dropped.
+    let guard = layer.pin_domains(&["d1", "d2"]).await.unwrap();
+    {
+        let enforcer = enforcer.read().await;
+        for domain in ["d1", "d2"] {
+            assert!(!enforcer
+                .get_filtered_policy(1, vec![domain.to_string()])
+                .is_empty());
+        }
+    }
+    drop(guard);
+    assert_eq!(lazy.loaded_domains().await, vec!["d1", "d2"]);
+
+    assert!(enforce(&mut layer, "d3", "/d3/data").await);
+    assert_eq!(lazy.loaded_domains().await, vec!["d3"]);
+}
+
+#[tokio::test]
+async fn test_reload_only_reads_loaded_domains() {
+    let (mut layer, loader) = new_layer(8).await;
+    let lazy = layer.get_lazy_domains().unwrap();
+    let enforcer = layer.get_enforcer();
+
+    enforce(&mut layer, "d1", "/d1/data").await;
+    enforce(&mut layer, "d2", "/d2/data").await;
+    enforcer.write().await.get_mut_model().add_policy(
+        "p",
+        "p",
+        vec![
+            "admin".to_string(),
+            "d9".to_string(),
+            "/d9/data".to_string(),
+            "GET".to_string(),
+        ],
+    );
+
+    lazy.reload(&enforcer).await.unwrap();
+
+    assert_eq!(loader.loads.load(Ordering::SeqCst), 4);
+    assert_eq!(lazy.loaded_domains().await, vec!["d1", "d2"]);
+    let enforcer = enforcer.read().await;
+    assert_eq!(enforcer.get_all_policy().len(), 2);
+    assert!(enforcer
+        .get_filtered_policy(1, vec!["d9".to_string()])
+        .is_empty());
+    assert!(enforcer.has_grouping_policy(vec![
+        "alice".to_string(),
+        "admin".to_string(),
+        "d2".to_string(),
+    ]));
+}
@@ -58,6 +58,7 @@ where
             let vals = CasbinVals {
                 subject: vec!["alice".to_string()],
                 domain: Option::from(String::from("domain1")),
+                ..Default::default()
             };
             req.extensions_mut().insert(vals);
             inner.call(req).await
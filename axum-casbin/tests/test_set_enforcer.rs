@@ -63,6 +63,7 @@ where
             let vals = CasbinVals {
                 subject: vec!["alice".to_string()],
                 domain: None,
+                ..Default::default()
             };
             req.extensions_mut().insert(vals);
             inner.call(req).await
@@ -95,7 +96,7 @@ async fn test_set_enforcer() {
     let app = Router::new()
         .route("/pen/1", get(handler))
         .route("/pen/2", get(handler))
-        .route("/book/:id", get(handler))
+        .route("/book/{id}", get(handler))
         .layer(casbin_middleware)
         .layer(FakeAuthLayer);
 
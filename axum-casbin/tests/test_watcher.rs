@@ -0,0 +1,108 @@
+#![cfg(feature = "runtime-tokio")]
+
+use std::{sync::Arc, time::Duration};
+
+use axum_casbin::{watch_policy_changes, MemoryPolicyChannel, PolicyChannel};
+use casbin::{CachedEnforcer, CoreApi, DefaultModel, MemoryAdapter, MgmtApi};
+use tokio::sync::RwLock;
+
+async fn new_enforcer() -> Arc<RwLock<CachedEnforcer>> {
+    let model = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+        .await
+        .unwrap();
+    let enforcer = CachedEnforcer::new(model, MemoryAdapter::default())
+        .await
+        .unwrap();
+    Arc::new(RwLock::new(enforcer))
+}
+
+async fn enforce(enforcer: &Arc<RwLock<CachedEnforcer>>, rvals: [&str; 4]) -> bool {
+    enforcer.read().await.enforce(rvals.to_vec()).unwrap()
+}
+
+async fn wait_for(enforcer: &Arc<RwLock<CachedEnforcer>>, rvals: [&str; 4], expected: bool) {
+    for _ in 0..100 {
+        if enforce(enforcer, rvals).await == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("{:?} was not {} in time", rvals, expected);
+}
+
+#[tokio::test]
+async fn test_policy_changes_propagate() {
+    let channel: Arc<dyn PolicyChannel> = Arc::new(MemoryPolicyChannel::new());
+    let node_a = new_enforcer().await;
+    let node_b = new_enforcer().await;
+    watch_policy_changes(node_a.clone(), channel.clone(), None)
+        .await
+        .unwrap();
+    watch_policy_changes(node_b.clone(), channel.clone(), None)
+        .await
+        .unwrap();
+
+    let request = ["alice", "domain1", "data1", "read"];
+    // The denial is now cached on node B.
+    assert!(!enforce(&node_b, request).await);
+
+    node_a
+        .write()
+        .await
+        .add_policy(
+            ["admin", "domain1", "data1", "read"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .await
+        .unwrap();
+    node_a
+        .write()
+        .await
+        .add_grouping_policy(["alice", "admin", "domain1"].map(String::from).to_vec())
+        .await
+        .unwrap();
+    wait_for(&node_b, request, true).await;
+
+    node_a
+        .write()
+        .await
+        .remove_grouping_policy(["alice", "admin", "domain1"].map(String::from).to_vec())
+        .await
+        .unwrap();
+    wait_for(&node_b, request, false).await;
+
+    // Node A must not apply its own changes twice.
+    assert_eq!(node_a.read().await.get_all_policy().len(), 1);
+    assert_eq!(node_b.read().await.get_all_policy().len(), 1);
+}
+
+#[tokio::test]
+async fn test_clear_policy_triggers_reload() {
+    let channel: Arc<dyn PolicyChannel> = Arc::new(MemoryPolicyChannel::new());
+    let node_a = new_enforcer().await;
+    let node_b = new_enforcer().await;
+    watch_policy_changes(node_a.clone(), channel.clone(), None)
+        .await
+        .unwrap();
+    watch_policy_changes(node_b.clone(), channel.clone(), None)
+        .await
+        .unwrap();
+
+    let request = ["bob", "domain2", "data2", "write"];
+    node_a
+        .write()
+        .await
+        .add_policy(
+            ["bob", "domain2", "data2", "write"]
+                .map(String::from)
+                .to_vec(),
+        )
+        .await
+        .unwrap();
+    wait_for(&node_b, request, true).await;
+
+    // Node B reloads from its own adapter, which holds no policies.
+    node_a.write().await.clear_policy().await.unwrap();
+    wait_for(&node_b, request, false).await;
+}
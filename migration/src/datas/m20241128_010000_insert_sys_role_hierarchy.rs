@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, sea_orm::Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 角色的 pid 表示继承的上级角色，内置的管理员和普通用户不应继承超级管理员的权限
+        let detach_built_in_roles_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+            UPDATE sys_role SET pid = '0'
+            WHERE id IN ('2', '3') AND pid = '1';
+        "#
+            .to_string(),
+        );
+
+        db.execute(detach_built_in_roles_stmt).await?;
+
+        let insert_casbin_rules_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+            INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5)
+            VALUES
+            ('p', 'ROLE_SUPER', 'built-in', '/role/:id/effective-permissions', 'GET', '', '')
+        "#
+            .to_string(),
+        );
+
+        db.execute(insert_casbin_rules_stmt).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let delete_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+        DELETE FROM casbin_rule
+        WHERE ptype = 'p' AND v0 = 'ROLE_SUPER' AND v1 = 'built-in'
+          AND v2 = '/role/:id/effective-permissions';
+        "#
+            .to_string(),
+        );
+
+        db.execute(delete_stmt).await?;
+
+        let restore_built_in_roles_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+        UPDATE sys_role SET pid = '1'
+        WHERE id IN ('2', '3') AND pid = '0';
+        "#
+            .to_string(),
+        );
+
+        db.execute(restore_built_in_roles_stmt).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::{prelude::*, sea_orm::Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let insert_casbin_rules_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+            INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5)
+            VALUES
+            ('p', 'ROLE_SUPER', 'built-in', '/authorization/export', 'GET', '', ''),
+            ('p', 'ROLE_SUPER', 'built-in', '/authorization/import', 'POST', '', '')
+        "#
+            .to_string(),
+        );
+
+        db.execute(insert_casbin_rules_stmt).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let delete_stmt = Statement::from_string(
+            manager.get_database_backend(),
+            r#"
+        DELETE FROM casbin_rule
+        WHERE ptype = 'p' AND v0 = 'ROLE_SUPER' AND v1 = 'built-in'
+          AND v2 IN ('/authorization/export', '/authorization/import');
+        "#
+            .to_string(),
+        );
+
+        db.execute(delete_stmt).await?;
+        Ok(())
+    }
+}
@@ -5,3 +5,12 @@ pub mod m20241024_034305_insert_sys_role_menu;
 pub mod m20241024_034526_insert_sys_role;
 pub mod m20241024_034744_insert_sys_menu;
 pub mod m20241024_082926_insert_casbin_rule;
+pub mod m20241126_010500_insert_sys_organization_casbin_rule;
+pub mod m20241128_010000_insert_sys_role_hierarchy;
+pub mod m20241129_010000_insert_sys_authorization_explain_casbin_rule;
+pub mod m20241130_010000_insert_sys_authorization_transfer_casbin_rule;
+pub mod m20241201_010500_insert_sys_field_mask_casbin_rule;
+pub mod m20241202_010000_insert_sys_authorization_policies_casbin_rule;
+pub mod m20241203_010000_insert_sys_operation_log_metrics_casbin_rule;
+pub mod m20241204_030000_insert_sys_audit_chain_casbin_rule;
+pub mod m20241205_010000_insert_sys_log_export_casbin_rule;
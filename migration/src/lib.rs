@@ -22,6 +22,16 @@ impl MigratorTrait for Migrator {
             Box::new(schemas::m20241023_091204_create_sys_tokens::Migration),
             Box::new(schemas::m20241023_091210_create_sys_user_role::Migration),
             Box::new(schemas::m20241023_091159_create_sys_role_menu::Migration),
+            Box::new(schemas::m20241120_021530_alter_sys_tokens_add_family_id::Migration),
+            Box::new(schemas::m20241122_031200_create_sys_user_totp::Migration),
+            Box::new(schemas::m20241123_081500_add_sys_user_password_policy::Migration),
+            Box::new(schemas::m20241125_020000_create_sys_user_identity::Migration),
+            Box::new(schemas::m20241126_010000_create_sys_user_organization::Migration),
+            Box::new(schemas::m20241127_010000_add_sys_role_data_scope::Migration),
+            Box::new(schemas::m20241201_010000_create_sys_field_mask::Migration),
+            Box::new(schemas::m20241202_020000_add_sys_operation_log_headers::Migration),
+            Box::new(schemas::m20241204_010000_add_audit_log_hash_chain::Migration),
+            Box::new(schemas::m20241204_020000_create_sys_audit_checkpoint::Migration),
             // 数据迁移
             Box::new(datas::m20241023_102950_insert_sys_domain::Migration),
             Box::new(datas::m20241024_033005_insert_sys_user::Migration),
@@ -30,6 +40,23 @@ impl MigratorTrait for Migrator {
             Box::new(datas::m20241024_033933_insert_sys_user_role::Migration),
             Box::new(datas::m20241024_034305_insert_sys_role_menu::Migration),
             Box::new(datas::m20241024_082926_insert_casbin_rule::Migration),
+            Box::new(datas::m20241126_010500_insert_sys_organization_casbin_rule::Migration),
+            Box::new(datas::m20241128_010000_insert_sys_role_hierarchy::Migration),
+            Box::new(
+                datas::m20241129_010000_insert_sys_authorization_explain_casbin_rule::Migration,
+            ),
+            Box::new(
+                datas::m20241130_010000_insert_sys_authorization_transfer_casbin_rule::Migration,
+            ),
+            Box::new(datas::m20241201_010500_insert_sys_field_mask_casbin_rule::Migration),
+            Box::new(
+                datas::m20241202_010000_insert_sys_authorization_policies_casbin_rule::Migration,
+            ),
+            Box::new(
+                datas::m20241203_010000_insert_sys_operation_log_metrics_casbin_rule::Migration,
+            ),
+            Box::new(datas::m20241204_030000_insert_sys_audit_chain_casbin_rule::Migration),
+            Box::new(datas::m20241205_010000_insert_sys_log_export_casbin_rule::Migration),
         ]
     }
 }
@@ -0,0 +1,121 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserTotp::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserTotp::UserId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SysUserTotp::Secret).string().not_null())
+                    .col(
+                        ColumnDef::new(SysUserTotp::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserTotp::LastUsedStep)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserTotp::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(SysUserTotp::UpdatedAt).timestamp().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserRecoveryCode::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserRecoveryCode::UserId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserRecoveryCode::CodeHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserRecoveryCode::UsedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserRecoveryCode::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_user_recovery_code_user_id")
+                    .table(SysUserRecoveryCode::Table)
+                    .col(SysUserRecoveryCode::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserRecoveryCode::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SysUserTotp::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserTotp {
+    Table,
+    UserId,
+    Secret,
+    Enabled,
+    LastUsedStep,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SysUserRecoveryCode {
+    Table,
+    Id,
+    UserId,
+    CodeHash,
+    UsedAt,
+    CreatedAt,
+}
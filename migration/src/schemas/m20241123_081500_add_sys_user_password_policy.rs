@@ -0,0 +1,118 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysUser::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysUser::PasswordChangedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysUser::MustChangePassword)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 已有用户以创建时间作为密码修改时间
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(SysUser::Table)
+                    .value(SysUser::PasswordChangedAt, Expr::col(SysUser::CreatedAt))
+                    .and_where(Expr::col(SysUser::PasswordChangedAt).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserPasswordHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserPasswordHistory::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserPasswordHistory::UserId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserPasswordHistory::PasswordHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserPasswordHistory::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_user_password_history_user_id")
+                    .table(SysUserPasswordHistory::Table)
+                    .col(SysUserPasswordHistory::UserId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SysUserPasswordHistory::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysUser::Table)
+                    .drop_column(SysUser::MustChangePassword)
+                    .drop_column(SysUser::PasswordChangedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUser {
+    Table,
+    CreatedAt,
+    PasswordChangedAt,
+    MustChangePassword,
+}
+
+#[derive(DeriveIden)]
+enum SysUserPasswordHistory {
+    Table,
+    Id,
+    UserId,
+    PasswordHash,
+    CreatedAt,
+}
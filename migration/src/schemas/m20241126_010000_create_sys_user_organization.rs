@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 每个用户最多归属一个组织，以 user_id 为主键
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysUserOrganization::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysUserOrganization::UserId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserOrganization::OrgId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysUserOrganization::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_user_organization_org_id")
+                    .table(SysUserOrganization::Table)
+                    .col(SysUserOrganization::OrgId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_sys_user_organization_user_id")
+                    .from(SysUserOrganization::Table, SysUserOrganization::UserId)
+                    .to(Alias::new("sys_user"), Alias::new("id"))
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_sys_user_organization_org_id")
+                    .from(SysUserOrganization::Table, SysUserOrganization::OrgId)
+                    .to(Alias::new("sys_organization"), Alias::new("id"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysUserOrganization::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysUserOrganization {
+    Table,
+    UserId,
+    OrgId,
+    CreatedAt,
+}
@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 已有角色默认可访问全部数据，保持原有行为
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysRole::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysRole::DataScope)
+                            .string()
+                            .not_null()
+                            .default("ALL"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 自定义数据范围的组织列表
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysRoleDataScope::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SysRoleDataScope::RoleId).string().not_null())
+                    .col(ColumnDef::new(SysRoleDataScope::OrgId).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(SysRoleDataScope::RoleId)
+                            .col(SysRoleDataScope::OrgId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_sys_role_data_scope_role_id")
+                    .from(SysRoleDataScope::Table, SysRoleDataScope::RoleId)
+                    .to(SysRole::Table, SysRole::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_sys_role_data_scope_org_id")
+                    .from(SysRoleDataScope::Table, SysRoleDataScope::OrgId)
+                    .to(Alias::new("sys_organization"), Alias::new("id"))
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysRoleDataScope::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysRole::Table)
+                    .drop_column(SysRole::DataScope)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysRole {
+    Table,
+    Id,
+    DataScope,
+}
+
+#[derive(DeriveIden)]
+enum SysRoleDataScope {
+    Table,
+    RoleId,
+    OrgId,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysFieldMask::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysFieldMask::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SysFieldMask::Domain).string().not_null())
+                    .col(ColumnDef::new(SysFieldMask::RoleCode).string().not_null())
+                    .col(
+                        ColumnDef::new(SysFieldMask::Resource)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(ColumnDef::new(SysFieldMask::Field).string().not_null())
+                    .col(ColumnDef::new(SysFieldMask::Strategy).string().not_null())
+                    .col(ColumnDef::new(SysFieldMask::Description).string().null())
+                    .col(
+                        ColumnDef::new(SysFieldMask::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(SysFieldMask::CreatedBy).string().not_null())
+                    .col(ColumnDef::new(SysFieldMask::UpdatedAt).timestamp().null())
+                    .col(ColumnDef::new(SysFieldMask::UpdatedBy).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_field_mask_rule")
+                    .table(SysFieldMask::Table)
+                    .col(SysFieldMask::Domain)
+                    .col(SysFieldMask::RoleCode)
+                    .col(SysFieldMask::Resource)
+                    .col(SysFieldMask::Field)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysFieldMask::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysFieldMask {
+    Table,
+    Id,
+    Domain,
+    RoleCode,
+    Resource,
+    Field,
+    Strategy,
+    Description,
+    CreatedAt,
+    CreatedBy,
+    UpdatedAt,
+    UpdatedBy,
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 按配置的白名单记录的请求头
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperationLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperationLog::Headers)
+                            .json_binary()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperationLog::Table)
+                    .drop_column(SysOperationLog::Headers)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysOperationLog {
+    Table,
+    Headers,
+}
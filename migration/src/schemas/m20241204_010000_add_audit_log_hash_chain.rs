@@ -0,0 +1,126 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 按域组成的哈希链，未启用时为空
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperationLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperationLog::ChainSeq)
+                            .big_integer()
+                            .null(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysOperationLog::PrevHash).string().null(),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(SysOperationLog::Hash).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_operation_log_chain")
+                    .table(SysOperationLog::Table)
+                    .col(SysOperationLog::Domain)
+                    .col(SysOperationLog::ChainSeq)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysLoginLog::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(SysLoginLog::ChainSeq).big_integer().null(),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(SysLoginLog::PrevHash).string().null())
+                    .add_column_if_not_exists(ColumnDef::new(SysLoginLog::Hash).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_login_log_chain")
+                    .table(SysLoginLog::Table)
+                    .col(SysLoginLog::Domain)
+                    .col(SysLoginLog::ChainSeq)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_sys_login_log_chain")
+                    .table(SysLoginLog::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysLoginLog::Table)
+                    .drop_column(SysLoginLog::ChainSeq)
+                    .drop_column(SysLoginLog::PrevHash)
+                    .drop_column(SysLoginLog::Hash)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_sys_operation_log_chain")
+                    .table(SysOperationLog::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SysOperationLog::Table)
+                    .drop_column(SysOperationLog::ChainSeq)
+                    .drop_column(SysOperationLog::PrevHash)
+                    .drop_column(SysOperationLog::Hash)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysOperationLog {
+    Table,
+    Domain,
+    ChainSeq,
+    PrevHash,
+    Hash,
+}
+
+#[derive(DeriveIden)]
+enum SysLoginLog {
+    Table,
+    Domain,
+    ChainSeq,
+    PrevHash,
+    Hash,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SysAuditCheckpoint::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::LogType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::Domain)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::ChainSeq)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SysAuditCheckpoint::Hash).string().not_null())
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::PublicKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::Signature)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SysAuditCheckpoint::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sys_audit_checkpoint_chain")
+                    .table(SysAuditCheckpoint::Table)
+                    .col(SysAuditCheckpoint::LogType)
+                    .col(SysAuditCheckpoint::Domain)
+                    .col(SysAuditCheckpoint::ChainSeq)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SysAuditCheckpoint::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SysAuditCheckpoint {
+    Table,
+    Id,
+    LogType,
+    Domain,
+    ChainSeq,
+    Hash,
+    PublicKey,
+    Signature,
+    CreatedAt,
+}
@@ -1,3 +1,5 @@
+// 已发布的迁移不再修改，其中的枚举名保持原样
+#[allow(clippy::upper_case_acronyms)]
 pub mod m20240815_082808_create_enum_status;
 pub mod m20240815_082854_create_sys_user;
 pub mod m20241023_090604_create_sys_role;
@@ -11,3 +13,13 @@ pub mod m20241023_091155_create_sys_organization;
 pub mod m20241023_091159_create_sys_role_menu;
 pub mod m20241023_091204_create_sys_tokens;
 pub mod m20241023_091210_create_sys_user_role;
+pub mod m20241120_021530_alter_sys_tokens_add_family_id;
+pub mod m20241122_031200_create_sys_user_totp;
+pub mod m20241123_081500_add_sys_user_password_policy;
+pub mod m20241125_020000_create_sys_user_identity;
+pub mod m20241126_010000_create_sys_user_organization;
+pub mod m20241127_010000_add_sys_role_data_scope;
+pub mod m20241201_010000_create_sys_field_mask;
+pub mod m20241202_020000_add_sys_operation_log_headers;
+pub mod m20241204_010000_add_audit_log_hash_chain;
+pub mod m20241204_020000_create_sys_audit_checkpoint;
@@ -31,6 +31,45 @@ impl<'a> Rule<'a> {
     }
 }
 
+/// Owned copy of a [`Filter`], kept by the adapter after a filtered load.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OwnedFilter {
+    pub(crate) p: Vec<String>,
+    pub(crate) g: Vec<String>,
+}
+
+impl OwnedFilter {
+    pub(crate) fn as_filter(&self) -> Filter<'_> {
+        Filter {
+            p: self.p.iter().map(String::as_str).collect(),
+            g: self.g.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// Whether a policy line belongs to the filtered scope.
+    pub(crate) fn matches<S: AsRef<str>>(&self, ptype: &str, rule: &[S]) -> bool {
+        let values = if ptype.starts_with('g') {
+            &self.g
+        } else {
+            &self.p
+        };
+        values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| !value.is_empty())
+            .all(|(i, value)| rule.get(i).map(AsRef::as_ref) == Some(value.as_str()))
+    }
+}
+
+impl From<&Filter<'_>> for OwnedFilter {
+    fn from(filter: &Filter<'_>) -> Self {
+        OwnedFilter {
+            p: filter.p.iter().map(|v| v.to_string()).collect(),
+            g: filter.g.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct RuleWithType<'a> {
     pub(crate) ptype: &'a str,
@@ -68,8 +107,8 @@ pub(crate) async fn remove_policies<'conn, 'rule, C: ConnectionTrait>(
     Ok(true)
 }
 
-pub(crate) async fn remove_filtered_policy<'conn, 'rule, C: ConnectionTrait>(
-    conn: &'conn C,
+pub(crate) async fn remove_filtered_policy<'rule, C: ConnectionTrait>(
+    conn: &C,
     ptype: &'rule str,
     index_of_match_start: usize,
     rule: Rule<'rule>,
@@ -102,17 +141,21 @@ pub(crate) async fn load_filtered_policy<'conn, 'filter, C: ConnectionTrait>(
     conn: &'conn C,
     filter: Filter<'filter>,
 ) -> Result<Vec<entity::Model>> {
+    Entity::find()
+        .filter(create_condition_from_filter(&filter))
+        .all(conn)
+        .await
+        .map_err(|err| CasbinError::from(AdapterError(Box::new(err))))
+}
+
+fn create_condition_from_filter(filter: &Filter) -> Condition {
     let g_filter = Rule::from_slice(&filter.g);
     let p_filter = Rule::from_slice(&filter.p);
 
     let g_condition = create_condition_from_rule("g", &g_filter);
     let p_condition = create_condition_from_rule("p", &p_filter);
 
-    Entity::find()
-        .filter(Condition::any().add(g_condition).add(p_condition))
-        .all(conn)
-        .await
-        .map_err(|err| CasbinError::from(AdapterError(Box::new(err))))
+    Condition::any().add(g_condition).add(p_condition)
 }
 
 fn create_condition_from_rule(prefix: &str, rule: &Rule) -> Condition {
@@ -135,6 +178,23 @@ pub(crate) async fn save_policies<'conn, 'rule, C: ConnectionTrait>(
     Ok(())
 }
 
+/// Replaces only the rows matching `filter`, leaving the rest of the table untouched.
+pub(crate) async fn save_filtered_policies<'conn, 'rule, C: ConnectionTrait>(
+    conn: &'conn C,
+    filter: Filter<'_>,
+    rules: Vec<RuleWithType<'rule>>,
+) -> Result<()> {
+    Entity::delete_many()
+        .filter(create_condition_from_filter(&filter))
+        .exec(conn)
+        .await
+        .map_err(|err| CasbinError::from(AdapterError(Box::new(err))))?;
+    if !rules.is_empty() {
+        add_policies(conn, rules).await?;
+    }
+    Ok(())
+}
+
 pub(crate) async fn add_policy<'conn, 'rule, C: ConnectionTrait>(
     conn: &'conn C,
     rule: RuleWithType<'rule>,
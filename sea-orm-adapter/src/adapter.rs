@@ -3,13 +3,15 @@ use casbin::{error::AdapterError, Adapter, Error as CasbinError, Filter, Model,
 use sea_orm::ConnectionTrait;
 
 use crate::{
-    action::{self, Rule, RuleWithType},
+    action::{self, OwnedFilter, Rule, RuleWithType},
     entity, migration,
 };
 
 pub struct SeaOrmAdapter<C> {
     conn: C,
     is_filtered: bool,
+    /// Scope of the last filtered load; a filtered adapter only saves within it.
+    filter: Option<OwnedFilter>,
 }
 
 impl<C: ConnectionTrait> SeaOrmAdapter<C> {
@@ -19,9 +21,20 @@ impl<C: ConnectionTrait> SeaOrmAdapter<C> {
             .map(|_| Self {
                 conn,
                 is_filtered: false,
+                filter: None,
             })
             .map_err(|err| CasbinError::from(AdapterError(Box::new(err))))
     }
+
+    /// Reads the policy lines matching `f` as `(ptype, rule)` pairs without loading
+    /// them into a model, e.g. to merge one more domain into a filtered enforcer.
+    pub async fn find_filtered_policy(&self, f: Filter<'_>) -> Result<Vec<(String, Vec<String>)>> {
+        let rules = action::load_filtered_policy(&self.conn, f).await?;
+        Ok(rules
+            .iter()
+            .filter_map(|rule| Self::normalize_policy(rule).map(|p| (rule.ptype.clone(), p)))
+            .collect())
+    }
 }
 
 impl<C> SeaOrmAdapter<C> {
@@ -54,12 +67,19 @@ impl<C> SeaOrmAdapter<C> {
 impl<C: ConnectionTrait + Send + Sync> Adapter for SeaOrmAdapter<C> {
     async fn load_policy(&mut self, m: &mut dyn Model) -> Result<()> {
         let rules = action::load_policy(&self.conn).await?;
+        self.is_filtered = false;
+        self.filter = None;
 
         for rule in &rules {
             if let Some(sec) = rule.ptype.chars().next().map(|x| x.to_string()) {
                 if let Some(t1) = m.get_mut_model().get_mut(&sec) {
                     if let Some(t2) = t1.get_mut(&rule.ptype) {
-                        if let Some(policy) = Self::normalize_policy(rule) {
+                        if let Some(mut policy) = Self::normalize_policy(rule) {
+                            // Trailing empty fields are not stored, e.g. an optional
+                            // condition, restore them so the rule matches the definition.
+                            if policy.len() < t2.tokens.len() {
+                                policy.resize(t2.tokens.len(), String::new());
+                            }
                             t2.get_mut_policy().insert(policy);
                         }
                     }
@@ -71,14 +91,21 @@ impl<C: ConnectionTrait + Send + Sync> Adapter for SeaOrmAdapter<C> {
     }
 
     async fn load_filtered_policy<'a>(&mut self, m: &mut dyn Model, f: Filter<'a>) -> Result<()> {
+        let filter = OwnedFilter::from(&f);
         let rules = action::load_filtered_policy(&self.conn, f).await?;
         self.is_filtered = true;
+        self.filter = Some(filter);
 
         for rule in &rules {
             if let Some(sec) = rule.ptype.chars().next().map(|x| x.to_string()) {
                 if let Some(t1) = m.get_mut_model().get_mut(&sec) {
                     if let Some(t2) = t1.get_mut(&rule.ptype) {
-                        if let Some(policy) = Self::normalize_policy(rule) {
+                        if let Some(mut policy) = Self::normalize_policy(rule) {
+                            // Trailing empty fields are not stored, e.g. an optional
+                            // condition, restore them so the rule matches the definition.
+                            if policy.len() < t2.tokens.len() {
+                                policy.resize(t2.tokens.len(), String::new());
+                            }
                             t2.get_mut_policy().insert(policy);
                         }
                     }
@@ -108,7 +135,17 @@ impl<C: ConnectionTrait + Send + Sync> Adapter for SeaOrmAdapter<C> {
         process_policy_type("p");
         process_policy_type("g");
 
-        action::save_policies(&self.conn, rules).await
+        match self.filter.as_ref().filter(|_| self.is_filtered) {
+            Some(filter) => {
+                // The model only holds the filtered scope, rows outside of it are kept.
+                let rules = rules
+                    .into_iter()
+                    .filter(|rule| filter.matches(rule.ptype, &rule.rule.values))
+                    .collect();
+                action::save_filtered_policies(&self.conn, filter.as_filter(), rules).await
+            },
+            None => action::save_policies(&self.conn, rules).await,
+        }
     }
 
     async fn clear_policy(&mut self) -> Result<()> {
@@ -402,7 +439,7 @@ mod tests {
             .remove_filtered_policy("", "g", 0, to_owned(vec!["carol"]),)
             .await
             .unwrap());
-        assert_eq!(vec![String::new(); 0], e.get_roles_for_user("carol", None));
+        assert_eq!(Vec::<String>::new(), e.get_roles_for_user("carol", None));
 
         // GitHub issue: https://github.com/casbin-rs/sqlx-adapter/pull/90
         // add policies:
@@ -479,4 +516,137 @@ mod tests {
         assert!(!e.enforce(("bob", "domain2", "data2", "read")).unwrap());
         assert!(!e.enforce(("bob", "domain2", "data2", "write")).unwrap());
     }
+
+    const DOMAINS: usize = 200;
+
+    fn domain_filter(domain: &str) -> casbin::Filter<'_> {
+        casbin::Filter {
+            p: vec!["", domain],
+            g: vec!["", "", domain],
+        }
+    }
+
+    /// Every domain gets its own admin role, member and policies, all sharing the
+    /// same subject and object names so that a leak is visible in the results.
+    async fn multi_domain_adapter() -> SeaOrmAdapter<sea_orm::DatabaseConnection> {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let mut adapter = SeaOrmAdapter::new(db).await.unwrap();
+
+        let mut policies = Vec::new();
+        let mut groupings = Vec::new();
+        for i in 0..DOMAINS {
+            let domain = format!("domain{}", i);
+            policies.push(to_owned(vec!["admin", &domain, "/data", "GET"]));
+            policies.push(to_owned(vec![
+                "admin",
+                &domain,
+                &format!("/data/{}", i),
+                "POST",
+            ]));
+            groupings.push(to_owned(vec!["alice", "admin", &domain]));
+            groupings.push(to_owned(vec![&format!("user{}", i), "admin", &domain]));
+        }
+        adapter.add_policies("p", "p", policies).await.unwrap();
+        adapter.add_policies("g", "g", groupings).await.unwrap();
+        adapter
+    }
+
+    fn assert_only_domain(rules: &[Vec<String>], index: usize, domain: &str) {
+        assert!(
+            rules.iter().all(|rule| rule[index] == domain),
+            "{:?}",
+            rules
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filtered_load_does_not_leak_domains() {
+        use casbin::prelude::*;
+
+        let mut adapter = multi_domain_adapter().await;
+        let mut m = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+            .await
+            .unwrap();
+
+        adapter
+            .load_filtered_policy(&mut m, domain_filter("domain42"))
+            .await
+            .unwrap();
+        assert!(adapter.is_filtered());
+
+        let p = m.get_policy("p", "p");
+        let g = m.get_policy("g", "g");
+        assert_eq!(p.len(), 2);
+        assert_eq!(g.len(), 2);
+        assert_only_domain(&p, 1, "domain42");
+        assert_only_domain(&g, 2, "domain42");
+        assert!(g.contains(&to_owned(vec!["user42", "admin", "domain42"])));
+
+        let found = adapter
+            .find_filtered_policy(domain_filter("domain7"))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 4);
+        for (ptype, rule) in &found {
+            let index = if ptype == "g" { 2 } else { 1 };
+            assert_eq!(rule[index], "domain7");
+        }
+
+        let mut full = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+            .await
+            .unwrap();
+        adapter.load_policy(&mut full).await.unwrap();
+        assert!(!adapter.is_filtered());
+        assert_eq!(full.get_policy("p", "p").len(), DOMAINS * 2);
+        assert_eq!(full.get_policy("g", "g").len(), DOMAINS * 2);
+    }
+
+    #[tokio::test]
+    async fn test_filtered_save_keeps_other_domains() {
+        use casbin::prelude::*;
+
+        let mut adapter = multi_domain_adapter().await;
+        let mut m = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+            .await
+            .unwrap();
+        adapter
+            .load_filtered_policy(&mut m, domain_filter("domain3"))
+            .await
+            .unwrap();
+
+        m.add_policy(
+            "p",
+            "p",
+            to_owned(vec!["admin", "domain3", "/extra", "PUT"]),
+        );
+        m.remove_policy("g", "g", to_owned(vec!["user3", "admin", "domain3"]));
+        // Rules outside of the loaded scope are not written by a filtered save.
+        m.add_policy("p", "p", to_owned(vec!["admin", "domain4", "/leak", "PUT"]));
+        adapter.save_policy(&mut m).await.unwrap();
+
+        let domain3 = adapter
+            .find_filtered_policy(domain_filter("domain3"))
+            .await
+            .unwrap();
+        assert_eq!(domain3.len(), 4);
+        assert!(domain3.contains(&(
+            "p".to_owned(),
+            to_owned(vec!["admin", "domain3", "/extra", "PUT"])
+        )));
+        assert!(!domain3.contains(&("g".to_owned(), to_owned(vec!["user3", "admin", "domain3"]))));
+
+        let domain4 = adapter
+            .find_filtered_policy(domain_filter("domain4"))
+            .await
+            .unwrap();
+        assert_eq!(domain4.len(), 4);
+        assert!(!domain4.iter().any(|(_, rule)| rule[2] == "/leak"));
+
+        let mut full = DefaultModel::from_file("examples/rbac_with_domains_model.conf")
+            .await
+            .unwrap();
+        adapter.load_policy(&mut full).await.unwrap();
+        assert_eq!(full.get_policy("p", "p").len(), DOMAINS * 2 + 1);
+        assert_eq!(full.get_policy("g", "g").len(), DOMAINS * 2 - 1);
+    }
 }
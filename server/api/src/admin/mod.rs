@@ -1,9 +1,12 @@
 pub use sys_access_key_api::SysAccessKeyApi;
+pub use sys_audit_chain_api::SysAuditChainApi;
 pub use sys_authentication_api::SysAuthenticationApi;
 pub use sys_domain_api::SysDomainApi;
 pub use sys_endpoint_api::SysEndpointApi;
+pub use sys_field_mask_api::SysFieldMaskApi;
 pub use sys_login_log_api::SysLoginLogApi;
 pub use sys_menu_api::SysMenuApi;
+pub use sys_online_session_api::SysOnlineSessionApi;
 pub use sys_operation_log_api::SysOperationLogApi;
 pub use sys_organization_api::SysOrganizationApi;
 pub use sys_role_api::SysRoleApi;
@@ -11,11 +14,14 @@ pub use sys_sandbox_api::SysSandboxApi;
 pub use sys_user_api::SysUserApi;
 
 mod sys_access_key_api;
+mod sys_audit_chain_api;
 mod sys_authentication_api;
 mod sys_domain_api;
 mod sys_endpoint_api;
+mod sys_field_mask_api;
 mod sys_login_log_api;
 mod sys_menu_api;
+mod sys_online_session_api;
 mod sys_operation_log_api;
 mod sys_organization_api;
 mod sys_role_api;
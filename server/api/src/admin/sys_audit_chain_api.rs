@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query};
+use server_core::web::{error::AppError, res::Res};
+use server_service::admin::{
+    AuditChainVerification, SysAuditChainService, TAuditChainService, VerifyAuditChainInput,
+};
+
+pub struct SysAuditChainApi;
+
+impl SysAuditChainApi {
+    pub async fn verify_audit_chain(
+        Query(input): Query<VerifyAuditChainInput>,
+        Extension(service): Extension<Arc<SysAuditChainService>>,
+    ) -> Result<Res<AuditChainVerification>, AppError> {
+        service.verify_chain(input).await.map(Res::new_data)
+    }
+}
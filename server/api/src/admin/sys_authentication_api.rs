@@ -1,22 +1,59 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
 
-use axum::{extract::ConnectInfo, http::HeaderMap, Extension};
-use axum_casbin::CasbinAxumLayer;
-use axum_extra::{headers::UserAgent, TypedHeader};
+use axum::{
+    extract::{ConnectInfo, Path, Query},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_casbin::{CasbinAxumLayer, DomainGuard};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization, UserAgent},
+    TypedHeader,
+};
 use server_core::web::{
-    auth::User, error::AppError, res::Res, util::ClientIp, validator::ValidatedForm, RequestId,
+    auth::User, error::AppError, jwt::JwtUtils, page::PaginatedData, res::Res, util::ClientIp,
+    validator::ValidatedForm, RequestId,
 };
 use server_service::{
     admin::{
-        dto::sys_auth_dto::LoginContext, AssignPermissionDto, AssignRouteDto, AuthOutput,
-        LoginInput, SysAuthService, SysAuthorizationService, TAuthService, TAuthorizationService,
-        UserInfoOutput, UserRoute,
+        check_domain_access, dto::sys_auth_dto::LoginContext, policy_csv_helper,
+        AssignPermissionDto, AssignRouteDto, AuthOutput, ExplainPermissionDto, ExportPolicyDto,
+        ImportPolicyDto, LoginInput, LoginOutput, OAuthAuthorizeOutput, OAuthCallbackInput,
+        PermissionExplanation, PolicyChangeOutput, PolicyFormat, PolicyImportDiff,
+        PolicyPageRequest, PolicyRule, PolicyRulesDto, ReachableEndpoint, ReachableEndpointsDto,
+        RefreshTokenInput, ReplacePoliciesDto, SysAuthService, SysAuthorizationService,
+        TAuthService, TAuthorizationService, TotpCodeInput, TotpEnrollOutput, TotpLoginInput,
+        TotpRecoveryCodesOutput, UserInfoOutput, UserRoute,
     },
     Audience,
 };
 
 pub struct SysAuthenticationApi;
 
+async fn build_login_context(
+    addr: SocketAddr,
+    headers: &HeaderMap,
+    user_agent: &UserAgent,
+    request_id: &RequestId,
+) -> LoginContext {
+    let client_ip = ClientIp::get_client_ip(addr.ip(), headers).await;
+
+    let address = xdb::searcher::search_by_ip(client_ip.as_str())
+        .unwrap_or_else(|_| "Unknown Location".to_string());
+
+    LoginContext {
+        client_ip,
+        client_port: Some(addr.port() as i32),
+        address,
+        user_agent: user_agent.as_str().to_string(),
+        request_id: request_id.to_string(),
+        audience: Audience::ManagementPlatform,
+        login_type: "PC".to_string(),
+        domain: "built-in".to_string(),
+    }
+}
+
 impl SysAuthenticationApi {
     pub async fn login_handler(
         ConnectInfo(addr): ConnectInfo<SocketAddr>,
@@ -25,36 +62,114 @@ impl SysAuthenticationApi {
         Extension(request_id): Extension<RequestId>,
         Extension(service): Extension<Arc<SysAuthService>>,
         ValidatedForm(input): ValidatedForm<LoginInput>,
-    ) -> Result<Res<AuthOutput>, AppError> {
-        let client_ip = {
-            let header_ip = ClientIp::get_real_ip(&headers);
-            if header_ip == "unknown" {
-                addr.ip().to_string()
-            } else {
-                header_ip
-            }
+    ) -> Result<Res<LoginOutput>, AppError> {
+        let login_context = LoginContext {
+            domain: input.domain.clone(),
+            ..build_login_context(addr, &headers, &user_agent, &request_id).await
         };
 
-        let address = xdb::searcher::search_by_ip(client_ip.as_str())
-            .unwrap_or_else(|_| "Unknown Location".to_string());
+        service
+            .pwd_login_with_security(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
 
-        let login_context = LoginContext {
-            client_ip,
-            client_port: Some(addr.port() as i32),
-            address,
-            user_agent: user_agent.as_str().to_string(),
-            request_id: request_id.to_string(),
-            audience: Audience::ManagementPlatform,
-            login_type: "PC".to_string(),
-            domain: "built-in".to_string(),
-        };
+    pub async fn totp_login_handler(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<TotpLoginInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = build_login_context(addr, &headers, &user_agent, &request_id).await;
+
+        service
+            .totp_login(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn oauth_authorize(
+        Path(provider): Path<String>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+    ) -> Result<Res<OAuthAuthorizeOutput>, AppError> {
+        service.oauth_authorize(&provider).await.map(Res::new_data)
+    }
+
+    pub async fn oauth_callback(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        Path(provider): Path<String>,
+        ValidatedForm(input): ValidatedForm<OAuthCallbackInput>,
+    ) -> Result<Res<LoginOutput>, AppError> {
+        let login_context = build_login_context(addr, &headers, &user_agent, &request_id).await;
 
         service
-            .pwd_login(input, login_context)
+            .oauth_login(&provider, input, login_context)
             .await
             .map(Res::new_data)
     }
 
+    pub async fn refresh_token(
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        headers: HeaderMap,
+        TypedHeader(user_agent): TypedHeader<UserAgent>,
+        Extension(request_id): Extension<RequestId>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<RefreshTokenInput>,
+    ) -> Result<Res<AuthOutput>, AppError> {
+        let login_context = build_login_context(addr, &headers, &user_agent, &request_id).await;
+
+        service
+            .refresh_token(input, login_context)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn logout(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        TypedHeader(Authorization(bearer)): TypedHeader<Authorization<Bearer>>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .logout(&user, bearer.token())
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 公布 JWT 验签公钥，按 JWKS 标准格式直接返回，不包装为 `Res`
+    pub async fn jwks() -> Result<impl IntoResponse, AppError> {
+        let jwks = JwtUtils::jwks().await?;
+        Ok(Json(jwks))
+    }
+
+    pub async fn totp_enroll(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+    ) -> Result<Res<TotpEnrollOutput>, AppError> {
+        service.totp_enroll(&user).await.map(Res::new_data)
+    }
+
+    pub async fn totp_activate(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<TotpCodeInput>,
+    ) -> Result<Res<TotpRecoveryCodesOutput>, AppError> {
+        service.totp_activate(&user, input).await.map(Res::new_data)
+    }
+
+    pub async fn totp_disable(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysAuthService>>,
+        ValidatedForm(input): ValidatedForm<TotpCodeInput>,
+    ) -> Result<Res<()>, AppError> {
+        service.totp_disable(&user, input).await.map(Res::new_data)
+    }
+
     pub async fn get_user_info(
         Extension(user): Extension<User>,
     ) -> Result<Res<UserInfoOutput>, AppError> {
@@ -86,6 +201,7 @@ impl SysAuthenticationApi {
         Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
         ValidatedForm(input): ValidatedForm<AssignPermissionDto>,
     ) -> Result<Res<()>, AppError> {
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
         let enforcer = cache_enforcer.get_enforcer();
 
         service
@@ -108,4 +224,196 @@ impl SysAuthenticationApi {
 
         Ok(Res::new_data(()))
     }
+
+    /// 解释权限判定
+    ///
+    /// 返回角色或用户访问指定接口的判定结果、命中的策略及继承的角色，用于排查 403。
+    pub async fn explain_permission(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<ExplainPermissionDto>,
+    ) -> Result<Res<PermissionExplanation>, AppError> {
+        check_domain_access(&user, &input.domain)?;
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .explain_permission(input, enforcer)
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 获取可访问的接口
+    ///
+    /// 逐个判定 `sys_endpoint` 中的接口，返回角色或用户在域内可访问的部分。
+    pub async fn get_reachable_endpoints(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<ReachableEndpointsDto>,
+    ) -> Result<Res<Vec<ReachableEndpoint>>, AppError> {
+        check_domain_access(&user, &input.domain)?;
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .get_reachable_endpoints(input, enforcer)
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 导出授权数据
+    ///
+    /// JSON 格式返回域的完整授权数据包，CSV 格式以 Casbin 策略文件的形式返回 p 与 g 规则。
+    pub async fn export_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        Query(input): Query<ExportPolicyDto>,
+    ) -> Result<Response, AppError> {
+        check_domain_access(&user, &input.domain)?;
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        let bundle = service.export_policies(input.domain, enforcer).await?;
+        Ok(match input.format {
+            PolicyFormat::Json => Res::new_data(bundle).into_response(),
+            PolicyFormat::Csv => (
+                [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+                policy_csv_helper::to_csv(&bundle),
+            )
+                .into_response(),
+        })
+    }
+
+    /// 导入授权数据
+    ///
+    /// 试运行时只返回将要执行的变更，否则在事务中应用并返回实际的变更。
+    pub async fn import_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<ImportPolicyDto>,
+    ) -> Result<Res<PolicyImportDiff>, AppError> {
+        check_domain_access(&user, &input.domain)?;
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .import_policies(
+                input.domain,
+                input.format,
+                input.content,
+                input.dry_run,
+                &user,
+                enforcer,
+            )
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 分页查询策略规则
+    ///
+    /// 可按规则类型、角色、域、资源与操作筛选，内置域以外的用户只能查询所在域的规则。
+    pub async fn get_paginated_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(user): Extension<User>,
+        Query(params): Query<PolicyPageRequest>,
+    ) -> Result<Res<PaginatedData<PolicyRule>>, AppError> {
+        service
+            .find_paginated_policies(params, &user)
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 新增策略规则
+    ///
+    /// 规则引用的域、角色与接口须已存在，已存在的规则会被跳过。
+    pub async fn add_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<PolicyRulesDto>,
+    ) -> Result<Res<PolicyChangeOutput>, AppError> {
+        check_rule_domains(&user, &input)?;
+        let _pinned = pin_rule_domains(&cache_enforcer, &input).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .add_policies(input.rules, enforcer)
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 删除策略规则
+    ///
+    /// 不存在的规则会被跳过。
+    pub async fn remove_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<PolicyRulesDto>,
+    ) -> Result<Res<PolicyChangeOutput>, AppError> {
+        check_rule_domains(&user, &input)?;
+        let _pinned = pin_rule_domains(&cache_enforcer, &input).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .remove_policies(input.rules, enforcer)
+            .await
+            .map(Res::new_data)
+    }
+
+    /// 替换策略规则
+    ///
+    /// 将域内某类规则整体替换为给定的规则，指定角色时只替换该角色的规则。
+    pub async fn replace_policies(
+        Extension(service): Extension<Arc<SysAuthorizationService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<ReplacePoliciesDto>,
+    ) -> Result<Res<PolicyChangeOutput>, AppError> {
+        check_domain_access(&user, &input.domain)?;
+        let _pinned = pin_domains(&cache_enforcer, &[&input.domain]).await?;
+        let enforcer = cache_enforcer.get_enforcer();
+
+        service
+            .replace_policies(input, enforcer)
+            .await
+            .map(Res::new_data)
+    }
+}
+
+/// 校验用户能否管理规则涉及的每个域
+fn check_rule_domains(user: &User, input: &PolicyRulesDto) -> Result<(), AppError> {
+    input
+        .rules
+        .iter()
+        .try_for_each(|rule| check_domain_access(user, &rule.domain))
+}
+
+/// 加载规则涉及的全部域的策略，并在返回的守卫释放前保持加载
+async fn pin_rule_domains(
+    layer: &CasbinAxumLayer,
+    input: &PolicyRulesDto,
+) -> Result<Option<DomainGuard>, AppError> {
+    let domains: BTreeSet<&str> = input
+        .rules
+        .iter()
+        .map(|rule| rule.domain.as_str())
+        .collect();
+    pin_domains(layer, &domains.into_iter().collect::<Vec<_>>()).await
+}
+
+/// 策略按域按需加载时，加载目标域的策略，并在返回的守卫释放前保持加载，
+/// 避免处理请求期间被其他请求换出
+async fn pin_domains(
+    layer: &CasbinAxumLayer,
+    domains: &[&str],
+) -> Result<Option<DomainGuard>, AppError> {
+    layer.pin_domains(domains).await.map_err(|e| AppError {
+        code: 500,
+        message: e.to_string(),
+    })
 }
@@ -42,6 +42,7 @@ impl SysEndpointApi {
                 map.insert("v1".to_string(), values.next().unwrap_or_default());
                 map.insert("v2".to_string(), values.next().unwrap_or_default());
                 map.insert("v3".to_string(), values.next().unwrap_or_default());
+                map.insert("v4".to_string(), values.next().unwrap_or_default());
                 map
             })
             .collect();
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    Extension,
+};
+use server_core::web::{
+    auth::User, error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm,
+};
+use server_service::admin::{
+    CreateFieldMaskInput, FieldMaskPageRequest, SysFieldMaskModel, SysFieldMaskService,
+    TFieldMaskService, UpdateFieldMaskInput,
+};
+
+pub struct SysFieldMaskApi;
+
+impl SysFieldMaskApi {
+    pub async fn get_paginated_field_masks(
+        Query(params): Query<FieldMaskPageRequest>,
+        Extension(service): Extension<Arc<SysFieldMaskService>>,
+    ) -> Result<Res<PaginatedData<SysFieldMaskModel>>, AppError> {
+        service
+            .find_paginated_field_masks(params)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn create_field_mask(
+        Extension(service): Extension<Arc<SysFieldMaskService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<CreateFieldMaskInput>,
+    ) -> Result<Res<SysFieldMaskModel>, AppError> {
+        service
+            .create_field_mask(input, user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_field_mask(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysFieldMaskService>>,
+    ) -> Result<Res<SysFieldMaskModel>, AppError> {
+        service.get_field_mask(&id).await.map(Res::new_data)
+    }
+
+    pub async fn update_field_mask(
+        Extension(service): Extension<Arc<SysFieldMaskService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<UpdateFieldMaskInput>,
+    ) -> Result<Res<SysFieldMaskModel>, AppError> {
+        service
+            .update_field_mask(input, user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn delete_field_mask(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysFieldMaskService>>,
+    ) -> Result<Res<()>, AppError> {
+        service.delete_field_mask(&id).await.map(Res::new_data)
+    }
+}
@@ -1,9 +1,13 @@
 use std::sync::Arc;
 
-use axum::extract::{Extension, Query};
-use server_core::web::{error::AppError, page::PaginatedData, res::Res};
+use axum::extract::{Extension, Path, Query};
+use server_core::web::{
+    auth::User, error::AppError, export::ExportFile, page::PaginatedData, res::Res,
+    validator::ValidatedForm,
+};
 use server_service::admin::{
-    LoginLogPageRequest, SysLoginLogModel, SysLoginLogService, TLoginLogService,
+    ExportJobOutput, LoginLogExportRequest, LoginLogPageRequest, SysLoginLogModel,
+    SysLoginLogService, TLoginLogService,
 };
 
 pub struct SysLoginLogApi;
@@ -12,9 +16,40 @@ impl SysLoginLogApi {
     pub async fn get_paginated_login_logs(
         Query(params): Query<LoginLogPageRequest>,
         Extension(service): Extension<Arc<SysLoginLogService>>,
+        user: User,
     ) -> Result<Res<PaginatedData<SysLoginLogModel>>, AppError> {
         service
-            .find_paginated_login_logs(params)
+            .find_paginated_login_logs(params, &user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn export_login_logs(
+        Query(params): Query<LoginLogExportRequest>,
+        Extension(service): Extension<Arc<SysLoginLogService>>,
+        user: User,
+    ) -> Result<ExportFile, AppError> {
+        service.export_login_logs(params, &user).await
+    }
+
+    pub async fn create_login_log_export_job(
+        Extension(service): Extension<Arc<SysLoginLogService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(params): ValidatedForm<LoginLogExportRequest>,
+    ) -> Result<Res<ExportJobOutput>, AppError> {
+        service
+            .create_login_log_export_job(params, &user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_login_log_export_job(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysLoginLogService>>,
+        user: User,
+    ) -> Result<Res<ExportJobOutput>, AppError> {
+        service
+            .get_login_log_export_job(&id, &user)
             .await
             .map(Res::new_data)
     }
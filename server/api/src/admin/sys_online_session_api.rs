@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use axum::extract::{Extension, Path, Query};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData, res::Res};
+use server_service::admin::{
+    OnlineSessionOutput, OnlineSessionPageRequest, SysOnlineSessionService, TOnlineSessionService,
+};
+
+pub struct SysOnlineSessionApi;
+
+impl SysOnlineSessionApi {
+    pub async fn get_paginated_sessions(
+        Query(params): Query<OnlineSessionPageRequest>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysOnlineSessionService>>,
+    ) -> Result<Res<PaginatedData<OnlineSessionOutput>>, AppError> {
+        service
+            .find_paginated_sessions(&user.domain(), params)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn kick_session(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysOnlineSessionService>>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .kick_session(&user.domain(), &id)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn kick_user_sessions(
+        Path(user_id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysOnlineSessionService>>,
+    ) -> Result<Res<u64>, AppError> {
+        service
+            .kick_user_sessions(&user.domain(), &user_id)
+            .await
+            .map(Res::new_data)
+    }
+}
@@ -1,9 +1,13 @@
 use std::sync::Arc;
 
-use axum::extract::{Extension, Query};
-use server_core::web::{error::AppError, page::PaginatedData, res::Res};
+use axum::extract::{Extension, Path, Query};
+use server_core::web::{
+    auth::User, error::AppError, export::ExportFile, page::PaginatedData, res::Res,
+    validator::ValidatedForm,
+};
 use server_service::admin::{
-    OperationLogPageRequest, SysOperationLogModel, SysOperationLogService, TOperationLogService,
+    AuditLogMetricsSnapshot, ExportJobOutput, OperationLogExportRequest, OperationLogPageRequest,
+    SysOperationLogModel, SysOperationLogService, TOperationLogService,
 };
 
 pub struct SysOperationLogApi;
@@ -12,9 +16,46 @@ impl SysOperationLogApi {
     pub async fn get_paginated_operation_logs(
         Query(params): Query<OperationLogPageRequest>,
         Extension(service): Extension<Arc<SysOperationLogService>>,
+        Extension(user): Extension<User>,
     ) -> Result<Res<PaginatedData<SysOperationLogModel>>, AppError> {
         service
-            .find_paginated_operation_logs(params)
+            .find_paginated_operation_logs(params, &user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_audit_log_metrics(
+        Extension(service): Extension<Arc<SysOperationLogService>>,
+    ) -> Result<Res<AuditLogMetricsSnapshot>, AppError> {
+        Ok(Res::new_data(service.get_audit_log_metrics().await))
+    }
+
+    pub async fn export_operation_logs(
+        Query(params): Query<OperationLogExportRequest>,
+        Extension(service): Extension<Arc<SysOperationLogService>>,
+        Extension(user): Extension<User>,
+    ) -> Result<ExportFile, AppError> {
+        service.export_operation_logs(params, &user).await
+    }
+
+    pub async fn create_operation_log_export_job(
+        Extension(service): Extension<Arc<SysOperationLogService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(params): ValidatedForm<OperationLogExportRequest>,
+    ) -> Result<Res<ExportJobOutput>, AppError> {
+        service
+            .create_operation_log_export_job(params, &user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_operation_log_export_job(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysOperationLogService>>,
+        user: User,
+    ) -> Result<Res<ExportJobOutput>, AppError> {
+        service
+            .get_operation_log_export_job(&id, &user)
             .await
             .map(Res::new_data)
     }
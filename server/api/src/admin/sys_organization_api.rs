@@ -1,9 +1,13 @@
 use std::sync::Arc;
 
-use axum::extract::{Extension, Query};
-use server_core::web::{error::AppError, page::PaginatedData, res::Res};
+use axum::extract::{Extension, Path, Query};
+use server_core::web::{
+    auth::User, error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm,
+};
 use server_service::admin::{
-    OrganizationPageRequest, SysOrganizationModel, SysOrganizationService, TOrganizationService,
+    AssignOrganizationUserInput, CreateOrganizationInput, MoveOrganizationInput,
+    OrganizationPageRequest, OrganizationTree, SysOrganizationModel, SysOrganizationService,
+    TOrganizationService, UpdateOrganizationInput, UserWithoutPassword,
 };
 
 pub struct SysOrganizationApi;
@@ -18,4 +22,75 @@ impl SysOrganizationApi {
             .await
             .map(Res::new_data)
     }
+
+    pub async fn tree_organizations(
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+    ) -> Result<Res<Vec<OrganizationTree>>, AppError> {
+        service.tree_organizations().await.map(Res::new_data)
+    }
+
+    pub async fn create_organization(
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<CreateOrganizationInput>,
+    ) -> Result<Res<SysOrganizationModel>, AppError> {
+        service
+            .create_organization(input, user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn get_organization(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+    ) -> Result<Res<SysOrganizationModel>, AppError> {
+        service.get_organization(&id).await.map(Res::new_data)
+    }
+
+    pub async fn update_organization(
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<UpdateOrganizationInput>,
+    ) -> Result<Res<SysOrganizationModel>, AppError> {
+        service
+            .update_organization(input, user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn move_organization(
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+        Extension(user): Extension<User>,
+        ValidatedForm(input): ValidatedForm<MoveOrganizationInput>,
+    ) -> Result<Res<SysOrganizationModel>, AppError> {
+        service
+            .move_organization(input, user)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn delete_organization(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+    ) -> Result<Res<()>, AppError> {
+        service.delete_organization(&id).await.map(Res::new_data)
+    }
+
+    pub async fn get_organization_users(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+    ) -> Result<Res<Vec<UserWithoutPassword>>, AppError> {
+        service.get_organization_users(&id).await.map(Res::new_data)
+    }
+
+    pub async fn assign_organization_users(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysOrganizationService>>,
+        ValidatedForm(input): ValidatedForm<AssignOrganizationUserInput>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .assign_organization_users(&id, input)
+            .await
+            .map(Res::new_data)
+    }
 }
@@ -4,9 +4,13 @@ use axum::{
     extract::{Path, Query},
     Extension,
 };
-use server_core::web::{error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm};
+use axum_casbin::CasbinAxumLayer;
+use server_core::web::{
+    auth::User, error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm,
+};
 use server_service::admin::{
-    CreateRoleInput, RolePageRequest, SysRoleModel, SysRoleService, TRoleService, UpdateRoleInput,
+    CreateRoleInput, RoleEffectivePermissionsOutput, RolePageRequest, SysRoleModel, SysRoleService,
+    TRoleService, UpdateRoleInput,
 };
 
 pub struct SysRoleApi;
@@ -24,9 +28,15 @@ impl SysRoleApi {
 
     pub async fn create_role(
         Extension(service): Extension<Arc<SysRoleService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
         ValidatedForm(input): ValidatedForm<CreateRoleInput>,
     ) -> Result<Res<SysRoleModel>, AppError> {
-        service.create_role(input).await.map(Res::new_data)
+        let enforcer = cache_enforcer.get_enforcer();
+        service
+            .create_role(input, &user.domain(), enforcer)
+            .await
+            .map(Res::new_data)
     }
 
     pub async fn get_role(
@@ -38,15 +48,36 @@ impl SysRoleApi {
 
     pub async fn update_role(
         Extension(service): Extension<Arc<SysRoleService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
         ValidatedForm(input): ValidatedForm<UpdateRoleInput>,
     ) -> Result<Res<SysRoleModel>, AppError> {
-        service.update_role(input).await.map(Res::new_data)
+        let enforcer = cache_enforcer.get_enforcer();
+        service
+            .update_role(input, &user.domain(), enforcer)
+            .await
+            .map(Res::new_data)
     }
 
     pub async fn delete_role(
         Path(id): Path<String>,
         Extension(service): Extension<Arc<SysRoleService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
     ) -> Result<Res<()>, AppError> {
-        service.delete_role(&id).await.map(Res::new_data)
+        let enforcer = cache_enforcer.get_enforcer();
+        service.delete_role(&id, enforcer).await.map(Res::new_data)
+    }
+
+    pub async fn get_effective_permissions(
+        Path(id): Path<String>,
+        Extension(service): Extension<Arc<SysRoleService>>,
+        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
+        Extension(user): Extension<User>,
+    ) -> Result<Res<RoleEffectivePermissionsOutput>, AppError> {
+        let enforcer = cache_enforcer.get_enforcer();
+        service
+            .get_effective_permissions(&id, &user.domain(), enforcer)
+            .await
+            .map(Res::new_data)
     }
 }
@@ -4,13 +4,12 @@ use axum::{
     extract::{Path, Query},
     Extension,
 };
-use axum_casbin::{casbin::MgmtApi, CasbinAxumLayer};
 use server_core::web::{
     auth::User, error::AppError, page::PaginatedData, res::Res, validator::ValidatedForm,
 };
 use server_service::admin::{
-    CreateUserInput, SysUserService, TUserService, UpdateUserInput, UserPageRequest,
-    UserWithoutPassword,
+    ChangePasswordInput, CreateUserInput, ResetPasswordInput, SysUserService, TUserService,
+    UpdateUserInput, UserPageRequest, UserWithoutPassword,
 };
 
 pub struct SysUserApi;
@@ -27,43 +26,12 @@ impl SysUserApi {
         Extension(service): Extension<Arc<SysUserService>>,
         user: User,
     ) -> Result<Res<PaginatedData<UserWithoutPassword>>, AppError> {
-        print!("user is {:#?}", user);
         service
-            .find_paginated_users(params)
+            .find_paginated_users(params, &user)
             .await
             .map(Res::new_data)
     }
 
-    pub async fn remove_policies(
-        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
-    ) -> Res<bool> {
-        let enforcer = cache_enforcer.get_enforcer();
-        let mut enforcer_write = enforcer.write().await;
-        let rule = vec![
-            "1".to_string(),
-            "built-in".to_string(),
-            "/user/users".to_string(),
-            "GET".to_string(),
-        ];
-        let _ = enforcer_write.remove_policies(vec![rule]).await;
-        Res::new_data(true)
-    }
-
-    pub async fn add_policies(
-        Extension(mut cache_enforcer): Extension<CasbinAxumLayer>,
-    ) -> Res<bool> {
-        let enforcer = cache_enforcer.get_enforcer();
-        let mut enforcer_write = enforcer.write().await;
-        let rule = vec![
-            "1".to_string(),
-            "built-in".to_string(),
-            "/user/users".to_string(),
-            "GET".to_string(),
-        ];
-        let _ = enforcer_write.add_policy(rule).await;
-        Res::new_data(true)
-    }
-
     pub async fn create_user(
         Extension(service): Extension<Arc<SysUserService>>,
         ValidatedForm(input): ValidatedForm<CreateUserInput>,
@@ -91,4 +59,35 @@ impl SysUserApi {
     ) -> Result<Res<()>, AppError> {
         service.delete_user(&id).await.map(Res::new_data)
     }
+
+    pub async fn force_logout(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+    ) -> Result<Res<u64>, AppError> {
+        service.force_logout(&id, &user).await.map(Res::new_data)
+    }
+
+    pub async fn change_password(
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+        ValidatedForm(input): ValidatedForm<ChangePasswordInput>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .change_password(&user.user_id(), input)
+            .await
+            .map(Res::new_data)
+    }
+
+    pub async fn reset_password(
+        Path(id): Path<String>,
+        Extension(user): Extension<User>,
+        Extension(service): Extension<Arc<SysUserService>>,
+        ValidatedForm(input): ValidatedForm<ResetPasswordInput>,
+    ) -> Result<Res<()>, AppError> {
+        service
+            .reset_password(&id, input, &user)
+            .await
+            .map(Res::new_data)
+    }
 }
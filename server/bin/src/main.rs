@@ -1,6 +1,6 @@
 use std::net::SocketAddr;
 
-use tokio::net::TcpListener;
+use tokio::{net::TcpListener, signal};
 
 #[tokio::main]
 async fn main() {
@@ -16,12 +16,16 @@ async fn main() {
     server_initialize::init_primary_connection().await;
     server_initialize::init_db_pools().await;
     server_initialize::initialize_keys_and_validation().await;
+    server_initialize::initialize_password_hasher().await;
+    server_initialize::initialize_identity_providers().await;
     server_initialize::initialize_event_channel().await;
 
     server_initialize::init_primary_redis().await;
     server_initialize::init_redis_pools().await;
     server_initialize::init_primary_mongo().await;
     server_initialize::init_mongo_pools().await;
+    server_initialize::initialize_audit_log_writer().await;
+    server_initialize::initialize_audit_chain().await;
 
     // build our application with a route
     let app = server_initialize::initialize_admin_router().await;
@@ -44,6 +48,35 @@ async fn main() {
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await
     .unwrap();
+
+    // 停止接收请求后写出剩余的操作日志
+    server_initialize::shutdown_audit_log_writer().await;
+}
+
+/// 等待 Ctrl+C 或 SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
@@ -1,8 +1,12 @@
 pub use config_init::init_from_file;
 pub use model::{
-    Config, DatabaseConfig, DatabasesInstancesConfig, JwtConfig, MongoConfig, MongoInstancesConfig,
-    OptionalConfigs, RedisConfig, RedisInstancesConfig, RedisMode, S3Config, S3InstancesConfig,
-    ServerConfig,
+    AuditChainConfig, AuditLogConfig, AuditSinkConfig, CasbinConfig, Config, DatabaseConfig,
+    DatabasesInstancesConfig, DomainLoginPolicy, ExportConfig, JwtAlgorithm, JwtConfig,
+    JwtKeyConfig, LdapConfig, LdapDirectoryConfig, LoginHours, LoginSecurityConfig, MfaConfig,
+    MongoConfig, MongoInstancesConfig, OAuthConfig, OidcProviderConfig, OperationLogConfig,
+    OptionalConfigs, PasswordHashConfig, PasswordPolicy, PasswordPolicyConfig, RedisConfig,
+    RedisInstancesConfig, RedisMode, S3Config, S3InstancesConfig, ServerConfig, SessionConfig,
+    SessionLimitStrategy,
 };
 pub use server_global::{project_error, project_info};
 
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+/// 审计日志哈希链配置
+///
+/// 启用后操作日志与登录日志按域组成哈希链，每条记录保存自身内容与上一条记录哈希的摘要。
+/// 哈希链以主数据库中的记录为准，多实例部署时同一个域的日志应由同一个实例写入。
+/// 配置了 `signing_key_path` 时每隔 `checkpoint_interval_secs` 对各链的最新记录签名。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AuditChainConfig {
+    /// 是否启用哈希链
+    pub enabled: bool,
+    /// 签名检查点的间隔（秒）
+    pub checkpoint_interval_secs: u64,
+    /// Ed25519 私钥文件（PKCS#8 DER），未配置时不生成检查点
+    pub signing_key_path: Option<String>,
+}
+
+impl Default for AuditChainConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            checkpoint_interval_secs: 3_600,
+            signing_key_path: None,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+/// 操作日志的批量写入配置
+///
+/// 操作日志先进入容量为 `queue_capacity` 的队列，队列已满时请求最多等待 `enqueue_timeout_ms`，
+/// 仍无法写入则丢弃该条日志。写入任务攒够 `batch_size` 条或每隔 `flush_interval_ms`
+/// 将日志批量写入所有 `sinks`，失败时按 `retry_backoff_ms` 指数退避重试 `max_retries` 次。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    /// 写入目标，可同时配置多个
+    pub sinks: Vec<AuditSinkConfig>,
+    /// 队列容量
+    pub queue_capacity: usize,
+    /// 单批写入的最大条数
+    pub batch_size: usize,
+    /// 未攒够一批时的写入间隔（毫秒）
+    pub flush_interval_ms: u64,
+    /// 队列已满时的最长等待时间（毫秒）
+    pub enqueue_timeout_ms: u64,
+    /// 单批写入失败后的最大重试次数
+    pub max_retries: u32,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            sinks: vec![AuditSinkConfig::Database],
+            queue_capacity: 10_000,
+            batch_size: 200,
+            flush_interval_ms: 1_000,
+            enqueue_timeout_ms: 50,
+            max_retries: 3,
+            retry_backoff_ms: 200,
+        }
+    }
+}
+
+/// 操作日志写入目标
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditSinkConfig {
+    /// 通过 SeaORM 写入主数据库的 `sys_operation_log` 表
+    Database,
+    /// 写入 MongoDB 集合，未指定 `instance` 时使用主实例
+    Mongo {
+        instance: Option<String>,
+        database: String,
+        collection: String,
+    },
+    /// 以 JSON Lines 格式追加写入文件
+    File { path: String },
+}
@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+/// Casbin 策略加载配置
+///
+/// 默认启动时加载全部策略。域较多时可开启按需加载：域的策略在该域的首个请求时
+/// 加载，超过 `domain_cache_capacity` 个域后淘汰最久未使用的域。
+///
+/// 策略可附带条件（如 `ip(10.0.0.0/8) && time(09:00-18:00)`），条件中可引用的请求头
+/// 需列在 `condition_headers` 中。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CasbinConfig {
+    /// 按域按需加载策略
+    pub lazy_load: bool,
+    /// 按需加载时内存中最多保留的域数量
+    pub domain_cache_capacity: usize,
+    /// 传入策略条件的请求头
+    pub condition_headers: Vec<String>,
+}
+
+impl Default for CasbinConfig {
+    fn default() -> Self {
+        Self {
+            lazy_load: false,
+            domain_cache_capacity: 64,
+            condition_headers: Vec::new(),
+        }
+    }
+}
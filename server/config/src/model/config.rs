@@ -1,8 +1,11 @@
 use serde::Deserialize;
 
 use super::{
-    DatabaseConfig, DatabasesInstancesConfig, JwtConfig, MongoConfig, MongoInstancesConfig,
-    RedisConfig, RedisInstancesConfig, S3Config, S3InstancesConfig, ServerConfig,
+    AuditChainConfig, AuditLogConfig, CasbinConfig, DatabaseConfig, DatabasesInstancesConfig,
+    ExportConfig, JwtConfig, LdapConfig, LoginSecurityConfig, MfaConfig, MongoConfig,
+    MongoInstancesConfig, OAuthConfig, OperationLogConfig, PasswordHashConfig,
+    PasswordPolicyConfig, RedisConfig, RedisInstancesConfig, S3Config, S3InstancesConfig,
+    ServerConfig, SessionConfig,
 };
 
 /// 应用程序配置结构
@@ -12,7 +15,7 @@ use super::{
 ///
 /// 1. 首先从配置文件（如 application.yaml）中加载整体配置
 /// 2. 然后通过 `init_from_file` 函数将配置注入到全局状态中
-///    ```rust
+///    ```rust,ignore
 ///    // 注入主配置
 ///    global::init_config::<Config>(config.clone()).await;
 ///
@@ -39,6 +42,14 @@ use super::{
 /// - `redis_instances`: 可选的 Redis 连接池配置，用于配置多个命名的 Redis 连接
 /// - `mongo`: 主 MongoDB 配置，用于配置默认的 MongoDB 连接
 /// - `mongo_instances`: 可选的 MongoDB 连接池配置，用于配置多个命名的 MongoDB 连接
+/// - `login_security`: 可选的登录安全配置，包含失败锁定、IP 访问控制与登录时段
+/// - `session`: 可选的在线会话配置，限制单个用户的并发会话数
+/// - `mfa`: 可选的两步验证配置，包含挑战令牌有效期与 TOTP 签发方
+/// - `password_policy`: 可选的密码策略配置，支持按域覆盖
+/// - `password_hash`: 可选的 Argon2 密码哈希参数
+/// - `oauth`: 可选的外部身份提供方（OIDC）登录配置
+/// - `ldap`: 可选的 LDAP / Active Directory 登录配置，按域启用
+/// - `casbin`: 可选的 Casbin 策略加载配置，支持按域按需加载
 ///
 /// # 示例配置（YAML）
 /// ```yaml
@@ -68,6 +79,17 @@ use super::{
 ///     urls:
 ///       - "redis://:password@localhost:6379"
 ///       - "redis://:password@localhost:6380"
+///
+/// login_security:
+///   max_failed_attempts: 5
+///   lockout_duration: 900
+///   domains:
+///     built-in:
+///       ip_denylist:
+///         - "203.0.113.0/24"
+///       login_hours:
+///         start: "08:00"
+///         end: "20:00"
 /// ```
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
@@ -104,4 +126,40 @@ pub struct Config {
     /// 可选的 S3 连接池配置
     /// 用于配置多个命名的 S3 连接
     pub s3_instances: Option<Vec<S3InstancesConfig>>,
+
+    /// 登录安全配置
+    pub login_security: Option<LoginSecurityConfig>,
+
+    /// 在线会话配置
+    pub session: Option<SessionConfig>,
+
+    /// 两步验证配置
+    pub mfa: Option<MfaConfig>,
+
+    /// 密码策略配置
+    pub password_policy: Option<PasswordPolicyConfig>,
+
+    /// Argon2 密码哈希参数
+    pub password_hash: Option<PasswordHashConfig>,
+
+    /// 外部身份提供方登录配置
+    pub oauth: Option<OAuthConfig>,
+
+    /// LDAP 登录配置
+    pub ldap: Option<LdapConfig>,
+
+    /// Casbin 策略加载配置
+    pub casbin: Option<CasbinConfig>,
+
+    /// 操作日志的脱敏与大小限制配置
+    pub operation_log: Option<OperationLogConfig>,
+
+    /// 操作日志的批量写入配置
+    pub audit_log: Option<AuditLogConfig>,
+
+    /// 审计日志哈希链配置
+    pub audit_chain: Option<AuditChainConfig>,
+
+    /// 日志导出配置
+    pub export: Option<ExportConfig>,
 }
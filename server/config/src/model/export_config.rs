@@ -0,0 +1,35 @@
+use serde::Deserialize;
+
+/// 日志导出配置
+///
+/// 同步导出直接以流的形式返回；异步导出任务将文件分段上传到 S3，完成后按
+/// `link_expires_secs` 签发下载链接。未配置 `bucket` 时不能创建异步导出任务。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// 使用的命名 S3 实例，未配置时使用主 S3 客户端
+    pub s3_instance: Option<String>,
+    /// 保存导出文件的存储桶
+    pub bucket: Option<String>,
+    /// 导出文件的对象键前缀
+    pub key_prefix: String,
+    /// 下载链接的有效期（秒）
+    pub link_expires_secs: u64,
+    /// 任务记录在内存中的保留时间（秒）
+    pub job_retention_secs: u64,
+    /// 同时运行的异步导出任务数上限
+    pub max_running_jobs: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            s3_instance: None,
+            bucket: None,
+            key_prefix: "exports/".to_string(),
+            link_expires_secs: 3_600,
+            job_retention_secs: 86_400,
+            max_running_jobs: 2,
+        }
+    }
+}
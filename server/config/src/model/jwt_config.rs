@@ -2,7 +2,62 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct JwtConfig {
+    /// HMAC 密钥，仅在使用 HS256 时需要
+    #[serde(default)]
     pub jwt_secret: String,
     pub issuer: String,
     pub expire: i64,
+    /// 刷新令牌有效期（秒），从令牌族首次登录时开始计算，默认 7 天
+    #[serde(default = "default_refresh_expire")]
+    pub refresh_expire: i64,
+    /// 签名算法，默认 HS256
+    #[serde(default)]
+    pub algorithm: JwtAlgorithm,
+    /// 非对称算法使用的密钥列表
+    ///
+    /// 带私钥的密钥可用于签名，仅有公钥的密钥只用于验签。密钥轮换时先加入新密钥并设为
+    /// `signing_kid`，旧密钥去掉私钥后保留到其签发的令牌全部过期为止。
+    #[serde(default)]
+    pub keys: Vec<JwtKeyConfig>,
+    /// 用于签名的密钥 kid，未配置时使用第一个带私钥的密钥
+    #[serde(default)]
+    pub signing_kid: Option<String>,
+    /// 令牌吊销列表（Redis）不可用时是否放行请求
+    ///
+    /// 默认拒绝请求，保证已注销的令牌不会被继续使用；设为 `true` 时优先保证可用性，
+    /// Redis 故障期间已吊销但未过期的令牌仍可访问。
+    #[serde(default)]
+    pub revocation_fail_open: bool,
+}
+
+fn default_refresh_expire() -> i64 {
+    7 * 24 * 3600
+}
+
+/// JWT 签名算法
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    #[default]
+    HS256,
+    RS256,
+    ES256,
+    EdDSA,
+}
+
+impl JwtAlgorithm {
+    /// 是否为非对称算法
+    pub fn is_asymmetric(&self) -> bool {
+        !matches!(self, JwtAlgorithm::HS256)
+    }
+}
+
+/// JWT 非对称密钥配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct JwtKeyConfig {
+    /// 密钥标识，写入令牌头并在 JWKS 中公布
+    pub kid: String,
+    /// PEM 格式公钥文件路径
+    pub public_key_path: String,
+    /// PEM 格式私钥文件路径（PKCS#8），仅签名密钥需要
+    pub private_key_path: Option<String>,
 }
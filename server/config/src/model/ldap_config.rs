@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// LDAP / Active Directory 登录配置
+///
+/// 按域启用，未在 `domains` 中配置的域仍使用本地密码登录。
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LdapConfig {
+    /// 按域配置的目录服务，键为域编码
+    pub domains: HashMap<String, LdapDirectoryConfig>,
+}
+
+impl LdapConfig {
+    /// 获取域对应的目录服务配置
+    pub fn directory_for(&self, domain: &str) -> Option<&LdapDirectoryConfig> {
+        self.domains.get(domain)
+    }
+}
+
+/// 目录服务配置
+///
+/// 登录时先以服务账号绑定，按 `user_filter` 查找用户条目，再以用户 DN 与登录密码
+/// 重新绑定完成认证。用户所属的组按 `group_roles` 映射为角色编码。
+#[derive(Deserialize, Debug, Clone)]
+pub struct LdapDirectoryConfig {
+    /// 服务地址，如 `ldap://127.0.0.1:389` 或 `ldaps://ad.example.com:636`
+    pub url: String,
+    /// 对 `ldap://` 连接启用 StartTLS
+    #[serde(default)]
+    pub starttls: bool,
+    /// 服务账号 DN
+    pub bind_dn: String,
+    pub bind_password: String,
+    /// 用户搜索的起始 DN
+    pub base_dn: String,
+    /// 用户搜索过滤器，`{username}` 会替换为转义后的登录名
+    #[serde(default = "default_user_filter")]
+    pub user_filter: String,
+    /// 用户所属组的属性名，Active Directory 与启用了 memberOf 的 OpenLDAP 均为 `memberOf`
+    #[serde(default = "default_group_attribute")]
+    pub group_attribute: String,
+    #[serde(default = "default_display_name_attribute")]
+    pub display_name_attribute: String,
+    #[serde(default = "default_email_attribute")]
+    pub email_attribute: String,
+    /// 组 DN 到角色编码的映射，组 DN 不区分大小写
+    #[serde(default)]
+    pub group_roles: HashMap<String, String>,
+    /// 目录中不存在该用户时是否回退到本地密码登录，用于保留本地管理员账号
+    #[serde(default = "default_local_fallback")]
+    pub local_fallback: bool,
+    /// 连接与操作超时（秒）
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+impl LdapDirectoryConfig {
+    /// 将用户所属的组映射为角色编码
+    pub fn map_roles(&self, groups: &[String]) -> Vec<String> {
+        let mut roles: Vec<String> = self
+            .group_roles
+            .iter()
+            .filter(|(group, _)| groups.iter().any(|g| g.eq_ignore_ascii_case(group)))
+            .map(|(_, role)| role.clone())
+            .collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+
+    /// 由目录组映射管理的全部角色编码
+    pub fn managed_roles(&self) -> Vec<String> {
+        let mut roles: Vec<String> = self.group_roles.values().cloned().collect();
+        roles.sort();
+        roles.dedup();
+        roles
+    }
+}
+
+fn default_user_filter() -> String {
+    "(&(objectClass=person)(uid={username}))".to_string()
+}
+
+fn default_group_attribute() -> String {
+    "memberOf".to_string()
+}
+
+fn default_display_name_attribute() -> String {
+    "displayName".to_string()
+}
+
+fn default_email_attribute() -> String {
+    "mail".to_string()
+}
+
+fn default_local_fallback() -> bool {
+    true
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_roles_ignores_group_case() {
+        let config: LdapDirectoryConfig = serde_yaml::from_str(
+            r#"
+url: "ldap://127.0.0.1:389"
+bind_dn: "cn=admin,dc=example,dc=com"
+bind_password: "secret"
+base_dn: "ou=people,dc=example,dc=com"
+group_roles:
+    "cn=admins,ou=groups,dc=example,dc=com": "ROLE_ADMIN"
+    "cn=staff,ou=groups,dc=example,dc=com": "ROLE_USER"
+"#,
+        )
+        .unwrap();
+
+        let groups = vec![
+            "CN=Admins,OU=Groups,DC=example,DC=com".to_string(),
+            "cn=other,ou=groups,dc=example,dc=com".to_string(),
+        ];
+        assert_eq!(config.map_roles(&groups), vec!["ROLE_ADMIN"]);
+        assert_eq!(config.managed_roles(), vec!["ROLE_ADMIN", "ROLE_USER"]);
+        assert!(config.local_fallback);
+        assert_eq!(
+            config.user_filter,
+            "(&(objectClass=person)(uid={username}))"
+        );
+    }
+}
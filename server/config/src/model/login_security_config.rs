@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// 登录安全配置
+///
+/// 未配置时使用默认值：同一账号 15 分钟内连续失败 5 次锁定 15 分钟，
+/// 同一 IP 连续失败 20 次锁定 15 分钟，不限制 IP 与登录时段。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LoginSecurityConfig {
+    /// 同一账号（用户名 + 域）允许的失败次数，0 表示不限制
+    pub max_failed_attempts: u64,
+    /// 同一 IP 允许的失败次数，0 表示不限制
+    pub ip_max_failed_attempts: u64,
+    /// 失败次数统计窗口（秒）
+    pub failure_window: u64,
+    /// 达到阈值后的锁定时长（秒）
+    pub lockout_duration: u64,
+    /// 按域配置的登录策略，键为域编码
+    pub domains: HashMap<String, DomainLoginPolicy>,
+}
+
+impl Default for LoginSecurityConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            ip_max_failed_attempts: 20,
+            failure_window: 900,
+            lockout_duration: 900,
+            domains: HashMap::new(),
+        }
+    }
+}
+
+/// 域登录策略
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DomainLoginPolicy {
+    /// IP 白名单，支持单个 IP 与 CIDR，为空表示不限制
+    pub ip_allowlist: Vec<String>,
+    /// IP 黑名单，支持单个 IP 与 CIDR，优先于白名单
+    pub ip_denylist: Vec<String>,
+    /// 允许登录的时段，未配置表示不限制
+    pub login_hours: Option<LoginHours>,
+}
+
+/// 允许登录的时段（服务器本地时间，格式 `HH:MM`）
+///
+/// `start` 晚于 `end` 时表示跨天时段，如 `22:00` 至 `06:00`。
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoginHours {
+    pub start: String,
+    pub end: String,
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+/// 两步验证配置
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MfaConfig {
+    /// 两步登录挑战令牌的有效期（秒）
+    pub challenge_ttl: u64,
+    /// 写入 TOTP 认证器的签发方名称
+    pub totp_issuer: String,
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            challenge_ttl: 300,
+            totp_issuer: "Soybean Admin".to_string(),
+        }
+    }
+}
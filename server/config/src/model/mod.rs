@@ -1,10 +1,22 @@
+pub use audit_chain_config::AuditChainConfig;
+pub use audit_log_config::{AuditLogConfig, AuditSinkConfig};
+pub use casbin_config::CasbinConfig;
 pub use config::Config;
 pub use database_config::{DatabaseConfig, DatabasesInstancesConfig};
-pub use jwt_config::JwtConfig;
+pub use export_config::ExportConfig;
+pub use jwt_config::{JwtAlgorithm, JwtConfig, JwtKeyConfig};
+pub use ldap_config::{LdapConfig, LdapDirectoryConfig};
+pub use login_security_config::{DomainLoginPolicy, LoginHours, LoginSecurityConfig};
+pub use mfa_config::MfaConfig;
 pub use mongo_config::{MongoConfig, MongoInstancesConfig};
+pub use oauth_config::{OAuthConfig, OidcProviderConfig};
+pub use operation_log_config::OperationLogConfig;
+pub use password_hash_config::PasswordHashConfig;
+pub use password_policy_config::{PasswordPolicy, PasswordPolicyConfig};
 pub use redis_config::{RedisConfig, RedisInstancesConfig, RedisMode};
 pub use s3_config::{S3Config, S3InstancesConfig};
 pub use server_config::ServerConfig;
+pub use session_config::{SessionConfig, SessionLimitStrategy};
 
 /// 可选配置集合的包装类
 #[allow(dead_code)]
@@ -19,10 +31,22 @@ impl<T> From<Option<Vec<T>>> for OptionalConfigs<T> {
     }
 }
 
+mod audit_chain_config;
+mod audit_log_config;
+mod casbin_config;
 mod config;
 mod database_config;
+mod export_config;
 mod jwt_config;
+mod ldap_config;
+mod login_security_config;
+mod mfa_config;
 mod mongo_config;
+mod oauth_config;
+mod operation_log_config;
+mod password_hash_config;
+mod password_policy_config;
 mod redis_config;
 mod s3_config;
 mod server_config;
+mod session_config;
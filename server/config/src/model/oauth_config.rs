@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// 外部身份提供方（OAuth2 / OpenID Connect）登录配置
+///
+/// 未配置任何提供方时外部登录不可用。外部账号首次登录时按 `sys_user_identity` 中的
+/// 绑定关系查找用户，未绑定且提供方开启了自动创建时，在提供方所属域中新建用户并
+/// 分配该域的默认角色。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OAuthConfig {
+    /// 授权请求 state 的有效期（秒）
+    pub state_ttl: u64,
+    /// 身份提供方列表
+    pub providers: Vec<OidcProviderConfig>,
+    /// 自动创建用户时分配的默认角色编码，键为域编码
+    pub default_roles: HashMap<String, String>,
+}
+
+impl Default for OAuthConfig {
+    fn default() -> Self {
+        Self {
+            state_ttl: 600,
+            providers: Vec::new(),
+            default_roles: HashMap::new(),
+        }
+    }
+}
+
+impl OAuthConfig {
+    /// 按名称查找提供方配置
+    pub fn provider(&self, name: &str) -> Option<&OidcProviderConfig> {
+        self.providers.iter().find(|provider| provider.name == name)
+    }
+}
+
+/// OpenID Connect 提供方配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct OidcProviderConfig {
+    /// 提供方名称，用于路由 `/auth/oauth/{name}/...` 与身份绑定
+    pub name: String,
+    /// 签发方地址，发现文档位于 `{issuer}/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    /// 客户端密钥，公共客户端可不配置（仅依赖 PKCE）
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// 在提供方注册的回调地址
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// 外部用户所属的域编码
+    #[serde(default = "default_domain")]
+    pub domain: String,
+    /// 未绑定的外部账号是否自动创建用户
+    #[serde(default)]
+    pub auto_provision: bool,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_domain() -> String {
+    "built-in".to_string()
+}
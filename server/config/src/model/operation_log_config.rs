@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+/// 操作日志的记录配置
+///
+/// 内置的敏感字段（密码、令牌、密钥等）总会被脱敏，`redact_keys` 与 `redact_pointers`
+/// 在此基础上追加。超过 `max_buffer_size` 的请求体或响应不记录内容，请求照常处理；
+/// 脱敏后超过 `max_payload_size` 的内容会被截断。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OperationLogConfig {
+    /// 请求体与响应的缓冲上限（字节）
+    pub max_buffer_size: usize,
+    /// 记录的请求体与响应序列化后的上限（字节）
+    pub max_payload_size: usize,
+    /// 追加的脱敏字段名，忽略大小写与 `_`、`-`，`*` 匹配任意字符
+    pub redact_keys: Vec<String>,
+    /// 追加的脱敏 JSON Pointer，如 `/data/*/phoneNumber`，`*` 匹配任意字段或数组下标
+    pub redact_pointers: Vec<String>,
+    /// 记录的请求头
+    pub header_allowlist: Vec<String>,
+}
+
+impl Default for OperationLogConfig {
+    fn default() -> Self {
+        Self {
+            max_buffer_size: 32 * 1024,
+            max_payload_size: 8 * 1024,
+            redact_keys: Vec::new(),
+            redact_pointers: Vec::new(),
+            header_allowlist: vec![
+                "content-type".to_string(),
+                "referer".to_string(),
+                "origin".to_string(),
+            ],
+        }
+    }
+}
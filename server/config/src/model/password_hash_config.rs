@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// Argon2 密码哈希参数
+///
+/// 默认值与 `argon2` crate 的默认参数一致（Argon2id，19 MiB，2 次迭代，1 个并行度）。
+/// 调整参数后，已有用户会在下次登录成功时按新参数重新哈希。
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PasswordHashConfig {
+    /// 内存开销（KiB）
+    pub memory_cost: u32,
+    /// 迭代次数
+    pub iterations: u32,
+    /// 并行度
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        Self {
+            memory_cost: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
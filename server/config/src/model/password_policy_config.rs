@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// 密码策略配置
+///
+/// 顶层字段为默认策略，`domains` 中可按域编码覆盖。
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    #[serde(flatten)]
+    pub default: PasswordPolicy,
+    /// 按域配置的密码策略，键为域编码
+    pub domains: HashMap<String, PasswordPolicy>,
+}
+
+impl PasswordPolicyConfig {
+    /// 获取指定域的密码策略，未单独配置时使用默认策略
+    pub fn policy_for(&self, domain: &str) -> &PasswordPolicy {
+        self.domains.get(domain).unwrap_or(&self.default)
+    }
+}
+
+/// 密码策略
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    /// 最小长度
+    pub min_length: usize,
+    /// 必须包含大写字母
+    pub require_uppercase: bool,
+    /// 必须包含小写字母
+    pub require_lowercase: bool,
+    /// 必须包含数字
+    pub require_digit: bool,
+    /// 必须包含特殊字符
+    pub require_special: bool,
+    /// 禁止密码包含用户名（不区分大小写）
+    pub disallow_username: bool,
+    /// 禁止与最近 N 次使用过的密码相同，0 表示不检查
+    pub history_size: usize,
+    /// 密码有效期（天），0 表示永不过期
+    pub max_age_days: u64,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_special: false,
+            disallow_username: true,
+            history_size: 5,
+            max_age_days: 0,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// 校验密码是否满足策略，返回第一条不满足的规则说明
+    ///
+    /// 历史密码需要比对哈希，由调用方另行检查。
+    pub fn validate(&self, password: &str, username: &str) -> Result<(), String> {
+        if password.chars().count() < self.min_length {
+            return Err(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        if self.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            return Err("Password must contain an uppercase letter".to_string());
+        }
+        if self.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            return Err("Password must contain a lowercase letter".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err("Password must contain a digit".to_string());
+        }
+        if self.require_special && !password.chars().any(|c| !c.is_alphanumeric()) {
+            return Err("Password must contain a special character".to_string());
+        }
+        if self.disallow_username
+            && !username.is_empty()
+            && password.to_lowercase().contains(&username.to_lowercase())
+        {
+            return Err("Password must not contain the username".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_special: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_password() {
+        let policy = strict_policy();
+
+        assert!(policy.validate("Sh0rt!", "admin").is_err());
+        assert!(policy.validate("lowercase1!x", "admin").is_err());
+        assert!(policy.validate("UPPERCASE1!X", "admin").is_err());
+        assert!(policy.validate("NoDigitsHere!", "admin").is_err());
+        assert!(policy.validate("NoSpecial123", "admin").is_err());
+        assert!(policy.validate("MyAdmin#2024x", "admin").is_err());
+        assert!(policy.validate("C0rrect-Horse", "admin").is_ok());
+    }
+
+    #[test]
+    fn test_policy_for_domain() {
+        let mut config = PasswordPolicyConfig::default();
+        config.domains.insert("strict".to_string(), strict_policy());
+
+        assert_eq!(config.policy_for("strict").min_length, 10);
+        assert_eq!(config.policy_for("built-in").min_length, 8);
+    }
+}
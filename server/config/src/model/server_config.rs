@@ -4,4 +4,10 @@ use serde::Deserialize;
 pub struct ServerConfig {
     pub host: String,
     pub port: u32,
+    /// 受信任的反向代理，支持 CIDR 与单个 IP
+    ///
+    /// 只有来自这些地址的连接才会读取 `X-Forwarded-For` 等请求头中的客户端 IP，
+    /// 为空时始终使用连接的对端地址。
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+
+/// 在线会话配置
+///
+/// 未配置时不限制并发会话数。刷新令牌已过期的会话不计入在线会话。
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// 单个用户允许同时在线的会话数，0 表示不限制
+    pub max_concurrent: u64,
+    /// 超出并发会话数时的处理策略
+    pub limit_strategy: SessionLimitStrategy,
+}
+
+/// 并发会话超限策略
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimitStrategy {
+    /// 踢出最早登录的会话
+    #[default]
+    EvictOldest,
+    /// 拒绝新的登录
+    RejectNew,
+}
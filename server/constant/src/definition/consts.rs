@@ -28,8 +28,6 @@ impl TokenStatus {
 pub enum SystemEvent {
     /// 用户认证登录事件
     AuthLoggedInEvent,
-    /// 系统操作日志事件
-    AuditOperationLoggedEvent,
     /// API密钥验证事件
     AuthApiKeyValidatedEvent,
 }
@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use ldap3::{ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use server_config::LdapDirectoryConfig;
+use thiserror::Error;
+
+/// LDAP 结果码 invalidCredentials
+const RC_INVALID_CREDENTIALS: u32 = 49;
+
+/// 目录中的用户信息
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectoryUser {
+    pub dn: String,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    /// 用户所属组的 DN
+    pub groups: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum LdapAuthError {
+    #[error("Directory service unavailable: {0}")]
+    Unavailable(String),
+    #[error("Invalid directory credentials")]
+    InvalidCredentials,
+    #[error("Multiple directory entries match the username")]
+    AmbiguousUser,
+}
+
+impl From<ldap3::LdapError> for LdapAuthError {
+    fn from(err: ldap3::LdapError) -> Self {
+        LdapAuthError::Unavailable(err.to_string())
+    }
+}
+
+/// LDAP 绑定认证
+///
+/// 先以服务账号绑定并按过滤器查找用户条目，再以用户 DN 与登录密码绑定。
+pub struct LdapAuthenticator<'a> {
+    config: &'a LdapDirectoryConfig,
+}
+
+impl<'a> LdapAuthenticator<'a> {
+    pub fn new(config: &'a LdapDirectoryConfig) -> Self {
+        Self { config }
+    }
+
+    /// 认证用户，目录中不存在该用户时返回 `Ok(None)`
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, LdapAuthError> {
+        // 空密码会被服务端视为匿名绑定而成功，必须在本地拒绝
+        if password.is_empty() {
+            return Err(LdapAuthError::InvalidCredentials);
+        }
+
+        let timeout = Duration::from_secs(self.config.timeout);
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(timeout)
+            .set_starttls(self.config.starttls);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.config.url).await?;
+        ldap3::drive!(conn);
+
+        let result = self.bind_and_search(&mut ldap, username, password).await;
+        if let Err(e) = ldap.unbind().await {
+            tracing::warn!("Failed to unbind LDAP connection: {}", e);
+        }
+        result
+    }
+
+    async fn bind_and_search(
+        &self,
+        ldap: &mut Ldap,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<DirectoryUser>, LdapAuthError> {
+        let timeout = Duration::from_secs(self.config.timeout);
+
+        ldap.with_timeout(timeout)
+            .simple_bind(&self.config.bind_dn, &self.config.bind_password)
+            .await?
+            .success()?;
+
+        let filter = self
+            .config
+            .user_filter
+            .replace("{username}", &ldap_escape(username));
+        let attributes = [
+            self.config.group_attribute.as_str(),
+            self.config.display_name_attribute.as_str(),
+            self.config.email_attribute.as_str(),
+        ];
+        let (entries, _) = ldap
+            .with_timeout(timeout)
+            .search(&self.config.base_dn, Scope::Subtree, &filter, attributes)
+            .await?
+            .success()?;
+
+        let entry = match entries.len() {
+            0 => return Ok(None),
+            1 => SearchEntry::construct(entries.into_iter().next().unwrap()),
+            _ => return Err(LdapAuthError::AmbiguousUser),
+        };
+
+        let result = ldap
+            .with_timeout(timeout)
+            .simple_bind(&entry.dn, password)
+            .await?;
+        if result.rc == RC_INVALID_CREDENTIALS {
+            return Err(LdapAuthError::InvalidCredentials);
+        }
+        result.success()?;
+
+        let first = |attribute: &str| {
+            entry
+                .attrs
+                .get(attribute)
+                .and_then(|values| values.first())
+                .filter(|value| !value.is_empty())
+                .cloned()
+        };
+
+        Ok(Some(DirectoryUser {
+            username: username.to_string(),
+            display_name: first(&self.config.display_name_attribute),
+            email: first(&self.config.email_attribute),
+            groups: entry
+                .attrs
+                .get(&self.config.group_attribute)
+                .cloned()
+                .unwrap_or_default(),
+            dn: entry.dn,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::test_directory::TestDirectory, *};
+
+    fn config(url: String) -> LdapDirectoryConfig {
+        serde_yaml::from_str(&format!(
+            r#"
+url: "{}"
+bind_dn: "cn=service,dc=example,dc=com"
+bind_password: "service-secret"
+base_dn: "ou=people,dc=example,dc=com"
+group_roles:
+    "cn=admins,ou=groups,dc=example,dc=com": "ROLE_ADMIN"
+"#,
+            url
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bind_search_and_map_groups() {
+        let directory = TestDirectory::start().await;
+        let config = config(directory.url());
+
+        let user = LdapAuthenticator::new(&config)
+            .authenticate("alice", "alice-secret")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.dn, "uid=alice,ou=people,dc=example,dc=com");
+        assert_eq!(user.display_name.as_deref(), Some("Alice Liddell"));
+        assert_eq!(user.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(config.map_roles(&user.groups), vec!["ROLE_ADMIN"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_and_empty_password() {
+        let directory = TestDirectory::start().await;
+        let config = config(directory.url());
+        let authenticator = LdapAuthenticator::new(&config);
+
+        assert!(matches!(
+            authenticator.authenticate("alice", "wrong").await,
+            Err(LdapAuthError::InvalidCredentials)
+        ));
+        assert!(matches!(
+            authenticator.authenticate("alice", "").await,
+            Err(LdapAuthError::InvalidCredentials)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_and_filter_injection() {
+        let directory = TestDirectory::start().await;
+        let config = config(directory.url());
+        let authenticator = LdapAuthenticator::new(&config);
+
+        assert!(authenticator
+            .authenticate("nobody", "secret")
+            .await
+            .unwrap()
+            .is_none());
+        // 登录名中的过滤器元字符会被转义，不能匹配到其他用户
+        assert!(authenticator
+            .authenticate("*", "alice-secret")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_service_account_is_unavailable() {
+        let directory = TestDirectory::start().await;
+        let mut config = config(directory.url());
+        config.bind_password = "wrong".to_string();
+
+        assert!(matches!(
+            LdapAuthenticator::new(&config)
+                .authenticate("alice", "alice-secret")
+                .await,
+            Err(LdapAuthError::Unavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod authenticator;
+#[cfg(test)]
+mod test_directory;
+
+pub use authenticator::{DirectoryUser, LdapAuthError, LdapAuthenticator};
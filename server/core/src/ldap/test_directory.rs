@@ -0,0 +1,329 @@
+//! 测试用的本地 LDAP 服务，仅实现简单绑定、搜索（与/或/非/相等/存在过滤器）和解绑。
+
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_ENUMERATED: u8 = 0x0A;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+
+const OP_BIND_REQUEST: u8 = 0x60;
+const OP_BIND_RESPONSE: u8 = 0x61;
+const OP_UNBIND_REQUEST: u8 = 0x42;
+const OP_SEARCH_REQUEST: u8 = 0x63;
+const OP_SEARCH_RESULT_ENTRY: u8 = 0x64;
+const OP_SEARCH_RESULT_DONE: u8 = 0x65;
+
+const FILTER_AND: u8 = 0xA0;
+const FILTER_OR: u8 = 0xA1;
+const FILTER_NOT: u8 = 0xA2;
+const FILTER_EQUALITY: u8 = 0xA3;
+const FILTER_PRESENT: u8 = 0x87;
+
+const RC_SUCCESS: u8 = 0;
+const RC_INSUFFICIENT_ACCESS: u8 = 50;
+const RC_INVALID_CREDENTIALS: u8 = 49;
+
+struct Entry {
+    dn: String,
+    password: String,
+    /// 属性名统一为小写
+    attributes: HashMap<String, Vec<String>>,
+}
+
+pub struct TestDirectory {
+    addr: SocketAddr,
+}
+
+impl TestDirectory {
+    /// 启动服务，目录中包含服务账号 `cn=service,dc=example,dc=com` 与用户 alice、bob
+    pub async fn start() -> Self {
+        let entries = Arc::new(vec![
+            entry(
+                "cn=service,dc=example,dc=com",
+                "service-secret",
+                &[("objectClass", &["top", "applicationProcess"])],
+            ),
+            entry(
+                "uid=alice,ou=people,dc=example,dc=com",
+                "alice-secret",
+                &[
+                    ("objectClass", &["top", "person", "inetOrgPerson"]),
+                    ("uid", &["alice"]),
+                    ("displayName", &["Alice Liddell"]),
+                    ("mail", &["alice@example.com"]),
+                    ("memberOf", &["CN=Admins,OU=Groups,DC=example,DC=com"]),
+                ],
+            ),
+            entry(
+                "uid=bob,ou=people,dc=example,dc=com",
+                "bob-secret",
+                &[
+                    ("objectClass", &["top", "person", "inetOrgPerson"]),
+                    ("uid", &["bob"]),
+                    ("memberOf", &["cn=staff,ou=groups,dc=example,dc=com"]),
+                ],
+            ),
+        ]);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let entries = entries.clone();
+                tokio::spawn(async move {
+                    let _ = serve(stream, &entries).await;
+                });
+            }
+        });
+
+        Self { addr }
+    }
+
+    pub fn url(&self) -> String {
+        format!("ldap://{}", self.addr)
+    }
+}
+
+fn entry(dn: &str, password: &str, attributes: &[(&str, &[&str])]) -> Entry {
+    Entry {
+        dn: dn.to_string(),
+        password: password.to_string(),
+        attributes: attributes
+            .iter()
+            .map(|(name, values)| {
+                (
+                    name.to_lowercase(),
+                    values.iter().map(|v| v.to_string()).collect(),
+                )
+            })
+            .collect(),
+    }
+}
+
+async fn serve(mut stream: TcpStream, entries: &[Entry]) -> std::io::Result<()> {
+    let mut bound_dn: Option<String> = None;
+
+    loop {
+        let message = read_message(&mut stream).await?;
+        let (_, body, _) = read_tlv(&message).ok_or_else(invalid)?;
+        let (_, id, rest) = read_tlv(body).ok_or_else(invalid)?;
+        let (op, request, _) = read_tlv(rest).ok_or_else(invalid)?;
+
+        match op {
+            OP_BIND_REQUEST => {
+                let (_, _version, rest) = read_tlv(request).ok_or_else(invalid)?;
+                let (_, name, rest) = read_tlv(rest).ok_or_else(invalid)?;
+                let (_, password, _) = read_tlv(rest).ok_or_else(invalid)?;
+                let name = String::from_utf8_lossy(name).to_string();
+
+                let valid = !password.is_empty()
+                    && entries.iter().any(|entry| {
+                        entry.dn.eq_ignore_ascii_case(&name)
+                            && entry.password.as_bytes() == password
+                    });
+                let rc = if valid {
+                    bound_dn = Some(name);
+                    RC_SUCCESS
+                } else {
+                    bound_dn = None;
+                    RC_INVALID_CREDENTIALS
+                };
+                write_message(&mut stream, id, &ldap_result(OP_BIND_RESPONSE, rc)).await?;
+            },
+            OP_SEARCH_REQUEST => {
+                let (_, base, rest) = read_tlv(request).ok_or_else(invalid)?;
+                // scope、derefAliases、sizeLimit、timeLimit、typesOnly
+                let mut rest = rest;
+                for _ in 0..5 {
+                    rest = read_tlv(rest).ok_or_else(invalid)?.2;
+                }
+                let (filter_tag, filter, rest) = read_tlv(rest).ok_or_else(invalid)?;
+                let (_, requested, _) = read_tlv(rest).ok_or_else(invalid)?;
+                let requested: Vec<String> = read_all(requested)
+                    .iter()
+                    .map(|(_, value)| String::from_utf8_lossy(value).to_lowercase())
+                    .collect();
+
+                if bound_dn.as_deref() != Some("cn=service,dc=example,dc=com") {
+                    write_message(
+                        &mut stream,
+                        id,
+                        &ldap_result(OP_SEARCH_RESULT_DONE, RC_INSUFFICIENT_ACCESS),
+                    )
+                    .await?;
+                    continue;
+                }
+
+                let base = String::from_utf8_lossy(base).to_lowercase();
+                for entry in entries.iter().filter(|entry| {
+                    entry.dn.to_lowercase().ends_with(&base)
+                        && matches_filter(entry, filter_tag, filter)
+                }) {
+                    write_message(&mut stream, id, &search_entry(entry, &requested)).await?;
+                }
+                write_message(
+                    &mut stream,
+                    id,
+                    &ldap_result(OP_SEARCH_RESULT_DONE, RC_SUCCESS),
+                )
+                .await?;
+            },
+            OP_UNBIND_REQUEST => return Ok(()),
+            _ => return Err(invalid()),
+        }
+    }
+}
+
+fn matches_filter(entry: &Entry, tag: u8, content: &[u8]) -> bool {
+    match tag {
+        FILTER_AND => read_all(content)
+            .iter()
+            .all(|(tag, content)| matches_filter(entry, *tag, content)),
+        FILTER_OR => read_all(content)
+            .iter()
+            .any(|(tag, content)| matches_filter(entry, *tag, content)),
+        FILTER_NOT => read_tlv(content)
+            .map(|(tag, content, _)| !matches_filter(entry, tag, content))
+            .unwrap_or(false),
+        FILTER_EQUALITY => {
+            let values = read_all(content);
+            match values.as_slice() {
+                [(_, name), (_, value)] => {
+                    let name = String::from_utf8_lossy(name).to_lowercase();
+                    let value = String::from_utf8_lossy(value);
+                    entry
+                        .attributes
+                        .get(&name)
+                        .is_some_and(|values| values.iter().any(|v| v.eq_ignore_ascii_case(&value)))
+                },
+                _ => false,
+            }
+        },
+        FILTER_PRESENT => entry
+            .attributes
+            .contains_key(&String::from_utf8_lossy(content).to_lowercase()),
+        _ => false,
+    }
+}
+
+fn search_entry(entry: &Entry, requested: &[String]) -> Vec<u8> {
+    let attributes: Vec<u8> = entry
+        .attributes
+        .iter()
+        .filter(|(name, _)| requested.is_empty() || requested.contains(name))
+        .flat_map(|(name, values)| {
+            // 返回时使用客户端请求的属性名写法
+            let values: Vec<u8> = values.iter().flat_map(|v| octet_string(v)).collect();
+            let mut attribute = octet_string(&original_name(name));
+            attribute.extend(tlv(TAG_SET, &values));
+            tlv(TAG_SEQUENCE, &attribute)
+        })
+        .collect();
+
+    let mut content = octet_string(&entry.dn);
+    content.extend(tlv(TAG_SEQUENCE, &attributes));
+    tlv(OP_SEARCH_RESULT_ENTRY, &content)
+}
+
+fn original_name(name: &str) -> String {
+    match name {
+        "displayname" => "displayName".to_string(),
+        "memberof" => "memberOf".to_string(),
+        "objectclass" => "objectClass".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+fn ldap_result(op: u8, rc: u8) -> Vec<u8> {
+    let mut content = tlv(TAG_ENUMERATED, &[rc]);
+    content.extend(octet_string(""));
+    content.extend(octet_string(""));
+    tlv(op, &content)
+}
+
+async fn write_message(stream: &mut TcpStream, id: &[u8], op: &[u8]) -> std::io::Result<()> {
+    let mut content = tlv(TAG_INTEGER, id);
+    content.extend_from_slice(op);
+    stream.write_all(&tlv(TAG_SEQUENCE, &content)).await
+}
+
+async fn read_message(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    let mut message = header.to_vec();
+
+    let len = if header[1] & 0x80 == 0 {
+        header[1] as usize
+    } else {
+        let mut len_bytes = vec![0u8; (header[1] & 0x7F) as usize];
+        stream.read_exact(&mut len_bytes).await?;
+        message.extend_from_slice(&len_bytes);
+        len_bytes
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize)
+    };
+
+    let mut content = vec![0u8; len];
+    stream.read_exact(&mut content).await?;
+    message.extend(content);
+    Ok(message)
+}
+
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        let len = rest
+            .get(..count)?
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    let content = rest.get(..len)?;
+    Some((tag, content, &rest[len..]))
+}
+
+fn read_all(mut input: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut items = Vec::new();
+    while let Some((tag, content, rest)) = read_tlv(input) {
+        items.push((tag, content));
+        input = rest;
+    }
+    items
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        encoded.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|&b| b == 0)
+            .collect();
+        encoded.push(0x80 | bytes.len() as u8);
+        encoded.extend(bytes);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+fn octet_string(value: &str) -> Vec<u8> {
+    tlv(TAG_OCTET_STRING, value.as_bytes())
+}
+
+fn invalid() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed LDAP message")
+}
@@ -1,2 +1,4 @@
+pub mod ldap;
+pub mod oauth;
 pub mod sign;
 pub mod web;
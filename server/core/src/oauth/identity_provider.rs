@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+/// 外部身份提供方返回的用户身份
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalIdentity {
+    /// 提供方名称
+    pub provider: String,
+    /// 提供方内的用户唯一标识（OIDC `sub`）
+    pub subject: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    pub preferred_username: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum OAuthError {
+    #[error("Provider discovery failed: {0}")]
+    Discovery(String),
+    #[error("Authorization code exchange failed: {0}")]
+    TokenExchange(String),
+    #[error("Invalid id_token: {0}")]
+    InvalidIdToken(String),
+}
+
+/// 外部身份提供方
+///
+/// 授权码流程分两步：`authorization_url` 生成跳转地址，提供方回调后由
+/// `exchange_code` 用授权码换取并校验用户身份。state、nonce 与 PKCE 校验码由调用方
+/// 生成并保存，两步之间保持一致。
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    /// 提供方名称
+    fn name(&self) -> &str;
+
+    /// 生成跳转到提供方的授权地址
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, OAuthError>;
+
+    /// 使用授权码换取令牌，校验后返回用户身份
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<ExternalIdentity, OAuthError>;
+}
@@ -0,0 +1,30 @@
+mod identity_provider;
+mod oidc_provider;
+mod pkce;
+mod state_store;
+
+pub use identity_provider::{ExternalIdentity, IdentityProvider, OAuthError};
+pub use oidc_provider::{OidcProvider, ProviderMetadata};
+pub use pkce::{generate_random_token, PkceChallenge};
+pub use state_store::{OAuthState, OAuthStateStore};
+
+use std::{collections::HashMap, sync::Arc};
+
+use once_cell::sync::Lazy;
+use tokio::sync::RwLock;
+
+static IDENTITY_PROVIDERS: Lazy<RwLock<HashMap<String, Arc<dyn IdentityProvider>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 注册身份提供方，同名提供方会被替换
+pub async fn register_provider(provider: Arc<dyn IdentityProvider>) {
+    IDENTITY_PROVIDERS
+        .write()
+        .await
+        .insert(provider.name().to_string(), provider);
+}
+
+/// 按名称获取已注册的身份提供方
+pub async fn get_provider(name: &str) -> Option<Arc<dyn IdentityProvider>> {
+    IDENTITY_PROVIDERS.read().await.get(name).cloned()
+}
@@ -0,0 +1,431 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use reqwest::{Client, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use server_config::OidcProviderConfig;
+use tokio::sync::RwLock;
+
+use super::{
+    identity_provider::{ExternalIdentity, IdentityProvider, OAuthError},
+    pkce::PkceChallenge,
+};
+
+/// 允许的 id_token 签名算法，拒绝 `none` 与对称算法
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// OIDC 发现文档中使用到的字段
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+/// 通用 OpenID Connect 提供方
+///
+/// 首次使用时读取发现文档与 JWKS 并缓存，id_token 的 kid 不在缓存中时重新拉取一次
+/// JWKS，以兼容提供方的密钥轮换。
+pub struct OidcProvider {
+    config: OidcProviderConfig,
+    client: Client,
+    metadata: RwLock<Option<Arc<ProviderMetadata>>>,
+    jwks: RwLock<Option<Arc<JwkSet>>>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcProviderConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    /// 获取发现文档，并校验其中的签发方与配置一致
+    pub async fn metadata(&self) -> Result<Arc<ProviderMetadata>, OAuthError> {
+        if let Some(metadata) = self.metadata.read().await.clone() {
+            return Ok(metadata);
+        }
+
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata =
+            self.get_json(&url).await.map_err(OAuthError::Discovery)?;
+        if metadata.issuer.trim_end_matches('/') != self.config.issuer.trim_end_matches('/') {
+            return Err(OAuthError::Discovery(format!(
+                "issuer mismatch: expected {}, got {}",
+                self.config.issuer, metadata.issuer
+            )));
+        }
+
+        let metadata = Arc::new(metadata);
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn jwks(&self, refresh: bool) -> Result<Arc<JwkSet>, OAuthError> {
+        if !refresh {
+            if let Some(jwks) = self.jwks.read().await.clone() {
+                return Ok(jwks);
+            }
+        }
+
+        let metadata = self.metadata().await?;
+        let jwks: JwkSet = self
+            .get_json(&metadata.jwks_uri)
+            .await
+            .map_err(OAuthError::Discovery)?;
+        let jwks = Arc::new(jwks);
+        *self.jwks.write().await = Some(jwks.clone());
+        Ok(jwks)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", url, response.status()));
+        }
+        response.json::<T>().await.map_err(|e| e.to_string())
+    }
+
+    /// 按 kid 查找验签密钥，未携带 kid 时仅在 JWKS 只有一个密钥时使用该密钥
+    async fn decoding_key(&self, kid: Option<&str>) -> Result<DecodingKey, OAuthError> {
+        for refresh in [false, true] {
+            let jwks = self.jwks(refresh).await?;
+            let jwk = match kid {
+                Some(kid) => jwks.find(kid),
+                None if jwks.keys.len() == 1 => jwks.keys.first(),
+                None => None,
+            };
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk)
+                    .map_err(|e| OAuthError::InvalidIdToken(e.to_string()));
+            }
+        }
+        Err(OAuthError::InvalidIdToken(format!(
+            "no matching key for kid {:?}",
+            kid
+        )))
+    }
+
+    /// 校验 id_token 的签名、签发方、受众、有效期与 nonce
+    async fn validate_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, OAuthError> {
+        let header =
+            decode_header(id_token).map_err(|e| OAuthError::InvalidIdToken(e.to_string()))?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(OAuthError::InvalidIdToken(format!(
+                "unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+
+        let metadata = self.metadata().await?;
+        let key = self.decoding_key(header.kid.as_deref()).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = 60;
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| OAuthError::InvalidIdToken(e.to_string()))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OAuthError::InvalidIdToken("nonce mismatch".to_string()));
+        }
+
+        Ok(claims)
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, OAuthError> {
+        let metadata = self.metadata().await?;
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| OAuthError::Discovery(e.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", &self.config.scopes.join(" "))
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", PkceChallenge::METHOD);
+        Ok(url.into())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<ExternalIdentity, OAuthError> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(client_secret) = &self.config.client_secret {
+            form.push(("client_secret", client_secret.as_str()));
+        }
+
+        let response = self
+            .client
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| OAuthError::TokenExchange(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(OAuthError::TokenExchange(format!("{}: {}", status, body)));
+        }
+        let token = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|e| OAuthError::TokenExchange(e.to_string()))?;
+        let id_token = token
+            .id_token
+            .ok_or_else(|| OAuthError::TokenExchange("missing id_token".to_string()))?;
+
+        let claims = self.validate_id_token(&id_token, nonce).await?;
+
+        Ok(ExternalIdentity {
+            provider: self.config.name.clone(),
+            subject: claims.sub,
+            email: claims.email,
+            name: claims.name,
+            preferred_username: claims.preferred_username,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use axum::{
+        extract::State,
+        routing::{get, post},
+        Form, Json, Router,
+    };
+    use chrono::Utc;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::{json, Value};
+    use server_config::JwtAlgorithm;
+
+    use super::*;
+    use crate::web::jwk::public_pem_to_jwk;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt");
+    const CLIENT_ID: &str = "soybean-admin";
+
+    /// 本地模拟的签发方，按测试设置的 nonce 与 PKCE 挑战码签发 id_token
+    #[derive(Default)]
+    struct MockIssuer {
+        issuer: String,
+        nonce: String,
+        code_challenge: String,
+        kid: String,
+    }
+
+    type SharedIssuer = Arc<Mutex<MockIssuer>>;
+
+    async fn discovery(State(mock): State<SharedIssuer>) -> Json<Value> {
+        let issuer = mock.lock().unwrap().issuer.clone();
+        Json(json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+        }))
+    }
+
+    async fn jwks() -> Json<JwkSet> {
+        let pem = std::fs::read(format!("{}/rsa.pub.pem", FIXTURES)).unwrap();
+        let jwk = public_pem_to_jwk(&pem, "mock-key", JwtAlgorithm::RS256).unwrap();
+        Json(JwkSet { keys: vec![jwk] })
+    }
+
+    async fn token(
+        State(mock): State<SharedIssuer>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<Value>, (http::StatusCode, &'static str)> {
+        let mock = mock.lock().unwrap();
+        let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+        if form.get("code").map(String::as_str) != Some("auth-code")
+            || form.get("client_id").map(String::as_str) != Some(CLIENT_ID)
+            || PkceChallenge::from_verifier(verifier).challenge != mock.code_challenge
+        {
+            return Err((http::StatusCode::BAD_REQUEST, "invalid_grant"));
+        }
+
+        let now = Utc::now().timestamp();
+        let claims = json!({
+            "iss": mock.issuer,
+            "aud": CLIENT_ID,
+            "sub": "external-user-1",
+            "exp": now + 300,
+            "iat": now,
+            "nonce": mock.nonce,
+            "email": "alice@example.com",
+            "preferred_username": "alice",
+        });
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(mock.kid.clone());
+        let pem = std::fs::read(format!("{}/rsa.pem", FIXTURES)).unwrap();
+        let id_token = encode(&header, &claims, &EncodingKey::from_rsa_pem(&pem).unwrap()).unwrap();
+        Ok(Json(json!({
+            "access_token": "access",
+            "token_type": "Bearer",
+            "id_token": id_token,
+        })))
+    }
+
+    async fn start_mock_issuer() -> (OidcProvider, SharedIssuer) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let mock = Arc::new(Mutex::new(MockIssuer {
+            issuer: issuer.clone(),
+            kid: "mock-key".to_string(),
+            ..Default::default()
+        }));
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(mock.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let provider = OidcProvider::new(OidcProviderConfig {
+            name: "mock".to_string(),
+            issuer,
+            client_id: CLIENT_ID.to_string(),
+            client_secret: None,
+            redirect_uri: "http://localhost:9527/oauth/mock/callback".to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+            domain: "built-in".to_string(),
+            auto_provision: false,
+        });
+        (provider, mock)
+    }
+
+    /// 模拟浏览器完成授权：记录授权地址中的 nonce 与挑战码
+    async fn authorize(provider: &OidcProvider, mock: &SharedIssuer) -> PkceChallenge {
+        let pkce = PkceChallenge::generate();
+        let url = provider
+            .authorization_url("state-1", "nonce-1", &pkce.challenge)
+            .await
+            .unwrap();
+        let url = Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(params["state"], "state-1");
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["scope"], "openid email");
+
+        let mut mock = mock.lock().unwrap();
+        mock.nonce = params["nonce"].clone();
+        mock.code_challenge = params["code_challenge"].clone();
+        pkce
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow() {
+        let (provider, mock) = start_mock_issuer().await;
+        let pkce = authorize(&provider, &mock).await;
+
+        let identity = provider
+            .exchange_code("auth-code", &pkce.verifier, "nonce-1")
+            .await
+            .unwrap();
+        assert_eq!(identity.provider, "mock");
+        assert_eq!(identity.subject, "external-user-1");
+        assert_eq!(identity.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(identity.preferred_username.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_nonce_and_pkce_mismatch() {
+        let (provider, mock) = start_mock_issuer().await;
+        let pkce = authorize(&provider, &mock).await;
+
+        let result = provider
+            .exchange_code("auth-code", &pkce.verifier, "other-nonce")
+            .await;
+        assert!(matches!(result, Err(OAuthError::InvalidIdToken(_))));
+
+        let result = provider
+            .exchange_code("auth-code", "wrong-verifier", "nonce-1")
+            .await;
+        assert!(matches!(result, Err(OAuthError::TokenExchange(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_kid() {
+        let (provider, mock) = start_mock_issuer().await;
+        let pkce = authorize(&provider, &mock).await;
+        mock.lock().unwrap().kid = "rotated-away".to_string();
+
+        let result = provider
+            .exchange_code("auth-code", &pkce.verifier, "nonce-1")
+            .await;
+        assert!(matches!(result, Err(OAuthError::InvalidIdToken(_))));
+    }
+}
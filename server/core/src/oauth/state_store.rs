@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::web::ttl_store::TtlStore;
+
+const OAUTH_STATE_PREFIX: &str = "oauth_state";
+
+/// 授权请求发起时保存的上下文，回调时按 state 取回
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthState {
+    pub provider: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// 授权请求 state 存储
+///
+/// 配置了 Redis 时使用 Redis 存储，否则使用进程内缓存。授权上下文以 JSON 保存，state 为随机 ULID，
+/// 只能取回一次，防止回调被重放。
+#[derive(Clone)]
+pub struct OAuthStateStore {
+    store: TtlStore,
+}
+
+impl OAuthStateStore {
+    pub fn new(store: TtlStore) -> Self {
+        Self { store }
+    }
+
+    /// 根据是否配置了全局 Redis 选择存储实现
+    pub async fn current() -> Self {
+        Self::new(TtlStore::current().await)
+    }
+
+    fn get_key(token: &str) -> String {
+        format!("{}:{}", OAUTH_STATE_PREFIX, token)
+    }
+
+    /// 保存授权上下文并返回 state
+    pub async fn issue(&self, state: &OAuthState, ttl: Duration) -> String {
+        let token = Ulid::new().to_string();
+        match serde_json::to_string(state) {
+            Ok(value) => {
+                if let Err(e) = self.store.set(&Self::get_key(&token), &value, ttl).await {
+                    tracing::error!("Failed to store OAuth state: {}", e);
+                }
+            },
+            Err(e) => tracing::error!("Failed to serialize OAuth state: {}", e),
+        }
+        token
+    }
+
+    /// 取回并删除未过期的授权上下文
+    pub async fn take(&self, token: &str) -> Option<OAuthState> {
+        match self.store.take(&Self::get_key(token)).await {
+            Ok(value) => value.and_then(|value| serde_json::from_str(&value).ok()),
+            Err(e) => {
+                tracing::error!("Failed to load OAuth state: {}", e);
+                None
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::web::ttl_store::MemoryTtlStore;
+
+    #[tokio::test]
+    async fn test_state_can_only_be_taken_once() {
+        let store = OAuthStateStore::new(TtlStore::Memory(Arc::new(MemoryTtlStore::new())));
+        let state = OAuthState {
+            provider: "keycloak".to_string(),
+            nonce: "nonce".to_string(),
+            pkce_verifier: "verifier".to_string(),
+        };
+
+        let token = store.issue(&state, Duration::from_secs(60)).await;
+        assert_eq!(store.take(&token).await, Some(state));
+        assert_eq!(store.take(&token).await, None);
+    }
+}
@@ -257,7 +257,7 @@ mod tests {
             .as_millis() as i64;
         let nonce = format!("nonce_{}", timestamp);
 
-        let mut params = vec![
+        let mut params = [
             ("AccessKeyId".to_string(), "test-access-key".to_string()),
             ("param1".to_string(), "value1".to_string()),
             ("param2".to_string(), "value2".to_string()),
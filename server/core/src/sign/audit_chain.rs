@@ -0,0 +1,191 @@
+use chrono::{NaiveDateTime, Timelike};
+use ring::{
+    digest,
+    error::KeyRejected,
+    signature::{self, Ed25519KeyPair, KeyPair},
+};
+use serde_json::Value;
+
+/// Previous hash of the first record in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Serializes a JSON value with object keys sorted and integral floats written as integers.
+///
+/// The result does not depend on key order or on how a JSON store normalizes numbers,
+/// so a record hashes the same before and after a round trip through the database.
+pub fn canonical_json(value: &Value) -> String {
+    let mut output = String::new();
+    write_canonical(value, &mut output);
+    output
+}
+
+fn write_canonical(value: &Value, output: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            output.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical(value, output);
+            }
+            output.push('}');
+        },
+        Value::Array(values) => {
+            output.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(value, output);
+            }
+            output.push(']');
+        },
+        Value::Number(number) => match number.as_f64() {
+            Some(float)
+                if !number.is_i64()
+                    && !number.is_u64()
+                    && float.fract() == 0.0
+                    && float.abs() < (1u64 << 53) as f64 =>
+            {
+                output.push_str(&(float as i64).to_string())
+            },
+            _ => output.push_str(&number.to_string()),
+        },
+        other => output.push_str(&other.to_string()),
+    }
+}
+
+/// Computes the hash of a chained record as hex-encoded `SHA-256(prev_hash "\n" content)`,
+/// where `content` is the canonical JSON of the record without its chain hashes.
+pub fn chain_hash(prev_hash: &str, content: &Value) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    context.update(prev_hash.as_bytes());
+    context.update(b"\n");
+    context.update(canonical_json(content).as_bytes());
+    hex::encode(context.finish())
+}
+
+/// Truncates a timestamp to the microsecond precision kept by the database,
+/// so the hashed value matches the stored one.
+pub fn truncate_to_micros(time: NaiveDateTime) -> NaiveDateTime {
+    time.with_nanosecond(time.nanosecond() / 1_000 * 1_000)
+        .unwrap_or(time)
+}
+
+/// The message signed by a checkpoint over the head of a chain.
+pub fn checkpoint_message(
+    log_type: &str,
+    domain: &str,
+    chain_seq: i64,
+    hash: &str,
+    signed_at: &NaiveDateTime,
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        log_type,
+        domain,
+        chain_seq,
+        hash,
+        signed_at.format("%Y-%m-%dT%H:%M:%S%.6f")
+    )
+}
+
+/// Signs chain checkpoints with an Ed25519 key.
+pub struct CheckpointSigner {
+    key_pair: Ed25519KeyPair,
+    public_key: String,
+}
+
+impl CheckpointSigner {
+    /// Loads the key from a PKCS#8 document, such as the DER output of
+    /// `openssl genpkey -algorithm ed25519 -outform DER`.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, KeyRejected> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)?;
+        let public_key = hex::encode(key_pair.public_key().as_ref());
+        Ok(Self {
+            key_pair,
+            public_key,
+        })
+    }
+
+    /// Hex-encoded public key.
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// Hex-encoded signature of `message`.
+    pub fn sign(&self, message: &str) -> String {
+        hex::encode(self.key_pair.sign(message.as_bytes()).as_ref())
+    }
+
+    /// Verifies a hex-encoded signature made with this key.
+    pub fn verify(&self, message: &str, signature: &str) -> bool {
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        signature::UnparsedPublicKey::new(&signature::ED25519, self.key_pair.public_key())
+            .verify(message.as_bytes(), &signature)
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use ring::rand::SystemRandom;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_json() {
+        let a = json!({ "b": 1, "a": { "d": [1.0, "x"], "c": null } });
+        let b = json!({ "a": { "c": null, "d": [1, "x"] }, "b": 1 });
+
+        assert_eq!(canonical_json(&a), r#"{"a":{"c":null,"d":[1,"x"]},"b":1}"#);
+        assert_eq!(canonical_json(&a), canonical_json(&b));
+        assert_eq!(canonical_json(&json!(1.5)), "1.5");
+    }
+
+    #[test]
+    fn test_chain_hash() {
+        let first = chain_hash(GENESIS_HASH, &json!({ "id": "1" }));
+        let second = chain_hash(&first, &json!({ "id": "2" }));
+
+        assert_eq!(first.len(), 64);
+        assert_eq!(first, chain_hash(GENESIS_HASH, &json!({ "id": "1" })));
+        assert_ne!(second, chain_hash(GENESIS_HASH, &json!({ "id": "2" })));
+    }
+
+    #[test]
+    fn test_truncate_to_micros() {
+        let time = NaiveDate::from_ymd_opt(2024, 12, 4)
+            .unwrap()
+            .and_hms_nano_opt(8, 0, 0, 123_456_789)
+            .unwrap();
+
+        assert_eq!(truncate_to_micros(time).nanosecond(), 123_456_000);
+    }
+
+    #[test]
+    fn test_checkpoint_signer() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let signer = CheckpointSigner::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let signed_at = NaiveDate::from_ymd_opt(2024, 12, 4)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let message = checkpoint_message("operation", "built-in", 42, GENESIS_HASH, &signed_at);
+        let signature = signer.sign(&message);
+
+        assert!(signer.verify(&message, &signature));
+        assert!(!signer.verify(&message.replace("42", "43"), &signature));
+        assert!(!signer.verify(&message, "not-hex"));
+    }
+}
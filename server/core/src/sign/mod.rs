@@ -1,5 +1,6 @@
 mod api_key;
 mod api_key_middleware;
+mod audit_chain;
 mod memory_nonce_store;
 mod nonce_store;
 mod redis_nonce_store;
@@ -11,6 +12,10 @@ pub use api_key_middleware::{
     api_key_middleware, protect_route, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
     SimpleApiKeyConfig,
 };
+pub use audit_chain::{
+    canonical_json, chain_hash, checkpoint_message, truncate_to_micros, CheckpointSigner,
+    GENESIS_HASH,
+};
 pub use memory_nonce_store::{create_memory_nonce_store_factory, MemoryNonceStore};
 pub use nonce_store::{NonceStore, NonceStoreFactory};
 pub use redis_nonce_store::{create_redis_nonce_store_factory, RedisNonceStore};
@@ -24,10 +29,12 @@ pub enum ValidatorType {
     Complex,
 }
 
-static API_KEY_VALIDATORS: Lazy<(
+type ApiKeyValidators = (
     Arc<RwLock<SimpleApiKeyValidator>>,
     Arc<RwLock<ComplexApiKeyValidator>>,
-)> = Lazy::new(|| {
+);
+
+static API_KEY_VALIDATORS: Lazy<ApiKeyValidators> = Lazy::new(|| {
     (
         Arc::new(RwLock::new(SimpleApiKeyValidator::new())),
         Arc::new(RwLock::new(ComplexApiKeyValidator::new(None))),
@@ -1,4 +1,3 @@
-use async_trait::async_trait;
 use axum::{
     extract::{FromRequest, Request},
     http::StatusCode,
@@ -67,6 +66,14 @@ impl Claims {
     pub fn set_jti(&mut self, jti: String) {
         self.jti = Some(jti);
     }
+
+    pub fn jti(&self) -> Option<&str> {
+        self.jti.as_deref()
+    }
+
+    pub fn exp(&self) -> Option<usize> {
+        self.exp
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -76,6 +83,10 @@ pub struct User {
     role: Vec<String>,
     domain: String,
     org: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<usize>,
 }
 
 impl User {
@@ -94,6 +105,16 @@ impl User {
     pub fn domain(&self) -> String {
         self.domain.to_string()
     }
+
+    /// 当前访问令牌的 jti
+    pub fn jti(&self) -> Option<String> {
+        self.jti.clone()
+    }
+
+    /// 当前访问令牌的过期时间（Unix 时间戳，秒）
+    pub fn exp(&self) -> Option<usize> {
+        self.exp
+    }
 }
 
 impl From<Claims> for User {
@@ -104,26 +125,22 @@ impl From<Claims> for User {
             role: claims.role,
             domain: claims.domain,
             org: claims.org,
+            jti: claims.jti,
+            exp: claims.exp,
         }
     }
 }
 
-#[async_trait]
 impl<S> FromRequest<S> for User
 where
     S: Send + Sync + 'static,
 {
     type Rejection = Res<String>;
 
-    fn from_request(
-        req: Request,
-        _state: &S,
-    ) -> impl std::future::Future<Output = Result<Self, Self::Rejection>> + Send {
-        async move {
-            req.extensions()
-                .get::<User>()
-                .cloned()
-                .ok_or_else(|| Res::new_error(StatusCode::UNAUTHORIZED.as_u16(), "Unauthorized"))
-        }
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        req.extensions()
+            .get::<User>()
+            .cloned()
+            .ok_or_else(|| Res::new_error(StatusCode::UNAUTHORIZED.as_u16(), "Unauthorized"))
     }
 }
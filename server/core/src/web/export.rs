@@ -0,0 +1,284 @@
+//! 数据导出
+//!
+//! 将 JSON 记录逐行编码为 CSV、JSON Lines 或 XLSX，编码器不保留已输出的行，
+//! 可配合数据库游标流式导出任意数量的记录。
+
+use std::{io, pin::Pin};
+
+use axum::{
+    body::Body,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::web::{error::AppError, xlsx::XlsxWriter};
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            },
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// 逐行编码记录，只输出 `columns` 中的字段
+pub struct ExportEncoder {
+    columns: Vec<&'static str>,
+    inner: Encoder,
+}
+
+enum Encoder {
+    Csv { header_written: bool },
+    Jsonl,
+    Xlsx(Box<XlsxWriter>),
+}
+
+impl ExportEncoder {
+    pub fn new(format: ExportFormat, columns: &[&'static str]) -> Self {
+        let inner = match format {
+            ExportFormat::Csv => Encoder::Csv {
+                header_written: false,
+            },
+            ExportFormat::Jsonl => Encoder::Jsonl,
+            ExportFormat::Xlsx => Encoder::Xlsx(Box::new(XlsxWriter::new(columns))),
+        };
+        Self {
+            columns: columns.to_vec(),
+            inner,
+        }
+    }
+
+    /// 编码一条记录并追加到 `output`
+    pub fn row(&mut self, record: &Value, output: &mut Vec<u8>) -> Result<(), AppError> {
+        let cells: Vec<&Value> = self
+            .columns
+            .iter()
+            .map(|column| record.get(column).unwrap_or(&Value::Null))
+            .collect();
+
+        match &mut self.inner {
+            Encoder::Csv { header_written } => {
+                if !*header_written {
+                    write_csv_header(&self.columns, output);
+                    *header_written = true;
+                }
+                write_csv_row(&cells, output);
+            },
+            Encoder::Jsonl => {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .zip(cells)
+                    .map(|(column, cell)| (column.to_string(), cell.clone()))
+                    .collect();
+                serde_json::to_writer(&mut *output, &object).map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+                output.push(b'\n');
+            },
+            Encoder::Xlsx(writer) => output.extend(writer.row(&cells)?),
+        }
+        Ok(())
+    }
+
+    /// 结束编码，返回剩余的内容
+    pub fn finish(self) -> Result<Vec<u8>, AppError> {
+        let mut output = Vec::new();
+        match self.inner {
+            Encoder::Csv { header_written } => {
+                if !header_written {
+                    write_csv_header(&self.columns, &mut output);
+                }
+            },
+            Encoder::Jsonl => {},
+            Encoder::Xlsx(writer) => output = writer.finish()?,
+        }
+        Ok(output)
+    }
+}
+
+fn write_csv_header(columns: &[&str], output: &mut Vec<u8>) {
+    // BOM 使 Excel 按 UTF-8 打开
+    output.extend_from_slice(b"\xEF\xBB\xBF");
+    let header: Vec<Value> = columns
+        .iter()
+        .map(|column| Value::String(column.to_string()))
+        .collect();
+    write_csv_row(&header.iter().collect::<Vec<_>>(), output);
+}
+
+fn write_csv_row(cells: &[&Value], output: &mut Vec<u8>) {
+    for (index, cell) in cells.iter().enumerate() {
+        if index > 0 {
+            output.push(b',');
+        }
+        match cell {
+            Value::Null => {},
+            Value::Bool(_) | Value::Number(_) => output.extend(cell.to_string().as_bytes()),
+            Value::String(text) => write_csv_field(text, output),
+            other => write_csv_field(&other.to_string(), output),
+        }
+    }
+    output.extend_from_slice(b"\r\n");
+}
+
+fn write_csv_field(text: &str, output: &mut Vec<u8>) {
+    // 以公式字符开头的文本加单引号，避免在电子表格中被当作公式执行
+    let guard = text.starts_with(['=', '+', '-', '@', '\t', '\r']);
+    let quote = guard || text.contains([',', '"', '\n', '\r']);
+
+    if !quote {
+        output.extend_from_slice(text.as_bytes());
+        return;
+    }
+    output.push(b'"');
+    if guard {
+        output.push(b'\'');
+    }
+    output.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+    output.push(b'"');
+}
+
+/// 导出内容的数据流
+pub type ExportStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>>;
+
+/// 以附件形式返回的导出文件
+pub struct ExportFile {
+    /// 不含扩展名的文件名
+    pub filename: String,
+    pub format: ExportFormat,
+    pub stream: ExportStream,
+}
+
+impl IntoResponse for ExportFile {
+    fn into_response(self) -> Response {
+        let filename = format!("{}.{}", self.filename, self.format.extension());
+        let disposition = format!(
+            "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+            filename.replace(['"', '\\'], "_"),
+            urlencoding::encode(&filename)
+        );
+
+        let mut response = Body::from_stream(self.stream).into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(self.format.content_type()),
+        );
+        if let Ok(value) = HeaderValue::from_str(&disposition) {
+            headers.insert(header::CONTENT_DISPOSITION, value);
+        }
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use serde_json::json;
+
+    use super::*;
+
+    const COLUMNS: &[&str] = &["id", "description", "latency"];
+
+    fn encode(format: ExportFormat, records: &[Value]) -> Vec<u8> {
+        let mut encoder = ExportEncoder::new(format, COLUMNS);
+        let mut output = Vec::new();
+        for record in records {
+            encoder.row(record, &mut output).unwrap();
+        }
+        output.extend(encoder.finish().unwrap());
+        output
+    }
+
+    #[test]
+    fn test_csv_encoding() {
+        let output = encode(
+            ExportFormat::Csv,
+            &[
+                json!({ "id": "1", "description": "a,\"b\"", "latency": 12, "ignored": true }),
+                json!({ "id": "2", "description": "=HYPERLINK(\"x\")" }),
+            ],
+        );
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\u{FEFF}id,description,latency\r\n\
+             1,\"a,\"\"b\"\"\",12\r\n\
+             2,\"'=HYPERLINK(\"\"x\"\")\",\r\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_header_without_rows() {
+        let output = encode(ExportFormat::Csv, &[]);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\u{FEFF}id,description,latency\r\n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_encoding() {
+        let output = encode(
+            ExportFormat::Jsonl,
+            &[
+                json!({ "latency": 3, "id": "1", "extra": 1 }),
+                json!({ "id": "2" }),
+            ],
+        );
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"id\":\"1\",\"description\":null,\"latency\":3}\n\
+             {\"id\":\"2\",\"description\":null,\"latency\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_export_file_headers() {
+        let response = ExportFile {
+            filename: "operation-logs".to_string(),
+            format: ExportFormat::Xlsx,
+            stream: Box::pin(stream::empty()),
+        }
+        .into_response();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            ExportFormat::Xlsx.content_type()
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"operation-logs.xlsx\"; filename*=UTF-8''operation-logs.xlsx"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! 响应字段脱敏
+//!
+//! 按域与角色配置脱敏规则，[`FieldMaskLayer`] 在响应发出前改写 `Res<T>` 的 JSON，
+//! 对 `data` 中名称匹配的字段执行隐藏、部分遮盖或哈希。用户拥有的任一角色配置了规则
+//! 即对其生效，同一字段命中多条规则时采用最严格的方式。
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use futures::future::BoxFuture;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::{auth::User, error::AppError, res::Res};
+
+/// 规则缓存时长，修改规则时会立即清除本实例的缓存
+const RULE_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// 脱敏方式，按严格程度递增排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MaskStrategy {
+    /// 部分遮盖，如 `138****1234`
+    Partial,
+    /// 替换为 SHA-256 摘要，可比较是否相同但无法还原
+    Hash,
+    /// 从响应中移除
+    Hide,
+}
+
+/// 脱敏规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMaskRule {
+    /// 角色编码
+    pub role: String,
+    /// 生效的请求路径，为空或 `*` 时对全部路径生效，以 `/*` 结尾时按前缀匹配
+    pub resource: String,
+    /// 字段名，与响应 JSON 中的名称一致，如 `phoneNumber`
+    pub field: String,
+    pub strategy: MaskStrategy,
+}
+
+impl FieldMaskRule {
+    fn matches_path(&self, path: &str) -> bool {
+        match self.resource.trim() {
+            "" | "*" => true,
+            resource => match resource.strip_suffix("/*") {
+                Some(prefix) => {
+                    path == prefix
+                        || path
+                            .strip_prefix(prefix)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                },
+                None => path == resource,
+            },
+        }
+    }
+}
+
+/// 读取域内的脱敏规则
+#[async_trait]
+pub trait FieldMaskRuleLoader: Send + Sync {
+    async fn load_rules(&self, domain: &str) -> Result<Vec<FieldMaskRule>, AppError>;
+}
+
+static RULE_CACHE: Lazy<Cache<String, Arc<Vec<FieldMaskRule>>>> =
+    Lazy::new(|| Cache::builder().time_to_live(RULE_CACHE_TTL).build());
+
+/// 清除域的规则缓存，下次请求时重新读取
+pub fn invalidate_field_masks(domain: &str) {
+    RULE_CACHE.invalidate(domain);
+}
+
+async fn domain_rules(
+    loader: &dyn FieldMaskRuleLoader,
+    domain: &str,
+) -> Result<Arc<Vec<FieldMaskRule>>, AppError> {
+    if let Some(rules) = RULE_CACHE.get(domain) {
+        return Ok(rules);
+    }
+    let rules = Arc::new(loader.load_rules(domain).await?);
+    RULE_CACHE.insert(domain.to_string(), rules.clone());
+    Ok(rules)
+}
+
+/// 选出对角色与路径生效的规则，按字段合并为最严格的方式
+pub fn select_masks(
+    rules: &[FieldMaskRule],
+    roles: &[String],
+    path: &str,
+) -> HashMap<String, MaskStrategy> {
+    let mut masks: HashMap<String, MaskStrategy> = HashMap::new();
+    for rule in rules
+        .iter()
+        .filter(|rule| roles.contains(&rule.role) && rule.matches_path(path))
+    {
+        masks
+            .entry(rule.field.clone())
+            .and_modify(|strategy| *strategy = (*strategy).max(rule.strategy))
+            .or_insert(rule.strategy);
+    }
+    masks
+}
+
+/// 递归处理 JSON 中名称匹配的字段
+///
+/// 对象与数组无法部分遮盖或哈希，命中任一方式时均被移除；`null` 保持不变。
+pub fn apply_masks(value: &mut Value, masks: &HashMap<String, MaskStrategy>) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, value| match masks.get(key) {
+                Some(MaskStrategy::Hide) => false,
+                Some(_) => !(value.is_object() || value.is_array()),
+                None => true,
+            });
+            for (key, value) in map.iter_mut() {
+                match masks.get(key) {
+                    Some(strategy) => mask_scalar(value, *strategy),
+                    None => apply_masks(value, masks),
+                }
+            }
+        },
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| apply_masks(value, masks)),
+        _ => {},
+    }
+}
+
+fn mask_scalar(value: &mut Value, strategy: MaskStrategy) {
+    let text = match value {
+        Value::String(text) => std::mem::take(text),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => return,
+    };
+    *value = Value::String(match strategy {
+        MaskStrategy::Partial => mask_partial(&text),
+        MaskStrategy::Hash => hex::encode(digest(&SHA256, text.as_bytes())),
+        MaskStrategy::Hide => String::new(),
+    });
+}
+
+/// 部分遮盖：邮箱保留用户名首字符与域名，较长的值保留前 3 位与后 4 位，
+/// 较短的值保留首尾各 1 位
+pub fn mask_partial(text: &str) -> String {
+    if let Some((local, domain)) = text.split_once('@') {
+        let first: String = local.chars().take(1).collect();
+        return format!("{}****@{}", first, domain);
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let (head, tail) = match chars.len() {
+        len if len > 7 => (3, 4),
+        len if len > 2 => (1, 1),
+        _ => (0, 0),
+    };
+    let mut masked: String = chars[..head].iter().collect();
+    masked.push_str(&"*".repeat(chars.len() - head - tail));
+    masked.extend(&chars[chars.len() - tail..]);
+    masked
+}
+
+/// 响应字段脱敏层，需位于 JWT 认证之后以获取当前用户
+#[derive(Clone)]
+pub struct FieldMaskLayer {
+    loader: Arc<dyn FieldMaskRuleLoader>,
+}
+
+impl FieldMaskLayer {
+    pub fn new(loader: Arc<dyn FieldMaskRuleLoader>) -> Self {
+        Self { loader }
+    }
+}
+
+impl<S> Layer<S> for FieldMaskLayer {
+    type Service = FieldMaskMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FieldMaskMiddleware {
+            inner,
+            loader: self.loader.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FieldMaskMiddleware<S> {
+    inner: S,
+    loader: Arc<dyn FieldMaskRuleLoader>,
+}
+
+impl<S> Service<Request<Body>> for FieldMaskMiddleware<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = Response<Body>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let loader = self.loader.clone();
+
+        Box::pin(async move {
+            let Some(user) = req.extensions().get::<User>().cloned() else {
+                return inner.call(req).await;
+            };
+            let path = req.uri().path().to_string();
+
+            let rules = match domain_rules(loader.as_ref(), &user.domain()).await {
+                Ok(rules) => rules,
+                // 规则无法读取时拒绝返回数据，避免敏感字段外泄
+                Err(e) => {
+                    tracing::error!("Failed to load field mask rules: {}", e.message);
+                    return Ok(Res::<()>::new_error(500, "Failed to load field mask rules")
+                        .into_response());
+                },
+            };
+            let masks = select_masks(&rules, &user.subject(), &path);
+            let response = inner.call(req).await?;
+            if masks.is_empty() || !is_json(&response) {
+                return Ok(response);
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let bytes = to_bytes(body, usize::MAX).await.unwrap_or_default();
+            let mut json = match serde_json::from_slice::<Value>(&bytes) {
+                Ok(json) => json,
+                Err(_) => return Ok(Response::from_parts(parts, Body::from(bytes))),
+            };
+            if let Some(data) = json.get_mut("data") {
+                apply_masks(data, &masks);
+            }
+
+            parts.headers.remove(CONTENT_LENGTH);
+            let body = serde_json::to_vec(&json).unwrap_or_default();
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+fn is_json(response: &Response<Body>) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(mime::APPLICATION_JSON.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rule(role: &str, resource: &str, field: &str, strategy: MaskStrategy) -> FieldMaskRule {
+        FieldMaskRule {
+            role: role.to_string(),
+            resource: resource.to_string(),
+            field: field.to_string(),
+            strategy,
+        }
+    }
+
+    #[test]
+    fn test_mask_partial() {
+        assert_eq!(mask_partial("13812341234"), "138****1234");
+        assert_eq!(mask_partial("alice@example.com"), "a****@example.com");
+        assert_eq!(mask_partial("abcd"), "a**d");
+        assert_eq!(mask_partial("ab"), "**");
+        assert_eq!(mask_partial("张三丰"), "张*丰");
+    }
+
+    #[test]
+    fn test_select_masks() {
+        let rules = vec![
+            rule("ROLE_USER", "/user/*", "phoneNumber", MaskStrategy::Partial),
+            rule("ROLE_AUDIT", "", "phoneNumber", MaskStrategy::Hide),
+            rule("ROLE_USER", "/user", "email", MaskStrategy::Hash),
+            rule("ROLE_OTHER", "", "email", MaskStrategy::Hide),
+        ];
+        let roles = vec!["ROLE_USER".to_string(), "ROLE_AUDIT".to_string()];
+
+        let masks = select_masks(&rules, &roles, "/user");
+        assert_eq!(masks.get("phoneNumber"), Some(&MaskStrategy::Hide));
+        assert_eq!(masks.get("email"), Some(&MaskStrategy::Hash));
+
+        let masks = select_masks(&rules, &roles[..1], "/user/1");
+        assert_eq!(masks.get("phoneNumber"), Some(&MaskStrategy::Partial));
+        assert_eq!(masks.get("email"), None);
+
+        assert!(select_masks(&rules, &roles[..1], "/users").is_empty());
+    }
+
+    #[test]
+    fn test_apply_masks() {
+        let masks = HashMap::from([
+            ("phoneNumber".to_string(), MaskStrategy::Partial),
+            ("email".to_string(), MaskStrategy::Hide),
+            ("idCard".to_string(), MaskStrategy::Hash),
+            ("profile".to_string(), MaskStrategy::Partial),
+        ]);
+        let mut data = json!({
+            "records": [
+                {
+                    "username": "alice",
+                    "phoneNumber": "13812341234",
+                    "email": "alice@example.com",
+                    "idCard": "abc",
+                    "profile": { "city": "x" }
+                },
+                { "username": "bob", "phoneNumber": null }
+            ],
+            "total": 2
+        });
+
+        apply_masks(&mut data, &masks);
+        assert_eq!(
+            data,
+            json!({
+                "records": [
+                    {
+                        "username": "alice",
+                        "phoneNumber": "138****1234",
+                        "idCard": "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                    },
+                    { "username": "bob", "phoneNumber": null }
+                ],
+                "total": 2
+            })
+        );
+    }
+}
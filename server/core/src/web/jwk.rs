@@ -0,0 +1,300 @@
+use std::{collections::HashMap, fs};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters,
+        EllipticCurveKeyType, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType,
+        PublicKeyUse, RSAKeyParameters, RSAKeyType,
+    },
+    Algorithm, DecodingKey, EncodingKey,
+};
+use server_config::{JwtAlgorithm, JwtConfig};
+use server_global::global::Keys;
+
+use crate::web::jwt::JwtError;
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+/// rsaEncryption (1.2.840.113549.1.1.1)
+const OID_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+/// id-ecPublicKey (1.2.840.10045.2.1)
+const OID_EC: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+/// prime256v1 (1.2.840.10045.3.1.7)
+const OID_P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+/// Ed25519 (1.3.101.112)
+const OID_ED25519: &[u8] = &[0x2B, 0x65, 0x70];
+
+pub fn to_algorithm(algorithm: JwtAlgorithm) -> Algorithm {
+    match algorithm {
+        JwtAlgorithm::HS256 => Algorithm::HS256,
+        JwtAlgorithm::RS256 => Algorithm::RS256,
+        JwtAlgorithm::ES256 => Algorithm::ES256,
+        JwtAlgorithm::EdDSA => Algorithm::EdDSA,
+    }
+}
+
+/// 根据配置加载非对称密钥，生成签名密钥、按 kid 索引的验签密钥和 JWKS
+pub fn load_keys(config: &JwtConfig) -> Result<Keys, JwtError> {
+    if !config.algorithm.is_asymmetric() {
+        return Ok(Keys::new(config.jwt_secret.as_bytes()));
+    }
+    if config.keys.is_empty() {
+        return Err(key_error("no keys configured"));
+    }
+
+    let signing = match &config.signing_kid {
+        Some(kid) => config
+            .keys
+            .iter()
+            .find(|key| &key.kid == kid)
+            .ok_or_else(|| key_error(format!("signing key '{}' not found", kid)))?,
+        None => config
+            .keys
+            .iter()
+            .find(|key| key.private_key_path.is_some())
+            .ok_or_else(|| key_error("no key with a private key configured"))?,
+    };
+    let private_key_path = signing
+        .private_key_path
+        .as_ref()
+        .ok_or_else(|| key_error(format!("signing key '{}' has no private key", signing.kid)))?;
+    let private_pem = read_file(private_key_path)?;
+    let encoding = match config.algorithm {
+        JwtAlgorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+        JwtAlgorithm::ES256 => EncodingKey::from_ec_pem(&private_pem),
+        _ => EncodingKey::from_ed_pem(&private_pem),
+    }
+    .map_err(|e| key_error(format!("{}: {}", private_key_path, e)))?;
+
+    let mut verification_keys = HashMap::new();
+    let mut jwks = JwkSet { keys: Vec::new() };
+    for key in &config.keys {
+        if verification_keys.contains_key(&key.kid) {
+            return Err(key_error(format!("duplicate kid '{}'", key.kid)));
+        }
+        let public_pem = read_file(&key.public_key_path)?;
+        let jwk = public_pem_to_jwk(&public_pem, &key.kid, config.algorithm)
+            .map_err(|e| key_error(format!("{}: {}", key.public_key_path, e)))?;
+        let decoding = DecodingKey::from_jwk(&jwk)
+            .map_err(|e| key_error(format!("{}: {}", key.public_key_path, e)))?;
+        verification_keys.insert(key.kid.clone(), decoding);
+        jwks.keys.push(jwk);
+    }
+
+    Keys::asymmetric(
+        to_algorithm(config.algorithm),
+        signing.kid.clone(),
+        encoding,
+        verification_keys,
+        jwks,
+    )
+    .ok_or_else(|| key_error(format!("signing key '{}' not found", signing.kid)))
+}
+
+/// 将 PEM 格式（SPKI）公钥转换为 JWK
+pub fn public_pem_to_jwk(pem: &[u8], kid: &str, algorithm: JwtAlgorithm) -> Result<Jwk, String> {
+    let pem = pem::parse(pem).map_err(|e| e.to_string())?;
+    if pem.tag() != "PUBLIC KEY" {
+        return Err(format!("expected PUBLIC KEY, got {}", pem.tag()));
+    }
+
+    // SubjectPublicKeyInfo ::= SEQUENCE { algorithm, subjectPublicKey BIT STRING }
+    let (spki, _) = read_tlv(pem.contents(), TAG_SEQUENCE)?;
+    let (algorithm_identifier, rest) = read_tlv(spki, TAG_SEQUENCE)?;
+    let (bit_string, _) = read_tlv(rest, TAG_BIT_STRING)?;
+    let public_key = match bit_string.split_first() {
+        Some((0, key)) => key,
+        _ => return Err("malformed subjectPublicKey".to_string()),
+    };
+    let (oid, parameters) = read_tlv(algorithm_identifier, TAG_OID)?;
+
+    let (key_algorithm, parameters) = match algorithm {
+        JwtAlgorithm::RS256 if oid == OID_RSA => {
+            let (rsa_key, _) = read_tlv(public_key, TAG_SEQUENCE)?;
+            let (n, rest) = read_tlv(rsa_key, TAG_INTEGER)?;
+            let (e, _) = read_tlv(rest, TAG_INTEGER)?;
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: encode_unsigned(n),
+                    e: encode_unsigned(e),
+                }),
+            )
+        },
+        JwtAlgorithm::ES256 if oid == OID_EC => {
+            let (curve, _) = read_tlv(parameters, TAG_OID)?;
+            if curve != OID_P256 {
+                return Err("ES256 requires a P-256 key".to_string());
+            }
+            match public_key.split_first() {
+                Some((0x04, point)) if point.len() == 64 => (
+                    KeyAlgorithm::ES256,
+                    AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                        key_type: EllipticCurveKeyType::EC,
+                        curve: EllipticCurve::P256,
+                        x: URL_SAFE_NO_PAD.encode(&point[..32]),
+                        y: URL_SAFE_NO_PAD.encode(&point[32..]),
+                    }),
+                ),
+                _ => return Err("unsupported EC point encoding".to_string()),
+            }
+        },
+        JwtAlgorithm::EdDSA if oid == OID_ED25519 && public_key.len() == 32 => (
+            KeyAlgorithm::EdDSA,
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(public_key),
+            }),
+        ),
+        _ => return Err(format!("key type does not match algorithm {:?}", algorithm)),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+/// 读取一个 DER TLV，返回其内容和剩余字节
+fn read_tlv(input: &[u8], expected_tag: u8) -> Result<(&[u8], &[u8]), String> {
+    let (&tag, rest) = input.split_first().ok_or("unexpected end of DER")?;
+    if tag != expected_tag {
+        return Err(format!(
+            "unexpected DER tag 0x{:02x}, expected 0x{:02x}",
+            tag, expected_tag
+        ));
+    }
+    let (&first, rest) = rest.split_first().ok_or("unexpected end of DER")?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return Err("invalid DER length".to_string());
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return Err("unexpected end of DER".to_string());
+    }
+    Ok(rest.split_at(len))
+}
+
+/// 去掉 DER INTEGER 的符号填充字节后进行 base64url 编码
+fn encode_unsigned(value: &[u8]) -> String {
+    let start = value
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(value.len().saturating_sub(1));
+    URL_SAFE_NO_PAD.encode(&value[start..])
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, JwtError> {
+    fs::read(path).map_err(|e| key_error(format!("{}: {}", path, e)))
+}
+
+fn key_error(message: impl Into<String>) -> JwtError {
+    JwtError::KeyLoadError(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
+    use serde::{Deserialize, Serialize};
+    use server_config::JwtKeyConfig;
+
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt");
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestClaims {
+        sub: String,
+        exp: usize,
+    }
+
+    fn key(kid: &str, name: &str, with_private: bool) -> JwtKeyConfig {
+        JwtKeyConfig {
+            kid: kid.to_string(),
+            public_key_path: format!("{}/{}.pub.pem", FIXTURES, name),
+            private_key_path: with_private.then(|| format!("{}/{}.pem", FIXTURES, name)),
+        }
+    }
+
+    fn config(algorithm: JwtAlgorithm, keys: Vec<JwtKeyConfig>) -> JwtConfig {
+        JwtConfig {
+            jwt_secret: String::new(),
+            issuer: "test".to_string(),
+            expire: 3600,
+            refresh_expire: 86400,
+            algorithm,
+            keys,
+            signing_kid: None,
+            revocation_fail_open: false,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_with_jwks() {
+        for (algorithm, name) in [
+            (JwtAlgorithm::RS256, "rsa"),
+            (JwtAlgorithm::ES256, "ec"),
+            (JwtAlgorithm::EdDSA, "ed"),
+        ] {
+            let keys = load_keys(&config(algorithm, vec![key("k1", name, true)])).unwrap();
+            let claims = TestClaims {
+                sub: "admin".to_string(),
+                exp: usize::MAX / 2,
+            };
+            let mut header = Header::new(keys.algorithm);
+            header.kid = keys.kid.clone();
+            let token = encode(&header, &claims, &keys.encoding).unwrap();
+
+            // 外部服务仅凭 JWKS 中的公钥即可验签
+            let header = decode_header(&token).unwrap();
+            let jwk = keys.jwks.find(header.kid.as_deref().unwrap()).unwrap();
+            let decoding = DecodingKey::from_jwk(jwk).unwrap();
+            let data =
+                decode::<TestClaims>(&token, &decoding, &Validation::new(keys.algorithm)).unwrap();
+            assert_eq!(data.claims, claims, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_verification_key() {
+        let mut cfg = config(
+            JwtAlgorithm::ES256,
+            vec![key("old", "ec", false), key("new", "ec", true)],
+        );
+        cfg.signing_kid = Some("new".to_string());
+        let keys = load_keys(&cfg).unwrap();
+
+        assert_eq!(keys.kid.as_deref(), Some("new"));
+        assert_eq!(keys.jwks.keys.len(), 2);
+        assert!(keys.decoding_key(Some("old")).is_some());
+        assert!(keys.decoding_key(Some("unknown")).is_none());
+
+        cfg.signing_kid = Some("old".to_string());
+        assert!(load_keys(&cfg).is_err());
+    }
+
+    #[test]
+    fn test_key_type_must_match_algorithm() {
+        let cfg = config(JwtAlgorithm::RS256, vec![key("k1", "ec", true)]);
+        assert!(load_keys(&cfg).is_err());
+    }
+}
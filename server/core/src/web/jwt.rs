@@ -1,9 +1,9 @@
 use std::{error::Error, fmt};
 
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, Header, TokenData};
+use jsonwebtoken::{decode, decode_header, encode, jwk::JwkSet, DecodingKey, Header, TokenData};
 use server_config::JwtConfig;
-use server_global::global;
+use server_global::global::{self, Keys};
 use ulid::Ulid;
 
 use crate::web::auth::Claims;
@@ -29,6 +29,7 @@ pub enum JwtError {
     ValidationNotInitialized,
     TokenCreationError(String),
     TokenValidationError(String),
+    KeyLoadError(String),
 }
 
 impl fmt::Display for JwtError {
@@ -38,6 +39,7 @@ impl fmt::Display for JwtError {
             JwtError::ValidationNotInitialized => write!(f, "Validation not initialized"),
             JwtError::TokenCreationError(err) => write!(f, "Token creation error: {}", err),
             JwtError::TokenValidationError(err) => write!(f, "Token validation error: {}", err),
+            JwtError::KeyLoadError(err) => write!(f, "Key load error: {}", err),
         }
     }
 }
@@ -63,7 +65,9 @@ impl JwtUtils {
         claims_clone.set_nbf(timestamp);
         claims_clone.set_jti(Ulid::new().to_string());
 
-        let token = encode(&Header::default(), &claims_clone, &keys.encoding)
+        let mut header = Header::new(keys.algorithm);
+        header.kid = keys.kid.clone();
+        let token = encode(&header, &claims_clone, &keys.encoding)
             .map_err(|e| JwtError::TokenCreationError(e.to_string()));
 
         if let Ok(ref tok) = token {
@@ -87,7 +91,43 @@ impl JwtUtils {
 
         let mut validation_clone = validation.clone();
         validation_clone.set_audience(&[audience.to_string()]);
-        decode::<Claims>(token, &keys.decoding, &validation_clone)
+        decode::<Claims>(token, Self::decoding_key(&keys, token)?, &validation_clone)
             .map_err(|e| JwtError::TokenValidationError(e.to_string()))
     }
+
+    /// 校验签名并解析令牌声明，不校验过期时间与受众
+    ///
+    /// 用于从已签发的令牌中读取 jti 等信息（如强制下线时吊销令牌）。
+    pub async fn decode_claims(token: &str) -> Result<Claims, JwtError> {
+        let keys_arc = global::KEYS.get().ok_or(JwtError::KeysNotInitialized)?;
+
+        let keys = keys_arc.lock().await;
+        let validation_arc = global::VALIDATION
+            .get()
+            .ok_or(JwtError::ValidationNotInitialized)?;
+        let validation = validation_arc.lock().await;
+
+        let mut validation_clone = validation.clone();
+        validation_clone.validate_exp = false;
+        validation_clone.validate_aud = false;
+        decode::<Claims>(token, Self::decoding_key(&keys, token)?, &validation_clone)
+            .map(|data| data.claims)
+            .map_err(|e| JwtError::TokenValidationError(e.to_string()))
+    }
+
+    /// 对外公布的验签公钥（JWKS），HS256 时为空集合
+    pub async fn jwks() -> Result<JwkSet, JwtError> {
+        let keys_arc = global::KEYS.get().ok_or(JwtError::KeysNotInitialized)?;
+        let keys = keys_arc.lock().await;
+        Ok(keys.jwks.clone())
+    }
+
+    /// 按令牌头中的 kid 选择验签密钥，kid 未知时拒绝
+    fn decoding_key<'a>(keys: &'a Keys, token: &str) -> Result<&'a DecodingKey, JwtError> {
+        let header =
+            decode_header(token).map_err(|e| JwtError::TokenValidationError(e.to_string()))?;
+        keys.decoding_key(header.kid.as_deref()).ok_or_else(|| {
+            JwtError::TokenValidationError(format!("unknown key id: {:?}", header.kid))
+        })
+    }
 }
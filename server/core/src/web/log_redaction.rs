@@ -0,0 +1,383 @@
+//! 操作日志的脱敏与大小限制
+//!
+//! 请求体、查询参数与响应在记录前按字段名与 JSON Pointer 脱敏，请求头只记录白名单内的部分。
+//! 二进制与 multipart 内容只记录类型与大小，脱敏后超过上限的内容会被截断。
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, HeaderMap};
+use serde_json::{json, Map, Value};
+use server_config::OperationLogConfig;
+use server_global::global;
+use tokio::sync::OnceCell;
+
+/// 脱敏后的值
+pub const REDACTED: &str = "******";
+
+/// 内置的敏感字段，覆盖 `server_model` 输入输出及请求头中的密码、令牌与密钥
+const DEFAULT_REDACT_KEYS: &[&str] = &[
+    "password",
+    "oldPassword",
+    "newPassword",
+    "token",
+    "accessToken",
+    "refreshToken",
+    "challengeToken",
+    "*Secret",
+    "recoveryCodes",
+    "otpauthUri",
+    "authorization",
+    "cookie",
+    "x-api-key",
+];
+
+static REDACTOR: OnceCell<Arc<LogRedactor>> = OnceCell::const_new();
+
+#[derive(Debug, Clone)]
+pub struct LogRedactor {
+    /// 归一化后的字段名模式
+    keys: Vec<String>,
+    /// 按段拆分的 JSON Pointer
+    pointers: Vec<Vec<String>>,
+    headers: Vec<String>,
+    max_buffer_size: usize,
+    max_payload_size: usize,
+}
+
+impl LogRedactor {
+    pub fn new(config: &OperationLogConfig) -> Self {
+        Self {
+            keys: DEFAULT_REDACT_KEYS
+                .iter()
+                .copied()
+                .chain(config.redact_keys.iter().map(String::as_str))
+                .map(normalize_key)
+                .collect(),
+            pointers: config
+                .redact_pointers
+                .iter()
+                .map(|pointer| parse_pointer(pointer))
+                .collect(),
+            headers: config
+                .header_allowlist
+                .iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect(),
+            max_buffer_size: config.max_buffer_size,
+            max_payload_size: config.max_payload_size,
+        }
+    }
+
+    /// 按全局配置创建的实例
+    pub async fn global() -> Arc<Self> {
+        REDACTOR
+            .get_or_init(|| async {
+                let config = global::get_config::<OperationLogConfig>()
+                    .await
+                    .unwrap_or_default();
+                Arc::new(Self::new(&config))
+            })
+            .await
+            .clone()
+    }
+
+    pub fn max_buffer_size(&self) -> usize {
+        self.max_buffer_size
+    }
+
+    /// 字段名是否需要脱敏，忽略大小写与 `_`、`-`
+    pub fn is_sensitive_key(&self, key: &str) -> bool {
+        let key = normalize_key(key);
+        self.keys.iter().any(|pattern| glob_match(pattern, &key))
+    }
+
+    /// 白名单内的请求头，敏感的请求头只记录为脱敏值
+    pub fn headers(&self, headers: &HeaderMap) -> Option<Value> {
+        let recorded: Map<String, Value> = self
+            .headers
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(name.as_str())?.to_str().ok()?;
+                let value = match self.is_sensitive_key(name) {
+                    true => REDACTED,
+                    false => value,
+                };
+                Some((name.clone(), Value::String(value.to_string())))
+            })
+            .collect();
+        (!recorded.is_empty()).then_some(Value::Object(recorded))
+    }
+
+    /// 按内容类型解析请求体或响应，脱敏并截断后返回；空内容返回 `None`
+    ///
+    /// 未指明类型时按 JSON 解析，multipart 与其他二进制内容只记录类型与大小。
+    pub fn payload(&self, content_type: Option<&str>, bytes: &Bytes) -> Option<Value> {
+        if bytes.is_empty() {
+            return None;
+        }
+
+        let mime = content_type
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase());
+        let mut value = match mime.as_deref() {
+            None => serde_json::from_slice(bytes).ok()?,
+            Some(mime) if mime == "application/json" || mime.ends_with("+json") => {
+                serde_json::from_slice(bytes).ok()?
+            },
+            Some("application/x-www-form-urlencoded") => Value::Object(
+                form_urlencoded::parse(bytes)
+                    .into_owned()
+                    .map(|(key, value)| (key, Value::String(value)))
+                    .collect(),
+            ),
+            Some(mime) if mime.starts_with("text/") => {
+                Value::String(String::from_utf8_lossy(bytes).into_owned())
+            },
+            Some(mime) => {
+                return Some(json!({
+                    "skipped": true,
+                    "contentType": mime,
+                    "size": bytes.len(),
+                }))
+            },
+        };
+
+        self.redact(&mut value);
+        Some(self.truncate(value))
+    }
+
+    /// 内容超过缓冲上限时记录的值
+    pub fn oversized(&self) -> Value {
+        json!({
+            "skipped": true,
+            "reason": "exceeds buffer limit",
+            "limit": self.max_buffer_size,
+        })
+    }
+
+    /// 按字段名与 JSON Pointer 脱敏
+    pub fn redact(&self, value: &mut Value) {
+        self.redact_keys(value);
+        for pointer in &self.pointers {
+            redact_pointer(value, pointer);
+        }
+    }
+
+    fn redact_keys(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_sensitive_key(key) {
+                        if !value.is_null() {
+                            *value = Value::String(REDACTED.to_string());
+                        }
+                    } else {
+                        self.redact_keys(value);
+                    }
+                }
+            },
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_keys(value)),
+            _ => {},
+        }
+    }
+
+    /// 序列化后超过上限时只保留开头的部分
+    fn truncate(&self, value: Value) -> Value {
+        let serialized = value.to_string();
+        if serialized.len() <= self.max_payload_size {
+            return value;
+        }
+
+        let mut end = self.max_payload_size;
+        while !serialized.is_char_boundary(end) {
+            end -= 1;
+        }
+        json!({
+            "truncated": true,
+            "size": serialized.len(),
+            "preview": &serialized[..end],
+        })
+    }
+}
+
+/// 请求或响应的内容类型
+pub fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers.get(CONTENT_TYPE)?.to_str().ok()
+}
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 仅支持 `*` 通配符的匹配
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let [first, middle @ .., last] = parts.as_slice() else {
+        return pattern == text;
+    };
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    middle.iter().all(|part| match rest.find(part) {
+        Some(index) => {
+            rest = &rest[index + part.len()..];
+            true
+        },
+        None => false,
+    })
+}
+
+fn parse_pointer(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn redact_pointer(value: &mut Value, segments: &[String]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    };
+
+    match value {
+        Value::Object(map) if segment == "*" => map
+            .values_mut()
+            .for_each(|value| redact_pointer(value, rest)),
+        Value::Object(map) => {
+            if let Some(value) = map.get_mut(segment) {
+                redact_pointer(value, rest);
+            }
+        },
+        Value::Array(values) if segment == "*" => values
+            .iter_mut()
+            .for_each(|value| redact_pointer(value, rest)),
+        Value::Array(values) => {
+            if let Some(value) = segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| values.get_mut(index))
+            {
+                redact_pointer(value, rest);
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn redactor() -> LogRedactor {
+        LogRedactor::new(&OperationLogConfig {
+            max_payload_size: 64,
+            redact_keys: vec!["id_card".to_string()],
+            redact_pointers: vec!["/data/records/*/phoneNumber".to_string()],
+            header_allowlist: vec!["content-type".to_string(), "authorization".to_string()],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_redact() {
+        let redactor = redactor();
+        let mut value = json!({
+            "username": "alice",
+            "password": "secret-1",
+            "access_key_secret": "ak",
+            "idCard": "110101",
+            "refreshToken": null,
+            "data": {
+                "records": [
+                    { "phoneNumber": "13812341234", "email": "a@example.com" },
+                    { "phoneNumber": "13912341234" }
+                ]
+            }
+        });
+
+        redactor.redact(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "username": "alice",
+                "password": REDACTED,
+                "access_key_secret": REDACTED,
+                "idCard": REDACTED,
+                "refreshToken": null,
+                "data": {
+                    "records": [
+                        { "phoneNumber": REDACTED, "email": "a@example.com" },
+                        { "phoneNumber": REDACTED }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_payload() {
+        let redactor = redactor();
+
+        let form = Bytes::from("identifier=alice&password=123456");
+        assert_eq!(
+            redactor.payload(Some("application/x-www-form-urlencoded"), &form),
+            Some(json!({ "identifier": "alice", "password": REDACTED }))
+        );
+
+        let json = Bytes::from(r#"{"oldPassword":"a","newPassword":"b"}"#);
+        assert_eq!(
+            redactor.payload(None, &json),
+            Some(json!({ "oldPassword": REDACTED, "newPassword": REDACTED }))
+        );
+
+        let file = Bytes::from_static(b"--boundary\r\n");
+        assert_eq!(
+            redactor.payload(Some("multipart/form-data; boundary=boundary"), &file),
+            Some(json!({ "skipped": true, "contentType": "multipart/form-data", "size": 12 }))
+        );
+
+        assert_eq!(
+            redactor.payload(Some("application/json"), &Bytes::new()),
+            None
+        );
+
+        let large = Bytes::from(json!({ "content": "x".repeat(100) }).to_string());
+        let truncated = redactor.payload(Some("application/json"), &large).unwrap();
+        assert_eq!(truncated["truncated"], json!(true));
+        assert_eq!(truncated["preview"].as_str().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_headers() {
+        let redactor = redactor();
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert("authorization", HeaderValue::from_static("Bearer abc"));
+        headers.insert("x-other", HeaderValue::from_static("1"));
+
+        assert_eq!(
+            redactor.headers(&headers),
+            Some(json!({ "content-type": "application/json", "authorization": REDACTED }))
+        );
+        assert_eq!(redactor.headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*secret", "accesskeysecret"));
+        assert!(glob_match("*secret", "secret"));
+        assert!(glob_match("pass*word", "passphraseword"));
+        assert!(!glob_match("*secret", "secretkey"));
+        assert!(!glob_match("token", "tokens"));
+    }
+}
@@ -0,0 +1,227 @@
+use std::{net::IpAddr, time::Duration};
+
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta};
+use ipnet::IpNet;
+
+use crate::web::ttl_store::TtlStore;
+
+const LOGIN_FAILURES_PREFIX: &str = "login_failures";
+const LOGIN_LOCK_PREFIX: &str = "login_lock";
+
+/// 登录失败计数与锁定状态存储
+///
+/// 配置了 Redis 时使用 Redis 存储，多实例之间共享计数；否则使用进程内缓存。
+/// 计数在统计窗口结束后自动清零，锁定在锁定时长结束后自动解除。
+/// Redis 不可用时不阻断登录，仅记录错误日志。
+#[derive(Clone)]
+pub struct LoginAttemptStore {
+    store: TtlStore,
+}
+
+impl LoginAttemptStore {
+    pub fn new(store: TtlStore) -> Self {
+        Self { store }
+    }
+
+    /// 根据是否配置了全局 Redis 选择存储实现
+    pub async fn current() -> Self {
+        Self::new(TtlStore::current().await)
+    }
+
+    fn failures_key(key: &str) -> String {
+        format!("{}:{}", LOGIN_FAILURES_PREFIX, key)
+    }
+
+    fn lock_key(key: &str) -> String {
+        format!("{}:{}", LOGIN_LOCK_PREFIX, key)
+    }
+
+    /// 记录一次失败并返回统计窗口内的失败次数，更新计数不会延长统计窗口
+    pub async fn record_failure(&self, key: &str, window: Duration) -> u64 {
+        self.store
+            .incr(&Self::failures_key(key), window)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to record login failure for {}: {}", key, e);
+                0
+            })
+    }
+
+    /// 锁定指定键
+    pub async fn lock(&self, key: &str, duration: Duration) {
+        if let Err(e) = self.store.set(&Self::lock_key(key), "1", duration).await {
+            tracing::error!("Failed to lock login for {}: {}", key, e);
+        }
+    }
+
+    /// 检查指定键是否处于锁定状态
+    pub async fn is_locked(&self, key: &str) -> bool {
+        self.store
+            .exists(&Self::lock_key(key))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to check login lock for {}: {}", key, e);
+                false
+            })
+    }
+
+    /// 清除指定键的失败计数
+    pub async fn reset(&self, key: &str) {
+        if let Err(e) = self.store.remove(&Self::failures_key(key)).await {
+            tracing::error!("Failed to reset login failures for {}: {}", key, e);
+        }
+    }
+}
+
+/// 判断 IP 是否命中规则列表
+///
+/// 规则支持 CIDR（如 `10.0.0.0/8`）与单个 IP，无法解析的规则与 IP 均视为不匹配。
+pub fn ip_matches(rules: &[String], ip: &str) -> bool {
+    let Ok(ip) = ip.trim().parse::<IpAddr>() else {
+        return false;
+    };
+
+    rules.iter().any(|rule| {
+        let rule = rule.trim();
+        if let Ok(net) = rule.parse::<IpNet>() {
+            net.contains(&ip)
+        } else if let Ok(addr) = rule.parse::<IpAddr>() {
+            addr == ip
+        } else {
+            false
+        }
+    })
+}
+
+/// 判断时间是否处于允许登录的时段内
+///
+/// `start` 晚于 `end` 时视为跨天时段；时段格式无法解析时返回 `None`。
+pub fn within_login_hours(start: &str, end: &str, now: NaiveTime) -> Option<bool> {
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+
+    Some(if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    })
+}
+
+/// 计算新会话登录前需要踢出的会话
+///
+/// `started_at` 为用户各会话首次登录的时间，按升序排列；登录已超过 `ttl`（刷新令牌有效期）
+/// 的会话已无法续期，不占用名额。返回需要踢出的会话下标，最早登录的在前，为空表示未超出限制。
+pub fn sessions_over_limit(
+    started_at: &[NaiveDateTime],
+    now: NaiveDateTime,
+    ttl: TimeDelta,
+    limit: u64,
+) -> Vec<usize> {
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let online: Vec<usize> = started_at
+        .iter()
+        .enumerate()
+        .filter(|(_, started_at)| **started_at + ttl > now)
+        .map(|(index, _)| index)
+        .collect();
+
+    // 为新会话预留一个名额
+    let excess = (online.len() as u64 + 1).saturating_sub(limit) as usize;
+    online.into_iter().take(excess).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::web::ttl_store::MemoryTtlStore;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_ip_matches() {
+        let rules = vec![
+            "10.0.0.0/8".to_string(),
+            "192.168.1.10".to_string(),
+            "2001:db8::/32".to_string(),
+            "invalid".to_string(),
+        ];
+
+        assert!(ip_matches(&rules, "10.1.2.3"));
+        assert!(ip_matches(&rules, "192.168.1.10"));
+        assert!(ip_matches(&rules, "2001:db8::1"));
+        assert!(!ip_matches(&rules, "192.168.1.11"));
+        assert!(!ip_matches(&rules, "unknown"));
+        assert!(!ip_matches(&[], "10.1.2.3"));
+    }
+
+    #[test]
+    fn test_within_login_hours() {
+        assert_eq!(within_login_hours("08:00", "20:00", time(8, 0)), Some(true));
+        assert_eq!(
+            within_login_hours("08:00", "20:00", time(20, 0)),
+            Some(false)
+        );
+        assert_eq!(
+            within_login_hours("22:00", "06:00", time(23, 30)),
+            Some(true)
+        );
+        assert_eq!(
+            within_login_hours("22:00", "06:00", time(5, 59)),
+            Some(true)
+        );
+        assert_eq!(
+            within_login_hours("22:00", "06:00", time(12, 0)),
+            Some(false)
+        );
+        assert_eq!(within_login_hours("8am", "20:00", time(12, 0)), None);
+    }
+
+    #[test]
+    fn test_sessions_over_limit() {
+        let now =
+            NaiveDateTime::parse_from_str("2024-12-01 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let ttl = TimeDelta::hours(2);
+        let live = [now - TimeDelta::minutes(30), now - TimeDelta::minutes(10)];
+
+        assert!(sessions_over_limit(&live, now, ttl, 3).is_empty());
+        assert_eq!(sessions_over_limit(&live, now, ttl, 2), vec![0]);
+        assert_eq!(sessions_over_limit(&live, now, ttl, 1), vec![0, 1]);
+        assert!(sessions_over_limit(&live, now, ttl, 0).is_empty());
+
+        // 已过期的会话不阻止新的登录
+        let with_expired = [now - TimeDelta::hours(5), now - TimeDelta::hours(3)];
+        assert!(sessions_over_limit(&with_expired, now, ttl, 1).is_empty());
+
+        let mixed = [now - TimeDelta::hours(3), now - TimeDelta::minutes(5)];
+        assert_eq!(sessions_over_limit(&mixed, now, ttl, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_failures_and_lock() {
+        let store = LoginAttemptStore::new(TtlStore::Memory(Arc::new(MemoryTtlStore::new())));
+        let window = Duration::from_secs(60);
+
+        assert_eq!(store.record_failure("user:built-in:admin", window).await, 1);
+        assert_eq!(store.record_failure("user:built-in:admin", window).await, 2);
+        assert_eq!(store.record_failure("ip:127.0.0.1", window).await, 1);
+
+        store.reset("user:built-in:admin").await;
+        assert_eq!(store.record_failure("user:built-in:admin", window).await, 1);
+
+        assert!(!store.is_locked("user:built-in:admin").await);
+        store
+            .lock("user:built-in:admin", Duration::from_millis(50))
+            .await;
+        assert!(store.is_locked("user:built-in:admin").await);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!store.is_locked("user:built-in:admin").await);
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::web::ttl_store::TtlStore;
+
+const MFA_CHALLENGE_PREFIX: &str = "mfa_challenge";
+
+/// 两步登录的挑战信息，第一步密码校验通过后签发
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MfaChallenge {
+    pub user_id: String,
+    pub domain: String,
+}
+
+/// 两步登录挑战存储
+///
+/// 配置了 Redis 时使用 Redis 存储，否则使用进程内缓存。挑战以 JSON 保存，挑战令牌为随机 ULID，
+/// 到期后自动失效，第二步成功后立即删除。
+#[derive(Clone)]
+pub struct MfaChallengeStore {
+    store: TtlStore,
+}
+
+impl MfaChallengeStore {
+    pub fn new(store: TtlStore) -> Self {
+        Self { store }
+    }
+
+    /// 根据是否配置了全局 Redis 选择存储实现
+    pub async fn current() -> Self {
+        Self::new(TtlStore::current().await)
+    }
+
+    fn get_key(token: &str) -> String {
+        format!("{}:{}", MFA_CHALLENGE_PREFIX, token)
+    }
+
+    /// 签发挑战并返回挑战令牌
+    pub async fn issue(&self, challenge: &MfaChallenge, ttl: Duration) -> String {
+        let token = Ulid::new().to_string();
+        match serde_json::to_string(challenge) {
+            Ok(value) => {
+                if let Err(e) = self.store.set(&Self::get_key(&token), &value, ttl).await {
+                    tracing::error!("Failed to store MFA challenge: {}", e);
+                }
+            },
+            Err(e) => tracing::error!("Failed to serialize MFA challenge: {}", e),
+        }
+        token
+    }
+
+    /// 获取未过期的挑战
+    pub async fn get(&self, token: &str) -> Option<MfaChallenge> {
+        match self.store.get(&Self::get_key(token)).await {
+            Ok(value) => value.and_then(|value| serde_json::from_str(&value).ok()),
+            Err(e) => {
+                tracing::error!("Failed to load MFA challenge: {}", e);
+                None
+            },
+        }
+    }
+
+    /// 删除挑战
+    pub async fn remove(&self, token: &str) {
+        if let Err(e) = self.store.remove(&Self::get_key(token)).await {
+            tracing::error!("Failed to remove MFA challenge: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::web::ttl_store::MemoryTtlStore;
+
+    fn memory_store() -> MfaChallengeStore {
+        MfaChallengeStore::new(TtlStore::Memory(Arc::new(MemoryTtlStore::new())))
+    }
+
+    fn challenge() -> MfaChallenge {
+        MfaChallenge {
+            user_id: "1".to_string(),
+            domain: "built-in".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_challenge_lifecycle() {
+        let store = memory_store();
+
+        let token = store.issue(&challenge(), Duration::from_secs(60)).await;
+        assert_eq!(store.get(&token).await, Some(challenge()));
+        assert_eq!(store.get("unknown").await, None);
+
+        store.remove(&token).await;
+        assert_eq!(store.get(&token).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_challenge_expiry() {
+        let store = memory_store();
+
+        let token = store.issue(&challenge(), Duration::from_millis(50)).await;
+        assert!(store.get(&token).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(store.get(&token).await.is_none());
+    }
+}
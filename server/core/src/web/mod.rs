@@ -1,10 +1,19 @@
 pub mod auth;
 pub mod error;
+pub mod export;
+pub mod field_mask;
+pub mod jwk;
 pub mod jwt;
+pub mod log_redaction;
+pub mod login_security;
+pub mod mfa_challenge;
 pub mod page;
 pub mod res;
+pub mod token_revocation;
+pub mod ttl_store;
 pub mod util;
 pub mod validator;
+pub mod xlsx;
 
 pub use request_id::{RequestId, RequestIdLayer};
 
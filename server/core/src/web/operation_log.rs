@@ -6,8 +6,8 @@ use std::{
 };
 
 use axum::{
-    body::{to_bytes, Body, Bytes},
-    extract::{ConnectInfo, Request},
+    body::{Body, Bytes},
+    extract::{ConnectInfo, MatchedPath, Request},
     response::Response,
     Extension,
 };
@@ -16,25 +16,42 @@ use chrono::Local;
 use futures::{future::BoxFuture, StreamExt};
 use http::{Extensions, HeaderMap, Uri};
 use serde_json::Value;
-use server_constant::definition::consts::SystemEvent;
-use server_global::global::{self, OperationLogContext};
+use server_global::global::{self, OperationLogContext, OperationLogOptions, RouteInfo};
 use tower_layer::Layer;
 use tower_service::Service;
 
-use super::{auth::User, RequestId};
+use super::{
+    auth::User,
+    log_redaction::{content_type, LogRedactor},
+    RequestId,
+};
 
 const USER_AGENT_HEADER: &str = "user-agent";
 const UNKNOWN_REQUEST_ID: &str = "unknown";
 const DEFAULT_BODY_CAPACITY: usize = 1024 * 16; // 16KB 默认缓冲区大小
 
+/// 操作日志层
+///
+/// 按匹配到的路径模板从路由收集器中读取模块、描述与记录方式，
+/// 未登记的路由使用 `fallback` 指定的记录方式。
 #[derive(Clone)]
 pub struct OperationLogLayer {
     pub enabled: bool,
+    pub fallback: OperationLogOptions,
 }
 
 impl OperationLogLayer {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            fallback: OperationLogOptions::default(),
+        }
+    }
+
+    /// 设置未登记路由的记录方式
+    pub fn fallback(mut self, options: OperationLogOptions) -> Self {
+        self.fallback = options;
+        self
     }
 }
 
@@ -51,6 +68,7 @@ where
         OperationLogMiddleware {
             inner: service,
             enabled: self.enabled,
+            fallback: self.fallback,
         }
     }
 }
@@ -59,6 +77,7 @@ where
 pub struct OperationLogMiddleware<S> {
     inner: S,
     enabled: bool,
+    fallback: OperationLogOptions,
 }
 
 impl<S> Service<Request<Body>> for OperationLogMiddleware<S>
@@ -84,7 +103,21 @@ where
         }
 
         let mut inner = self.inner.clone();
+        let fallback = self.fallback;
         Box::pin(async move {
+            let route = match req.extensions().get::<MatchedPath>() {
+                Some(path) => global::find_route(req.method(), path.as_str()).await,
+                None => None,
+            };
+            let options = route.as_ref().map_or(fallback, |route| route.operation_log);
+            if !options.enabled {
+                return inner.call(req).await;
+            }
+            let (module_name, description) = describe_route(route, req.uri());
+
+            let redactor = LogRedactor::global().await;
+            let limit = redactor.max_buffer_size();
+
             let start_time = Local::now().naive_local();
             let (parts, body) = req.into_parts();
             let headers = &parts.headers;
@@ -97,90 +130,121 @@ where
                 .map(ToString::to_string)
                 .unwrap_or_else(|| UNKNOWN_REQUEST_ID.to_string());
 
-            if let Ok(bytes) = buffer_body(body).await {
-                let method = parts.method.to_string();
-                let uri = parts.uri.to_string();
-                let ip = get_client_ip(extensions, headers);
-                let user_agent = get_user_agent(headers);
-                let params = parse_query_params(&parts.uri);
-
-                let req = Request::from_parts(parts, Body::from(bytes.clone()));
-                let response = inner.call(req).await?;
-
-                let (response_parts, response_body) = response.into_parts();
-                let response_bytes = to_bytes(response_body, usize::MAX)
-                    .await
-                    .unwrap_or_default();
-
-                let end_time = Local::now().naive_local();
-                let duration = (end_time - start_time).num_milliseconds() as i32;
-
-                let context = OperationLogContext {
-                    user_id,
-                    username,
-                    domain,
-                    module_name: "TODO".to_string(),
-                    description: "TODO".to_string(),
-                    request_id,
-                    method,
-                    url: uri,
-                    ip,
-                    user_agent,
-                    params,
-                    body: (!bytes.is_empty())
-                        .then(|| serde_json::from_slice(&bytes).ok())
-                        .flatten(),
-                    response: serde_json::from_slice(&response_bytes).ok(),
-                    start_time,
-                    end_time,
-                    duration,
-                    created_at: start_time,
-                };
-
-                global::send_dyn_event(
-                    SystemEvent::AuditOperationLoggedEvent.as_ref(),
-                    Box::new(context),
-                );
-
-                Ok(Response::from_parts(
-                    response_parts,
-                    Body::from(response_bytes),
-                ))
+            let (body, request_body) = if options.record_body {
+                match buffer_body(body, limit).await {
+                    Ok((body, Some(bytes))) => {
+                        (body, redactor.payload(content_type(headers), &bytes))
+                    },
+                    Ok((body, None)) => (body, Some(redactor.oversized())),
+                    Err(_) => return inner.call(Request::from_parts(parts, Body::empty())).await,
+                }
             } else {
-                let mut inner = inner;
-                inner.call(Request::from_parts(parts, Body::empty())).await
-            }
+                (body, None)
+            };
+
+            let method = parts.method.to_string();
+            let uri = parts.uri.to_string();
+            let ip = get_client_ip(extensions, headers);
+            let user_agent = get_user_agent(headers);
+            let request_headers = redactor.headers(headers);
+            let params = parse_query_params(&parts.uri).map(|mut params| {
+                redactor.redact(&mut params);
+                params
+            });
+
+            let response = inner.call(Request::from_parts(parts, body)).await?;
+            let (response_parts, response_body) = response.into_parts();
+
+            let (response_body, response) = if options.record_response {
+                match buffer_body(response_body, limit).await {
+                    Ok((body, Some(bytes))) => (
+                        body,
+                        redactor.payload(content_type(&response_parts.headers), &bytes),
+                    ),
+                    Ok((body, None)) => (body, Some(redactor.oversized())),
+                    Err(_) => (Body::empty(), None),
+                }
+            } else {
+                (response_body, None)
+            };
+
+            let end_time = Local::now().naive_local();
+            let duration = (end_time - start_time).num_milliseconds() as i32;
+
+            let context = OperationLogContext {
+                user_id,
+                username,
+                domain,
+                module_name,
+                description,
+                request_id,
+                method,
+                url: uri,
+                ip,
+                user_agent,
+                headers: request_headers,
+                params,
+                body: request_body,
+                response,
+                start_time,
+                end_time,
+                duration,
+                created_at: start_time,
+            };
+
+            global::send_audit_log(context).await;
+
+            Ok(Response::from_parts(response_parts, response_body))
         })
     }
 }
 
-/// 缓冲请求体，带容量限制
+/// 获取操作日志的模块与描述
+///
+/// 已登记的路由使用登记的服务名与摘要，未登记的路由以路径的第一段作为模块、描述为空。
+#[inline]
+fn describe_route(route: Option<RouteInfo>, uri: &Uri) -> (String, String) {
+    match route {
+        Some(route) => (route.service_name, route.summary),
+        None => (
+            uri.path()
+                .split('/')
+                .find(|segment| !segment.is_empty())
+                .unwrap_or_default()
+                .to_string(),
+            String::new(),
+        ),
+    }
+}
+
+/// 缓冲不超过 `limit` 字节的消息体
 ///
 /// # 参数
-/// * `body` - 请求体，类型为 axum 的 Body
+/// * `body` - 请求体或响应体
+/// * `limit` - 缓冲上限
 ///
 /// # 返回值
-/// * `Result<Bytes, Box<dyn std::error::Error + Send + Sync>>` -
-///   成功返回缓冲的字节数据，失败返回错误
+/// * `Result<(Body, Option<Bytes>), axum::Error>` - 可继续转发的消息体，
+///   以及未超过上限时的完整内容；超过上限时已读取的部分与剩余的流拼接，转发的内容保持不变
 ///
 /// # 错误
-/// * 当请求体大小超过 MAX_SIZE (32KB) 时返回错误
-/// * 当读取请求体流失败时返回错误
+/// * 当读取消息体流失败时返回错误
 #[inline]
-async fn buffer_body(body: Body) -> Result<Bytes, Box<dyn std::error::Error + Send + Sync>> {
-    const MAX_SIZE: usize = DEFAULT_BODY_CAPACITY * 2;
-    let mut bytes = BytesMut::with_capacity(DEFAULT_BODY_CAPACITY);
+async fn buffer_body(body: Body, limit: usize) -> Result<(Body, Option<Bytes>), axum::Error> {
+    let mut bytes = BytesMut::with_capacity(DEFAULT_BODY_CAPACITY.min(limit));
 
     let mut stream = body.into_data_stream();
     while let Some(chunk) = stream.next().await {
         let chunk = chunk?;
-        if bytes.len() + chunk.len() > MAX_SIZE {
-            return Err("Request body too large".into());
-        }
         bytes.extend_from_slice(&chunk);
+        if bytes.len() > limit {
+            let head = futures::stream::once(async move { Ok(bytes.freeze()) });
+            return Ok((Body::from_stream(head.chain(stream)), None));
+        }
     }
 
-    Ok(bytes.freeze())
+    let bytes = bytes.freeze();
+    Ok((Body::from(bytes.clone()), Some(bytes)))
 }
 
 /// 从请求头获取用户代理
@@ -272,13 +336,14 @@ fn parse_query_params(uri: &Uri) -> Option<Value> {
 
 #[cfg(test)]
 mod tests {
-    use std::convert::Infallible;
+    use std::{convert::Infallible, time::Duration};
 
     use axum::{
         body::{Body, HttpBody},
         http::{Method, Request, StatusCode},
     };
     use serde_json::json;
+    use tokio::sync::mpsc;
 
     use super::*;
     use crate::web::auth::{Claims, User};
@@ -317,11 +382,28 @@ mod tests {
             .unwrap()
     }
 
+    /// 从审计日志队列中取出请求对应的日志
+    ///
+    /// 其他测试并行发送的日志也会进入队列，按方法、地址与请求体查找。
+    fn receive_logged(
+        rx: &mut mpsc::Receiver<OperationLogContext>,
+        method: &str,
+        uri: &str,
+        body: &Option<Value>,
+    ) -> Option<OperationLogContext> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .find(|ctx| ctx.method == method && ctx.url == uri && &ctx.body == body)
+    }
+
     /// 验证上下文
-    async fn assert_context(method: &str, uri: &str, params: Option<Value>, body: Option<Value>) {
-        let ctx = OperationLogContext::get()
-            .await
-            .expect("Context should exist");
+    fn assert_context(
+        rx: &mut mpsc::Receiver<OperationLogContext>,
+        method: &str,
+        uri: &str,
+        params: Option<Value>,
+        body: Option<Value>,
+    ) {
+        let ctx = receive_logged(rx, method, uri, &body).expect("Context should exist");
         println!("验证上下文: {} {}", method, uri);
         println!("参数: {:?}", params);
         println!("请求体: {:?}", body);
@@ -385,9 +467,10 @@ mod tests {
         let service = tower::service_fn(|_req: Request<Body>| async move {
             Ok::<_, Infallible>(Response::new(Body::from("ok")))
         });
+        let (tx, mut rx) = mpsc::channel(64);
+        global::set_audit_log_queue(tx, Duration::from_secs(1)).await;
 
         for (method, uri, params, body) in test_cases {
-            OperationLogContext::clear().await;
             println!("\n▶ 测试场景: {} {}", method, uri);
             if let Some(p) = &params {
                 println!("  查询参数: {}", p);
@@ -396,16 +479,24 @@ mod tests {
                 println!("  请求体: {}", b);
             }
 
-            let mut middleware = OperationLogMiddleware {
-                inner: service.clone(),
-                enabled: true,
-            };
+            let mut middleware = OperationLogLayer::new(true).layer(service);
 
             let request = create_request(method.clone(), uri, body.clone());
             let _ = middleware.call(request).await.unwrap();
 
-            assert_context(&method.to_string(), uri, params, body).await;
+            assert_context(&mut rx, method.as_ref(), uri, params, body);
         }
+
+        // 未登记的路由按 `fallback` 指定的方式记录
+        let mut middleware = OperationLogLayer::new(true)
+            .fallback(OperationLogOptions::DISABLED)
+            .layer(service);
+        let body = Some(json!({"test": true}));
+        let request = create_request(Method::POST, "/unregistered", body.clone());
+        let _ = middleware.call(request).await.unwrap();
+        assert!(receive_logged(&mut rx, "POST", "/unregistered", &body).is_none());
+
+        global::close_audit_log_queue().await;
     }
 
     #[tokio::test]
@@ -443,10 +534,7 @@ mod tests {
         for (method, uri, body, expected_status) in test_cases {
             println!("\n▶ 测试错误场景: {:?}", expected_status);
 
-            let mut middleware = OperationLogMiddleware {
-                inner: service.clone(),
-                enabled: true,
-            };
+            let mut middleware = OperationLogLayer::new(true).layer(service);
 
             let mut request = create_request(method, uri, body);
 
@@ -462,6 +550,58 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_oversized_body_forwarded() {
+        let service = tower::service_fn(|req: Request<Body>| async move {
+            let bytes = axum::body::to_bytes(req.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            Ok::<_, Infallible>(Response::new(Body::from(bytes.len().to_string())))
+        });
+        let mut middleware = OperationLogLayer::new(true).layer(service);
+
+        let body = json!({ "large": "x".repeat(DEFAULT_BODY_CAPACITY * 3) });
+        let size = serde_json::to_vec(&body).unwrap().len();
+        let response = middleware
+            .call(create_request(Method::POST, "/test", Some(body)))
+            .await
+            .unwrap();
+
+        // 超过缓冲上限时不记录内容，但请求体完整转发
+        let forwarded = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(forwarded, size.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_describe_route() {
+        global::add_route(
+            RouteInfo::new("/log-test/:id", Method::PUT, "LogTestApi", "更新测试数据")
+                .with_operation_log(OperationLogOptions::RESPONSE_ONLY),
+        )
+        .await;
+
+        let route = global::find_route(&Method::PUT, "/log-test/{id}").await;
+        assert_eq!(
+            route.as_ref().map(|route| route.operation_log),
+            Some(OperationLogOptions::RESPONSE_ONLY)
+        );
+        assert!(global::find_route(&Method::GET, "/log-test/{id}")
+            .await
+            .is_none());
+
+        let uri: Uri = "/log-test/1".parse().unwrap();
+        assert_eq!(
+            describe_route(route, &uri),
+            ("LogTestApi".to_string(), "更新测试数据".to_string())
+        );
+        assert_eq!(
+            describe_route(None, &uri),
+            ("log-test".to_string(), String::new())
+        );
+    }
+
     #[tokio::test]
     async fn test_disabled_middleware() {
         println!("\n=== Testing Disabled Middleware ===");
@@ -471,10 +611,7 @@ mod tests {
             Ok::<_, Infallible>(Response::new(Body::from("ok")))
         });
 
-        let mut middleware = OperationLogMiddleware {
-            inner: service,
-            enabled: false,
-        };
+        let mut middleware = OperationLogLayer::new(false).layer(service);
 
         let request = create_request(Method::POST, "/test", Some(json!({"test": true})));
         let response = middleware.call(request).await.unwrap();
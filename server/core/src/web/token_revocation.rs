@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use redis::RedisResult;
+
+use crate::web::ttl_store::TtlStore;
+
+const REVOKED_JTI_PREFIX: &str = "auth_revoked_jti";
+
+/// 令牌吊销列表
+///
+/// 配置了 Redis 时使用 Redis 存储，多实例之间共享吊销状态；
+/// 否则使用进程内缓存。条目在对应令牌过期后自动清除。
+#[derive(Clone)]
+pub struct TokenRevocationStore {
+    store: TtlStore,
+}
+
+impl TokenRevocationStore {
+    pub fn new(store: TtlStore) -> Self {
+        Self { store }
+    }
+
+    /// 根据是否配置了全局 Redis 选择吊销列表实现
+    pub async fn current() -> Self {
+        Self::new(TtlStore::current().await)
+    }
+
+    fn get_key(jti: &str) -> String {
+        format!("{}:{}", REVOKED_JTI_PREFIX, jti)
+    }
+
+    /// 吊销指定 jti，`ttl` 为令牌剩余有效期
+    pub async fn revoke(&self, jti: &str, ttl: Duration) {
+        if let Err(e) = self.store.set(&Self::get_key(jti), "1", ttl).await {
+            tracing::error!("Failed to revoke token {}: {}", jti, e);
+        }
+    }
+
+    /// 检查指定 jti 是否已被吊销
+    ///
+    /// 吊销列表不可用时返回错误，由调用方决定拒绝还是放行请求。
+    pub async fn is_revoked(&self, jti: &str) -> RedisResult<bool> {
+        self.store.exists(&Self::get_key(jti)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::web::ttl_store::MemoryTtlStore;
+
+    fn memory_store() -> TokenRevocationStore {
+        TokenRevocationStore::new(TtlStore::Memory(Arc::new(MemoryTtlStore::new())))
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_revoke() {
+        let store = memory_store();
+        assert!(!store.is_revoked("jti-1").await.unwrap());
+
+        store.revoke("jti-1", Duration::from_secs(60)).await;
+        assert!(store.is_revoked("jti-1").await.unwrap());
+        assert!(!store.is_revoked("jti-2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_expiry() {
+        let store = memory_store();
+        store.revoke("jti-1", Duration::from_millis(50)).await;
+        assert!(store.is_revoked("jti-1").await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!store.is_revoked("jti-1").await.unwrap());
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use moka::{sync::Cache, Expiry};
+use once_cell::sync::Lazy;
+use redis::{FromRedisValue, RedisResult};
+use server_global::global::{RedisConnection, GLOBAL_PRIMARY_REDIS};
+
+/// 带过期时间的键值存储
+///
+/// 登录失败计数、令牌吊销列表、两步登录挑战与 OAuth state 共用的底层存储。
+/// 配置了 Redis 时使用 Redis 存储，多实例之间共享；否则使用进程内缓存。
+/// 进程内存储的操作不会失败，Redis 的错误原样返回，由调用方决定放行还是拒绝。
+#[derive(Clone)]
+pub enum TtlStore {
+    /// 进程内存储
+    Memory(Arc<MemoryTtlStore>),
+    /// Redis 存储
+    Redis(RedisConnection),
+}
+
+static MEMORY_TTL_STORE: Lazy<Arc<MemoryTtlStore>> = Lazy::new(|| Arc::new(MemoryTtlStore::new()));
+
+impl TtlStore {
+    /// 根据是否配置了全局 Redis 选择存储实现
+    pub async fn current() -> Self {
+        match GLOBAL_PRIMARY_REDIS.read().await.clone() {
+            Some(connection) => TtlStore::Redis(connection),
+            None => TtlStore::Memory(MEMORY_TTL_STORE.clone()),
+        }
+    }
+
+    /// 写入值，覆盖已有的值与过期时间
+    pub async fn set(&self, key: &str, value: &str, ttl: Duration) -> RedisResult<()> {
+        match self {
+            TtlStore::Memory(store) => {
+                store.set(key, value, ttl);
+                Ok(())
+            },
+            TtlStore::Redis(connection) => {
+                let cmd = redis::cmd("SET")
+                    .arg(key)
+                    .arg(value)
+                    .arg("EX")
+                    .arg(ttl.as_secs().max(1))
+                    .to_owned();
+                query(connection, cmd).await
+            },
+        }
+    }
+
+    /// 获取未过期的值
+    pub async fn get(&self, key: &str) -> RedisResult<Option<String>> {
+        match self {
+            TtlStore::Memory(store) => Ok(store.get(key)),
+            TtlStore::Redis(connection) => {
+                query(connection, redis::cmd("GET").arg(key).to_owned()).await
+            },
+        }
+    }
+
+    /// 取回并删除未过期的值
+    pub async fn take(&self, key: &str) -> RedisResult<Option<String>> {
+        match self {
+            TtlStore::Memory(store) => Ok(store.take(key)),
+            TtlStore::Redis(connection) => {
+                query(connection, redis::cmd("GETDEL").arg(key).to_owned()).await
+            },
+        }
+    }
+
+    /// 检查键是否存在且未过期
+    pub async fn exists(&self, key: &str) -> RedisResult<bool> {
+        match self {
+            TtlStore::Memory(store) => Ok(store.exists(key)),
+            TtlStore::Redis(connection) => {
+                query(connection, redis::cmd("EXISTS").arg(key).to_owned()).await
+            },
+        }
+    }
+
+    /// 删除键
+    pub async fn remove(&self, key: &str) -> RedisResult<()> {
+        match self {
+            TtlStore::Memory(store) => {
+                store.remove(key);
+                Ok(())
+            },
+            TtlStore::Redis(connection) => {
+                query(connection, redis::cmd("DEL").arg(key).to_owned()).await
+            },
+        }
+    }
+
+    /// 计数加一并返回新的计数
+    ///
+    /// 过期时间在第一次计数时设置，之后的计数不会延长。
+    pub async fn incr(&self, key: &str, ttl: Duration) -> RedisResult<u64> {
+        match self {
+            TtlStore::Memory(store) => Ok(store.incr(key, ttl)),
+            TtlStore::Redis(connection) => {
+                let count: u64 = query(connection, redis::cmd("INCR").arg(key).to_owned()).await?;
+                if count == 1 {
+                    let cmd = redis::cmd("EXPIRE")
+                        .arg(key)
+                        .arg(ttl.as_secs().max(1))
+                        .to_owned();
+                    query::<()>(connection, cmd).await?;
+                }
+                Ok(count)
+            },
+        }
+    }
+}
+
+async fn query<T: FromRedisValue>(connection: &RedisConnection, cmd: redis::Cmd) -> RedisResult<T> {
+    match connection {
+        RedisConnection::Single(client) => {
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            cmd.query_async(&mut conn).await
+        },
+        RedisConnection::Cluster(client) => {
+            let mut conn = client.get_async_connection().await?;
+            cmd.query_async(&mut conn).await
+        },
+    }
+}
+
+#[derive(Clone)]
+struct MemoryEntry {
+    value: String,
+    ttl: Duration,
+}
+
+struct MemoryExpiry;
+
+impl Expiry<String, MemoryEntry> for MemoryExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &MemoryEntry,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(value.ttl)
+    }
+}
+
+/// 基于 moka 的进程内存储，每个条目按写入时的 `ttl` 过期，更新计数不会延长过期时间
+pub struct MemoryTtlStore {
+    entries: Cache<String, MemoryEntry>,
+}
+
+impl MemoryTtlStore {
+    pub fn new() -> Self {
+        Self {
+            entries: Cache::builder().expire_after(MemoryExpiry).build(),
+        }
+    }
+
+    pub fn set(&self, key: &str, value: &str, ttl: Duration) {
+        // 先删除再写入，使过期时间从本次写入开始计算
+        self.entries.invalidate(key);
+        self.entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                value: value.to_string(),
+                ttl,
+            },
+        );
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries.get(key).map(|entry| entry.value)
+    }
+
+    pub fn take(&self, key: &str) -> Option<String> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+
+    pub fn exists(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn remove(&self, key: &str) {
+        self.entries.invalidate(key);
+    }
+
+    pub fn incr(&self, key: &str, ttl: Duration) -> u64 {
+        let entry = self
+            .entries
+            .entry(key.to_string())
+            .and_upsert_with(|entry| {
+                let count = entry
+                    .and_then(|entry| entry.value().value.parse::<u64>().ok())
+                    .unwrap_or(0);
+                MemoryEntry {
+                    value: (count + 1).to_string(),
+                    ttl,
+                }
+            })
+            .into_value();
+        entry.value.parse().unwrap_or(1)
+    }
+}
+
+impl Default for MemoryTtlStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_values() {
+        let store = TtlStore::Memory(Arc::new(MemoryTtlStore::new()));
+
+        store
+            .set("key", "value", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(store.exists("key").await.unwrap());
+        assert_eq!(store.get("key").await.unwrap().as_deref(), Some("value"));
+
+        assert_eq!(store.take("key").await.unwrap().as_deref(), Some("value"));
+        assert_eq!(store.take("key").await.unwrap(), None);
+
+        store
+            .set("key", "value", Duration::from_secs(60))
+            .await
+            .unwrap();
+        store.remove("key").await.unwrap();
+        assert!(!store.exists("key").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_incr() {
+        let store = TtlStore::Memory(Arc::new(MemoryTtlStore::new()));
+        let window = Duration::from_millis(100);
+
+        assert_eq!(store.incr("count", window).await.unwrap(), 1);
+        assert_eq!(store.incr("count", window).await.unwrap(), 2);
+
+        // 再次计数不延长过期时间
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(store.incr("count", window).await.unwrap(), 3);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(store.incr("count", window).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_memory_store_expiry() {
+        let store = MemoryTtlStore::new();
+        store.set("key", "value", Duration::from_millis(50));
+        assert!(store.exists("key"));
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!store.exists("key"));
+        assert_eq!(store.get("key"), None);
+    }
+}
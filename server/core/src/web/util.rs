@@ -1,4 +1,10 @@
+use std::net::IpAddr;
+
 use axum::http::HeaderMap;
+use server_config::ServerConfig;
+use server_global::global;
+
+use super::login_security::ip_matches;
 
 /// 客户端 IP 地址处理工具
 ///
@@ -58,6 +64,45 @@ impl ClientIp {
         "unknown".to_string()
     }
 
+    /// 解析客户端 IP 地址
+    ///
+    /// 只有连接的对端地址命中受信任代理时才读取代理请求头，否则直接使用对端地址，
+    /// 避免客户端伪造请求头冒充其他 IP。`X-Forwarded-For` 从右向左跳过受信任的代理，
+    /// 取第一个不受信任的地址。
+    pub fn resolve(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[String]) -> String {
+        let peer = peer.to_string();
+        if !ip_matches(trusted_proxies, &peer) {
+            return peer;
+        }
+
+        let chain: Vec<String> = Self::get_proxy_chain(headers)
+            .into_iter()
+            .filter(|ip| !ip.is_empty())
+            .collect();
+        if let Some(first) = chain.first() {
+            return chain
+                .iter()
+                .rev()
+                .find(|ip| !ip_matches(trusted_proxies, ip))
+                .unwrap_or(first)
+                .clone();
+        }
+
+        match Self::get_real_ip(headers) {
+            ip if ip == "unknown" => peer,
+            ip => ip,
+        }
+    }
+
+    /// 按 `server.trusted_proxies` 配置解析客户端 IP 地址
+    pub async fn get_client_ip(peer: IpAddr, headers: &HeaderMap) -> String {
+        let trusted_proxies = global::get_config::<ServerConfig>()
+            .await
+            .map(|config| config.trusted_proxies.clone())
+            .unwrap_or_default();
+        Self::resolve(peer, headers, &trusted_proxies)
+    }
+
     /// 检查 IP 地址是否有效
     ///
     /// # 参数
@@ -136,6 +181,33 @@ mod tests {
         assert_eq!(ClientIp::get_real_ip(&headers), "192.168.1.1");
     }
 
+    #[test]
+    fn test_resolve_trusted_proxies() {
+        let trusted = vec!["10.0.0.0/8".to_string()];
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Forwarded-For",
+            "203.0.113.9, 198.51.100.7, 10.0.0.2".parse().unwrap(),
+        );
+        headers.insert("X-Real-IP", "203.0.113.9".parse().unwrap());
+
+        // 非受信任的对端伪造的请求头被忽略
+        let peer: IpAddr = "192.0.2.1".parse().unwrap();
+        assert_eq!(ClientIp::resolve(peer, &headers, &trusted), "192.0.2.1");
+        assert_eq!(ClientIp::resolve(peer, &headers, &[]), "192.0.2.1");
+
+        // 受信任的代理转发时取最右侧不受信任的地址
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        assert_eq!(ClientIp::resolve(proxy, &headers, &trusted), "198.51.100.7");
+
+        headers.remove("X-Forwarded-For");
+        assert_eq!(ClientIp::resolve(proxy, &headers, &trusted), "203.0.113.9");
+        assert_eq!(
+            ClientIp::resolve(proxy, &HeaderMap::new(), &trusted),
+            "10.0.0.1"
+        );
+    }
+
     #[test]
     fn test_is_valid_ip() {
         assert!(ClientIp::is_valid_ip("192.168.1.1"));
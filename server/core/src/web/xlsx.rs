@@ -0,0 +1,280 @@
+//! 流式写出的 XLSX 工作簿
+//!
+//! 工作表逐行写入，每个工作表最多 1,048,576 行，超出后自动新建工作表。ZIP 以流模式写出，
+//! 条目使用数据描述符，不需要预先知道条目大小；工作表条目启用 ZIP64，文件大小不受 4 GiB 限制。
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    mem,
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use zip::{
+    result::ZipError,
+    write::{SimpleFileOptions, StreamWriter},
+    CompressionMethod, ZipWriter,
+};
+
+use crate::web::error::AppError;
+
+/// 单个工作表的最大行数
+const MAX_SHEET_ROWS: usize = 1_048_576;
+/// 单元格的最大字符数
+const MAX_CELL_CHARS: usize = 32_767;
+
+const SHEET_HEADER: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#
+);
+const SHEET_FOOTER: &str = "</sheetData></worksheet>";
+
+pub struct XlsxWriter {
+    zip: ZipWriter<StreamWriter<SharedBuffer>>,
+    buffer: SharedBuffer,
+    columns: Vec<String>,
+    sheets: usize,
+    sheet_rows: usize,
+}
+
+impl XlsxWriter {
+    /// 创建工作簿，每个工作表的第一行为 `columns`
+    pub fn new(columns: &[&str]) -> Self {
+        let buffer = SharedBuffer::default();
+        Self {
+            zip: ZipWriter::new_stream(buffer.clone()),
+            buffer,
+            columns: columns.iter().map(ToString::to_string).collect(),
+            sheets: 0,
+            sheet_rows: MAX_SHEET_ROWS,
+        }
+    }
+
+    /// 写入一行，返回可以输出的内容
+    pub fn row(&mut self, cells: &[&Value]) -> Result<Vec<u8>, AppError> {
+        if self.sheet_rows >= MAX_SHEET_ROWS {
+            self.start_sheet()?;
+        }
+
+        let mut xml = String::from("<row>");
+        cells.iter().for_each(|cell| write_cell(&mut xml, cell));
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes()).map_err(zip_error)?;
+        self.sheet_rows += 1;
+
+        Ok(self.buffer.take())
+    }
+
+    /// 写入工作簿结构与 ZIP 目录，返回剩余的内容
+    pub fn finish(mut self) -> Result<Vec<u8>, AppError> {
+        if self.sheets == 0 {
+            self.start_sheet()?;
+        }
+        self.zip
+            .write_all(SHEET_FOOTER.as_bytes())
+            .map_err(zip_error)?;
+
+        let sheets = 1..=self.sheets;
+
+        let mut content_types = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+            r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+            r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        ));
+        let mut workbook = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        ));
+        let mut workbook_rels = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        ));
+        for sheet in sheets {
+            let _ = write!(
+                content_types,
+                r#"<Override PartName="/xl/worksheets/sheet{sheet}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            );
+            let _ = write!(
+                workbook,
+                r#"<sheet name="Sheet{sheet}" sheetId="{sheet}" r:id="rId{sheet}"/>"#
+            );
+            let _ = write!(
+                workbook_rels,
+                r#"<Relationship Id="rId{sheet}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{sheet}.xml"/>"#
+            );
+        }
+        content_types.push_str("</Types>");
+        workbook.push_str("</sheets></workbook>");
+        workbook_rels.push_str("</Relationships>");
+
+        let root_rels = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+            "</Relationships>"
+        );
+
+        for (name, content) in [
+            ("[Content_Types].xml", content_types.as_str()),
+            ("_rels/.rels", root_rels),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", workbook_rels.as_str()),
+        ] {
+            self.zip
+                .start_file(name, entry_options())
+                .map_err(zip_error)?;
+            self.zip.write_all(content.as_bytes()).map_err(zip_error)?;
+        }
+
+        self.zip.finish().map_err(zip_error)?;
+        Ok(self.buffer.take())
+    }
+
+    fn start_sheet(&mut self) -> Result<(), AppError> {
+        if self.sheets > 0 {
+            self.zip
+                .write_all(SHEET_FOOTER.as_bytes())
+                .map_err(zip_error)?;
+        }
+
+        self.sheets += 1;
+        // 工作表可能超过 4 GiB，条目需要 ZIP64 扩展字段
+        self.zip
+            .start_file(
+                format!("xl/worksheets/sheet{}.xml", self.sheets),
+                entry_options().large_file(true),
+            )
+            .map_err(zip_error)?;
+        self.zip
+            .write_all(SHEET_HEADER.as_bytes())
+            .map_err(zip_error)?;
+
+        let mut xml = String::from("<row>");
+        self.columns.iter().for_each(|column| {
+            write_cell(&mut xml, &Value::String(column.clone()));
+        });
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes()).map_err(zip_error)?;
+        self.sheet_rows = 1;
+
+        Ok(())
+    }
+}
+
+/// 数字写为数值单元格，其余写为内联字符串，对象与数组写为 JSON 文本
+fn write_cell(xml: &mut String, value: &Value) {
+    let text = match value {
+        Value::Null => {
+            xml.push_str("<c/>");
+            return;
+        },
+        Value::Number(number) => {
+            let _ = write!(xml, "<c><v>{}</v></c>", number);
+            return;
+        },
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+
+    xml.push_str(r#"<c t="inlineStr"><is><t xml:space="preserve">"#);
+    for c in text.chars().take(MAX_CELL_CHARS) {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\t' | '\n' | '\r' => xml.push(c),
+            // XML 不允许的控制字符
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {},
+            c => xml.push(c),
+        }
+    }
+    xml.push_str("</t></is></c>");
+}
+
+fn entry_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn zip_error(e: impl Into<ZipError>) -> AppError {
+    AppError {
+        code: 500,
+        message: format!("XLSX export failed: {}", e.into()),
+    }
+}
+
+/// ZIP 写出器的输出缓冲区，每写入一行后取出已生成的内容
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Vec<u8> {
+        mem::take(&mut *self.0.lock())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use serde_json::json;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_xlsx_writer() {
+        let mut writer = XlsxWriter::new(&["id", "url"]);
+        let mut bytes = writer.row(&[&json!(1), &json!("/user?a=1&b=<2>")]).unwrap();
+        bytes.extend(writer.row(&[&json!(2), &Value::Null]).unwrap());
+        bytes.extend(writer.finish().unwrap());
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 5);
+
+        let sheet = read_entry(&mut archive, "xl/worksheets/sheet1.xml");
+        assert!(sheet.starts_with(SHEET_HEADER));
+        assert!(sheet.ends_with(SHEET_FOOTER));
+        assert!(sheet.contains("<c><v>1</v></c>"));
+        assert!(sheet.contains("/user?a=1&amp;b=&lt;2&gt;"));
+        assert!(sheet.contains("<c/>"));
+
+        let workbook = read_entry(&mut archive, "xl/workbook.xml");
+        assert!(workbook.contains(r#"<sheet name="Sheet1" sheetId="1" r:id="rId1"/>"#));
+    }
+
+    #[test]
+    fn test_xlsx_writer_without_rows() {
+        let bytes = XlsxWriter::new(&["id"]).finish().unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let sheet = read_entry(&mut archive, "xl/worksheets/sheet1.xml");
+        assert!(sheet.contains(r#"<t xml:space="preserve">id</t>"#));
+    }
+}
@@ -3,17 +3,22 @@ use std::{
     collections::HashMap,
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use aws_sdk_s3::Client as S3Client;
 use chrono::NaiveDateTime;
 use http::Method;
-use jsonwebtoken::{DecodingKey, EncodingKey, Validation};
+use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, EncodingKey, Validation};
 use mongodb::Client as MongoClient;
 use once_cell::sync::Lazy;
 use redis::{cluster::ClusterClient, Client};
 use sea_orm::DatabaseConnection;
+use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex, OnceCell, RwLock};
 
@@ -83,15 +88,53 @@ pub static GLOBAL_S3_POOL: Lazy<RwLock<HashMap<String, Arc<S3Client>>>> =
 //*****************************************************************************
 
 pub struct Keys {
+    pub algorithm: Algorithm,
+    /// 签名密钥的 kid，写入令牌头
+    pub kid: Option<String>,
     pub encoding: EncodingKey,
     pub decoding: DecodingKey,
+    /// 按 kid 索引的验签密钥，包含轮换期内保留的旧公钥
+    pub verification_keys: HashMap<String, DecodingKey>,
+    /// 对外公布的公钥集合
+    pub jwks: JwkSet,
 }
 
 impl Keys {
     pub fn new(secret: &[u8]) -> Self {
         Self {
+            algorithm: Algorithm::HS256,
+            kid: None,
             encoding: EncodingKey::from_secret(secret),
             decoding: DecodingKey::from_secret(secret),
+            verification_keys: HashMap::new(),
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    /// 使用非对称密钥构建，`decoding` 为签名密钥对应的公钥
+    pub fn asymmetric(
+        algorithm: Algorithm,
+        kid: String,
+        encoding: EncodingKey,
+        verification_keys: HashMap<String, DecodingKey>,
+        jwks: JwkSet,
+    ) -> Option<Self> {
+        let decoding = verification_keys.get(&kid)?.clone();
+        Some(Self {
+            algorithm,
+            kid: Some(kid),
+            encoding,
+            decoding,
+            verification_keys,
+            jwks,
+        })
+    }
+
+    /// 按令牌头中的 kid 选择验签密钥，未携带 kid 时使用签名密钥对应的公钥
+    pub fn decoding_key(&self, kid: Option<&str>) -> Option<&DecodingKey> {
+        match kid {
+            Some(kid) if !self.verification_keys.is_empty() => self.verification_keys.get(kid),
+            _ => Some(&self.decoding),
         }
     }
 }
@@ -181,12 +224,62 @@ pub async fn register_event_listeners(
 // 路由信息收集
 //*****************************************************************************
 
+/// 路由的操作日志记录方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperationLogOptions {
+    pub enabled: bool,
+    /// 记录请求体
+    pub record_body: bool,
+    /// 记录响应
+    pub record_response: bool,
+}
+
+impl OperationLogOptions {
+    /// 记录请求体与响应
+    pub const FULL: Self = Self {
+        enabled: true,
+        record_body: true,
+        record_response: true,
+    };
+    /// 只记录请求体，用于响应中含有密钥等敏感数据的路由
+    pub const REQUEST_ONLY: Self = Self {
+        enabled: true,
+        record_body: true,
+        record_response: false,
+    };
+    /// 只记录响应，用于请求体中含有密码等敏感数据的路由
+    pub const RESPONSE_ONLY: Self = Self {
+        enabled: true,
+        record_body: false,
+        record_response: true,
+    };
+    /// 只记录操作人、路径与耗时等信息
+    pub const METADATA: Self = Self {
+        enabled: true,
+        record_body: false,
+        record_response: false,
+    };
+    /// 不记录操作日志
+    pub const DISABLED: Self = Self {
+        enabled: false,
+        record_body: false,
+        record_response: false,
+    };
+}
+
+impl Default for OperationLogOptions {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RouteInfo {
     pub path: String,
     pub method: Method,
     pub service_name: String,
     pub summary: String,
+    pub operation_log: OperationLogOptions,
 }
 
 impl RouteInfo {
@@ -196,8 +289,15 @@ impl RouteInfo {
             method,
             service_name: service_name.to_string(),
             summary: summary.to_string(),
+            operation_log: OperationLogOptions::default(),
         }
     }
+
+    /// 设置路由的操作日志记录方式
+    pub fn with_operation_log(mut self, options: OperationLogOptions) -> Self {
+        self.operation_log = options;
+        self
+    }
 }
 
 pub static ROUTE_COLLECTOR: Lazy<Mutex<Vec<RouteInfo>>> = Lazy::new(|| Mutex::new(Vec::new()));
@@ -210,6 +310,34 @@ pub async fn get_collected_routes() -> Vec<RouteInfo> {
     ROUTE_COLLECTOR.lock().await.clone()
 }
 
+/// 按路径模板查找路由，`{id}` 与 `:id` 两种参数写法均可，末尾的 `/` 会被忽略
+pub async fn find_route(method: &Method, path: &str) -> Option<RouteInfo> {
+    let path = normalize_route_path(path);
+    ROUTE_COLLECTOR
+        .lock()
+        .await
+        .iter()
+        .find(|route| route.method == *method && normalize_route_path(&route.path) == path)
+        .cloned()
+}
+
+fn normalize_route_path(path: &str) -> String {
+    let path = path
+        .split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix('{')
+                .and_then(|segment| segment.strip_suffix('}'))
+                .map_or_else(|| segment.to_string(), |name| format!(":{}", name))
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    }
+}
+
 pub async fn clear_routes() {
     ROUTE_COLLECTOR.lock().await.clear();
 }
@@ -230,6 +358,7 @@ pub struct OperationLogContext {
     pub url: String,
     pub ip: String,
     pub user_agent: Option<String>,
+    pub headers: Option<Value>,
     pub params: Option<Value>,
     pub body: Option<Value>,
     pub response: Option<Value>,
@@ -258,6 +387,105 @@ impl OperationLogContext {
     }
 }
 
+//*****************************************************************************
+// 审计日志队列
+//*****************************************************************************
+
+/// 审计日志队列的运行指标
+#[derive(Debug, Default)]
+pub struct AuditLogMetrics {
+    /// 进入队列的日志数
+    pub enqueued: AtomicU64,
+    /// 队列已满或未启动时丢弃的日志数
+    pub dropped: AtomicU64,
+    /// 写入成功的日志数，每个写入目标分别计数
+    pub written: AtomicU64,
+    /// 批量写入的重试次数
+    pub retried: AtomicU64,
+    /// 重试耗尽后写入失败的日志数，每个写入目标分别计数
+    pub failed: AtomicU64,
+}
+
+/// 审计日志队列的指标快照
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogMetricsSnapshot {
+    pub enqueued: u64,
+    pub dropped: u64,
+    pub written: u64,
+    pub retried: u64,
+    pub failed: u64,
+    /// 队列中等待写入的日志数
+    pub queued: usize,
+    /// 队列容量，队列未启动时为 0
+    pub capacity: usize,
+}
+
+pub static AUDIT_LOG_METRICS: Lazy<AuditLogMetrics> = Lazy::new(AuditLogMetrics::default);
+
+struct AuditLogQueue {
+    tx: mpsc::Sender<OperationLogContext>,
+    enqueue_timeout: Duration,
+}
+
+static AUDIT_LOG_QUEUE: Lazy<RwLock<Option<AuditLogQueue>>> = Lazy::new(|| RwLock::new(None));
+
+/// 设置审计日志队列的发送端
+pub async fn set_audit_log_queue(tx: mpsc::Sender<OperationLogContext>, enqueue_timeout: Duration) {
+    *AUDIT_LOG_QUEUE.write().await = Some(AuditLogQueue {
+        tx,
+        enqueue_timeout,
+    });
+}
+
+/// 关闭审计日志队列，写入任务处理完队列中剩余的日志后退出
+pub async fn close_audit_log_queue() {
+    AUDIT_LOG_QUEUE.write().await.take();
+}
+
+/// 将操作日志放入审计日志队列
+///
+/// 队列已满时最多等待配置的时间，仍无法放入或队列未启动时丢弃并计入指标。
+pub async fn send_audit_log(context: OperationLogContext) {
+    let queue = AUDIT_LOG_QUEUE
+        .read()
+        .await
+        .as_ref()
+        .map(|queue| (queue.tx.clone(), queue.enqueue_timeout));
+    let Some((tx, enqueue_timeout)) = queue else {
+        AUDIT_LOG_METRICS.dropped.fetch_add(1, Ordering::Relaxed);
+        return;
+    };
+
+    match tx.send_timeout(context, enqueue_timeout).await {
+        Ok(()) => AUDIT_LOG_METRICS.enqueued.fetch_add(1, Ordering::Relaxed),
+        Err(_) => AUDIT_LOG_METRICS.dropped.fetch_add(1, Ordering::Relaxed),
+    };
+}
+
+/// 获取审计日志队列的指标快照
+pub async fn audit_log_metrics() -> AuditLogMetricsSnapshot {
+    let (queued, capacity) = AUDIT_LOG_QUEUE
+        .read()
+        .await
+        .as_ref()
+        .map(|queue| {
+            let capacity = queue.tx.max_capacity();
+            (capacity - queue.tx.capacity(), capacity)
+        })
+        .unwrap_or_default();
+
+    AuditLogMetricsSnapshot {
+        enqueued: AUDIT_LOG_METRICS.enqueued.load(Ordering::Relaxed),
+        dropped: AUDIT_LOG_METRICS.dropped.load(Ordering::Relaxed),
+        written: AUDIT_LOG_METRICS.written.load(Ordering::Relaxed),
+        retried: AUDIT_LOG_METRICS.retried.load(Ordering::Relaxed),
+        failed: AUDIT_LOG_METRICS.failed.load(Ordering::Relaxed),
+        queued,
+        capacity,
+    }
+}
+
 /// 异步发送字符串事件
 #[inline]
 pub fn send_string_event(msg: String) {
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use server_config::AuditChainConfig;
+use server_global::{global, project_info};
+use server_service::admin::audit_checkpoint_task;
+
+pub async fn initialize_audit_chain() {
+    let config = global::get_config::<AuditChainConfig>()
+        .await
+        .unwrap_or_default();
+    if !config.enabled {
+        return;
+    }
+
+    if config.signing_key_path.is_some() {
+        tokio::spawn(audit_checkpoint_task(Duration::from_secs(
+            config.checkpoint_interval_secs.max(1),
+        )));
+    }
+
+    project_info!("Audit chain initialization completed successfully")
+}
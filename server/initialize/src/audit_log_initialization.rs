@@ -0,0 +1,20 @@
+use server_config::AuditLogConfig;
+use server_global::{global, project_info};
+use server_service::admin::AuditLogWriter;
+
+pub async fn initialize_audit_log_writer() {
+    let config = global::get_config::<AuditLogConfig>()
+        .await
+        .unwrap_or_default();
+
+    AuditLogWriter::start(&config).await;
+
+    project_info!("Audit log writer initialization completed successfully")
+}
+
+/// 写出队列中剩余的操作日志，应在服务停止接收请求后调用
+pub async fn shutdown_audit_log_writer() {
+    AuditLogWriter::shutdown().await;
+
+    project_info!("Audit log writer shut down successfully")
+}
@@ -1,22 +1,227 @@
-use std::error::Error;
+use std::{error::Error, sync::Arc, time::Duration};
 
-use axum_casbin::CasbinAxumLayer;
-use casbin::DefaultModel;
-use sea_orm::Database;
+use async_trait::async_trait;
+use axum_casbin::{
+    reload_message, watch_policy_changes, CasbinAxumLayer, DomainPolicyLoader, LazyDomainPolicies,
+    PolicyChannel, PolicyChannelError,
+};
+use casbin::{Adapter, DefaultModel, Filter};
+use futures::{future, stream, stream::BoxStream, StreamExt};
+use redis::{
+    cluster::ClusterClientBuilder, AsyncCommands, Client, ProtocolVersion, PushInfo, PushKind,
+    RedisResult,
+};
+use sea_orm::{Database, DatabaseConnection};
 use sea_orm_adapter::SeaOrmAdapter;
+use server_config::{CasbinConfig, RedisConfig};
+use server_global::global::{get_config, RedisConnection};
+use server_middleware::register_policy_conditions;
 
-use crate::project_info;
+use crate::{project_error, project_info, redis_initialization::get_primary_redis};
+
+/// 策略变更通知的 Redis 频道
+const POLICY_CHANNEL: &str = "casbin:policy";
+
+/// 订阅断开后重新订阅的初始间隔与最大间隔
+const RESUBSCRIBE_MIN_DELAY: Duration = Duration::from_secs(1);
+const RESUBSCRIBE_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 按需加载时启动阶段读取的域
+const BOOTSTRAP_DOMAIN: &str = "built-in";
 
 pub async fn initialize_casbin(
     model_path: &str,
     db_url: &str,
 ) -> Result<CasbinAxumLayer, Box<dyn Error>> {
     project_info!("Initializing Casbin with model: {}", model_path);
+    let casbin_config = get_config::<CasbinConfig>().await.unwrap_or_default();
+    register_policy_conditions();
     let model = DefaultModel::from_file(model_path).await?;
     let db = Database::connect(db_url).await?;
-    let adapter = SeaOrmAdapter::new(db).await?;
+    let mut adapter = SeaOrmAdapter::new(db.clone()).await?;
+
+    let mut casbin_axum_layer = if casbin_config.lazy_load {
+        // 适配器处于过滤状态时，执行器创建时不会加载全部策略，保存策略时也只会写回已加载的范围
+        let mut bootstrap_model = DefaultModel::from_file(model_path).await?;
+        adapter
+            .load_filtered_policy(&mut bootstrap_model, domain_filter(BOOTSTRAP_DOMAIN))
+            .await?;
+        let loader = SeaOrmDomainLoader(SeaOrmAdapter::new(db).await?);
+        project_info!(
+            "Casbin policies are loaded per domain, cache capacity: {}",
+            casbin_config.domain_cache_capacity
+        );
+        CasbinAxumLayer::new(model, adapter)
+            .await?
+            .with_lazy_domains(LazyDomainPolicies::new(
+                Arc::new(loader),
+                casbin_config.domain_cache_capacity,
+            ))
+    } else {
+        CasbinAxumLayer::new(model, adapter).await?
+    };
+
+    // 配置了 Redis 时，通过发布订阅在多个实例间同步策略变更
+    if let Some(connection) = get_primary_redis().await {
+        let cluster_urls = get_config::<RedisConfig>()
+            .await
+            .and_then(|config| config.get_urls())
+            .unwrap_or_default();
+        let channel = Arc::new(RedisPolicyChannel::new(connection, cluster_urls));
+        match watch_policy_changes(
+            casbin_axum_layer.get_enforcer(),
+            channel,
+            casbin_axum_layer.get_lazy_domains(),
+        )
+        .await
+        {
+            Ok(instance_id) => {
+                project_info!("Casbin policy watcher started, instance: {}", instance_id)
+            },
+            Err(e) => project_error!("Failed to start Casbin policy watcher: {}", e),
+        }
+    }
 
-    let casbin_axum_layer = CasbinAxumLayer::new(model, adapter).await?;
     project_info!("Casbin initialization completed successfully");
     Ok(casbin_axum_layer)
 }
+
+/// 按域过滤策略，域位于 `p` 规则的第 2 列与 `g` 规则的第 3 列
+fn domain_filter(domain: &str) -> Filter<'_> {
+    Filter {
+        p: vec!["", domain],
+        g: vec!["", "", domain],
+    }
+}
+
+/// 从数据库读取单个域的策略
+struct SeaOrmDomainLoader(SeaOrmAdapter<DatabaseConnection>);
+
+#[async_trait]
+impl DomainPolicyLoader for SeaOrmDomainLoader {
+    async fn load_domain(&self, domain: &str) -> casbin::Result<Vec<(String, Vec<String>)>> {
+        self.0.find_filtered_policy(domain_filter(domain)).await
+    }
+}
+
+/// 基于 Redis 发布订阅的策略变更通知
+pub struct RedisPolicyChannel {
+    connection: RedisConnection,
+    /// 集群节点地址，集群模式下订阅需单独建立 RESP3 连接
+    cluster_urls: Vec<String>,
+}
+
+impl RedisPolicyChannel {
+    pub fn new(connection: RedisConnection, cluster_urls: Vec<String>) -> Self {
+        Self {
+            connection,
+            cluster_urls,
+        }
+    }
+}
+
+fn channel_error(e: redis::RedisError) -> PolicyChannelError {
+    PolicyChannelError(e.to_string())
+}
+
+/// 建立单机模式的订阅连接，返回消息内容流
+async fn subscribe_single(client: &Client) -> RedisResult<BoxStream<'static, String>> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(POLICY_CHANNEL).await?;
+    Ok(pubsub
+        .into_on_message()
+        .filter_map(|msg| future::ready(msg.get_payload::<String>().ok()))
+        .boxed())
+}
+
+#[async_trait]
+impl PolicyChannel for RedisPolicyChannel {
+    async fn publish(&self, payload: String) -> Result<(), PolicyChannelError> {
+        match &self.connection {
+            RedisConnection::Single(client) => {
+                let mut con = client
+                    .get_multiplexed_async_connection()
+                    .await
+                    .map_err(channel_error)?;
+                con.publish::<_, _, ()>(POLICY_CHANNEL, payload)
+                    .await
+                    .map_err(channel_error)
+            },
+            RedisConnection::Cluster(client) => {
+                let mut con = client.get_async_connection().await.map_err(channel_error)?;
+                con.publish::<_, _, ()>(POLICY_CHANNEL, payload)
+                    .await
+                    .map_err(channel_error)
+            },
+        }
+    }
+
+    async fn subscribe(&self) -> Result<BoxStream<'static, String>, PolicyChannelError> {
+        match &self.connection {
+            RedisConnection::Single(client) => {
+                let messages = subscribe_single(client).await.map_err(channel_error)?;
+
+                // 连接断开时消息流结束，重新订阅后通知重新加载策略，补齐断开期间错过的变更
+                Ok(stream::unfold(
+                    (client.clone(), messages),
+                    |(client, mut messages)| async move {
+                        if let Some(payload) = messages.next().await {
+                            return Some((payload, (client, messages)));
+                        }
+
+                        project_error!("Casbin policy subscription lost, resubscribing");
+                        let mut delay = RESUBSCRIBE_MIN_DELAY;
+                        loop {
+                            tokio::time::sleep(delay).await;
+                            match subscribe_single(&client).await {
+                                Ok(messages) => {
+                                    project_info!("Casbin policy subscription restored");
+                                    return Some((reload_message(), (client, messages)));
+                                },
+                                Err(e) => {
+                                    project_error!(
+                                        "Failed to resubscribe to Casbin policy changes: {}",
+                                        e
+                                    );
+                                    delay = (delay * 2).min(RESUBSCRIBE_MAX_DELAY);
+                                },
+                            }
+                        }
+                    },
+                )
+                .boxed())
+            },
+            RedisConnection::Cluster(_) => {
+                let (sender, receiver) = tokio::sync::mpsc::unbounded_channel::<PushInfo>();
+                let client = ClusterClientBuilder::new(self.cluster_urls.clone())
+                    .use_protocol(ProtocolVersion::RESP3)
+                    .push_sender(sender)
+                    .build()
+                    .map_err(channel_error)?;
+                let mut con = client.get_async_connection().await.map_err(channel_error)?;
+                con.subscribe(POLICY_CHANNEL).await.map_err(channel_error)?;
+
+                // 连接随流一起保留，断开后会自动重新订阅
+                Ok(
+                    stream::unfold((receiver, con), |(mut receiver, con)| async move {
+                        loop {
+                            let push = receiver.recv().await?;
+                            if push.kind != PushKind::Message {
+                                continue;
+                            }
+                            // 消息推送的数据依次为频道和内容
+                            if let Some(payload) = push
+                                .data
+                                .get(1)
+                                .and_then(|value| redis::from_redis_value::<String>(value).ok())
+                            {
+                                return Some((payload, (receiver, con)));
+                            }
+                        }
+                    })
+                    .boxed(),
+                )
+            },
+        }
+    }
+}
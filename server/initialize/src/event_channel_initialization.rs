@@ -4,7 +4,6 @@ use server_global::global;
 pub async fn initialize_event_channel() {
     use server_service::admin::{
         api_key_validate_listener, auth_login_listener, jwt_created_listener,
-        sys_operation_log_listener,
     };
 
     global::register_event_listeners(
@@ -14,10 +13,6 @@ pub async fn initialize_event_channel() {
                 SystemEvent::AuthLoggedInEvent.to_string(),
                 Box::new(|rx| Box::pin(auth_login_listener(rx))),
             ),
-            (
-                SystemEvent::AuditOperationLoggedEvent.to_string(),
-                Box::new(|rx| Box::pin(sys_operation_log_listener(rx))),
-            ),
             (
                 SystemEvent::AuthApiKeyValidatedEvent.to_string(),
                 Box::new(|rx| Box::pin(api_key_validate_listener(rx))),
@@ -1,4 +1,6 @@
 pub use access_key_initialization::initialize_access_key;
+pub use audit_chain_initialization::initialize_audit_chain;
+pub use audit_log_initialization::{initialize_audit_log_writer, shutdown_audit_log_writer};
 pub use aws_s3_initialization::{init_primary_s3, init_s3_pools};
 pub use casbin_initialization::initialize_casbin;
 pub use config_initialization::initialize_config;
@@ -8,12 +10,16 @@ pub use ip2region_initialization::init_xdb;
 pub use jwt_initialization::initialize_keys_and_validation;
 pub use log_tracing_init::initialize_log_tracing;
 pub use mongo_initialization::{init_mongo_pools, init_primary_mongo};
+pub use oauth_initialization::initialize_identity_providers;
+pub use password_hash_initialization::initialize_password_hasher;
 pub use redis_initialization::{init_primary_redis, init_redis_pools};
 pub use router_initialization::initialize_admin_router;
 pub use server_global::{project_error, project_info};
 pub use server_initialization::get_server_address;
 
 mod access_key_initialization;
+mod audit_chain_initialization;
+mod audit_log_initialization;
 mod aws_s3_initialization;
 mod casbin_initialization;
 mod config_initialization;
@@ -23,6 +29,8 @@ mod ip2region_initialization;
 mod jwt_initialization;
 mod log_tracing_init;
 mod mongo_initialization;
+mod oauth_initialization;
+mod password_hash_initialization;
 mod redis_initialization;
 mod router_initialization;
 mod server_initialization;
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use server_config::OAuthConfig;
+use server_core::oauth::{self, OidcProvider};
+use server_global::global;
+
+use crate::project_info;
+
+/// 按配置注册外部身份提供方，发现文档在首次使用时才拉取
+pub async fn initialize_identity_providers() {
+    let config = global::get_config::<OAuthConfig>()
+        .await
+        .unwrap_or_default();
+
+    for provider in config.providers.iter().cloned() {
+        let name = provider.name.clone();
+        oauth::register_provider(Arc::new(OidcProvider::new(provider))).await;
+        project_info!("Identity provider '{}' registered", name);
+    }
+}
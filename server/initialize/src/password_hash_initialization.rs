@@ -0,0 +1,21 @@
+use server_config::PasswordHashConfig;
+use server_global::global;
+use server_utils::SecureUtil;
+
+use crate::{project_error, project_info};
+
+pub async fn initialize_password_hasher() {
+    let config = global::get_config::<PasswordHashConfig>()
+        .await
+        .unwrap_or_default();
+
+    match SecureUtil::configure_argon2(config.memory_cost, config.iterations, config.parallelism) {
+        Ok(_) => project_info!(
+            "Argon2 password hasher initialized (m={}, t={}, p={})",
+            config.memory_cost,
+            config.iterations,
+            config.parallelism
+        ),
+        Err(e) => project_error!("Invalid Argon2 parameters, using defaults: {}", e),
+    }
+}
@@ -10,19 +10,23 @@ use server_core::sign::{
     api_key_middleware, protect_route, ApiKeySource, ApiKeyValidation, ComplexApiKeyConfig,
     SimpleApiKeyConfig, ValidatorType,
 };
-use server_core::web::{RequestId, RequestIdLayer};
-use server_global::global::{clear_routes, get_collected_routes, get_config};
+use server_core::web::{
+    field_mask::FieldMaskLayer, operation_log::OperationLogLayer, RequestId, RequestIdLayer,
+};
+use server_global::global::{clear_routes, get_collected_routes, get_config, OperationLogOptions};
 use server_middleware::jwt_auth_middleware;
 use server_router::admin::{
-    SysAccessKeyRouter, SysAuthenticationRouter, SysDomainRouter, SysEndpointRouter,
-    SysLoginLogRouter, SysMenuRouter, SysOperationLogRouter, SysOrganizationRouter, SysRoleRouter,
+    SysAccessKeyRouter, SysAuditChainRouter, SysAuthenticationRouter, SysDomainRouter,
+    SysEndpointRouter, SysFieldMaskRouter, SysLoginLogRouter, SysMenuRouter,
+    SysOnlineSessionRouter, SysOperationLogRouter, SysOrganizationRouter, SysRoleRouter,
     SysSandboxRouter, SysUserRouter,
 };
 use server_service::{
     admin::{
-        SysAccessKeyService, SysAuthService, SysAuthorizationService, SysDomainService,
-        SysEndpointService, SysLoginLogService, SysMenuService, SysOperationLogService,
-        SysOrganizationService, SysRoleService, SysUserService, TEndpointService,
+        SysAccessKeyService, SysAuditChainService, SysAuthService, SysAuthorizationService,
+        SysDomainService, SysEndpointService, SysFieldMaskService, SysLoginLogService,
+        SysMenuService, SysOnlineSessionService, SysOperationLogService, SysOrganizationService,
+        SysRoleService, SysUserService, TEndpointService,
     },
     SysEndpoint,
 };
@@ -82,9 +86,15 @@ async fn apply_layers<T: Send + Sync + 'static>(
     }
 
     if need_auth {
-        router = router.layer(axum::middleware::from_fn(move |req, next| {
-            jwt_auth_middleware(req, next, audience.as_str())
-        }));
+        // 字段脱敏与操作日志依赖 JWT 认证写入的用户信息，需位于认证层之内；
+        // 操作日志位于字段脱敏之外，记录的是脱敏后的响应
+        // 未登记的路由多为登录用户的自助操作，可能含有密码等敏感数据，只记录基本信息
+        router = router
+            .layer(FieldMaskLayer::new(Arc::new(SysFieldMaskService)))
+            .layer(OperationLogLayer::new(true).fallback(OperationLogOptions::METADATA))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                jwt_auth_middleware(req, next, audience.as_str())
+            }));
     }
 
     router
@@ -104,16 +114,18 @@ pub async fn initialize_admin_router() -> Router {
 
     // 初始化验证器
     // 根据是否配置了 Redis 来选择 nonce 存储实现
-    let nonce_store_factory =
-        if let Some(_) = crate::redis_initialization::get_primary_redis().await {
-            // 如果 Redis 可用，使用 Redis 作为 nonce 存储
-            project_info!("Using Redis for nonce storage");
-            server_core::sign::create_redis_nonce_store_factory("api_key")
-        } else {
-            // 否则使用内存存储
-            project_info!("Using memory for nonce storage");
-            server_core::sign::create_memory_nonce_store_factory()
-        };
+    let nonce_store_factory = if crate::redis_initialization::get_primary_redis()
+        .await
+        .is_some()
+    {
+        // 如果 Redis 可用，使用 Redis 作为 nonce 存储
+        project_info!("Using Redis for nonce storage");
+        server_core::sign::create_redis_nonce_store_factory("api_key")
+    } else {
+        // 否则使用内存存储
+        project_info!("Using memory for nonce storage");
+        server_core::sign::create_memory_nonce_store_factory()
+    };
 
     server_core::sign::init_validators_with_nonce_store(None, nonce_store_factory.clone()).await;
 
@@ -195,6 +207,14 @@ pub async fn initialize_admin_router() -> Router {
         None
     );
 
+    merge_router!(
+        SysAuthenticationRouter::init_well_known_router().await,
+        None,
+        false,
+        false,
+        None
+    );
+
     let auth_router = SysAuthenticationRouter::init_authorization_router()
         .await
         .layer(Extension(Arc::new(SysAuthService) as Arc<SysAuthService>))
@@ -223,6 +243,14 @@ pub async fn initialize_admin_router() -> Router {
         None
     );
 
+    merge_router!(
+        SysUserRouter::init_user_self_router().await,
+        SysUserService,
+        false,
+        true,
+        None
+    );
+
     merge_router!(
         SysMenuRouter::init_menu_router().await,
         SysMenuService,
@@ -281,6 +309,13 @@ pub async fn initialize_admin_router() -> Router {
         true,
         None
     );
+    merge_router!(
+        SysOnlineSessionRouter::init_online_session_router().await,
+        SysOnlineSessionService,
+        true,
+        true,
+        None
+    );
     merge_router!(
         SysOperationLogRouter::init_operation_log_router().await,
         SysOperationLogService,
@@ -292,8 +327,24 @@ pub async fn initialize_admin_router() -> Router {
     merge_router!(
         SysOrganizationRouter::init_organization_router().await,
         SysOrganizationService,
-        false,
-        false,
+        true,
+        true,
+        None
+    );
+
+    merge_router!(
+        SysFieldMaskRouter::init_field_mask_router().await,
+        SysFieldMaskService,
+        true,
+        true,
+        None
+    );
+
+    merge_router!(
+        SysAuditChainRouter::init_audit_chain_router().await,
+        SysAuditChainService,
+        true,
+        true,
         None
     );
 
@@ -332,7 +383,7 @@ async fn process_collected_routes() {
         .map(|route| {
             let resource = route.path.split('/').nth(1).unwrap_or("").to_string();
             SysEndpoint {
-                id: generate_id(&route.path, &route.method.to_string()),
+                id: generate_id(&route.path, route.method.as_ref()),
                 path: route.path.clone(),
                 method: route.method.to_string(),
                 action: "rw".to_string(),
@@ -16,6 +16,7 @@ mod tests {
     use server_core::web::{
         auth::{Claims, User},
         res::Res,
+        token_revocation::TokenRevocationStore,
     };
     use server_initialize::{initialize_config, initialize_keys_and_validation};
     use server_middleware::jwt_auth_middleware;
@@ -65,6 +66,68 @@ mod tests {
         println!("body_str is {}", body_str);
     }
 
+    #[tokio::test]
+    async fn test_revoked_token_rejected() {
+        initialize_config("../resources/application.yaml").await;
+        initialize_keys_and_validation().await;
+
+        let app =
+            Router::new()
+                .route("/pen/1", get(user_info_handler))
+                .layer(axum::middleware::from_fn(move |req, next| {
+                    jwt_auth_middleware(req, next, Audience::ManagementPlatform.as_str())
+                }));
+
+        let jti = "01JREVOKEDTOKENTEST000000";
+        let token = generate_jwt_with_jti(jti);
+        let request = || {
+            Request::builder()
+                .uri("/pen/1")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response_code(response).await, StatusCode::OK.as_u16());
+
+        TokenRevocationStore::current()
+            .await
+            .revoke(jti, std::time::Duration::from_secs(60))
+            .await;
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(
+            response_code(response).await,
+            StatusCode::UNAUTHORIZED.as_u16()
+        );
+    }
+
+    async fn response_code(response: axum::response::Response) -> u16 {
+        let body_bytes = axum::body::to_bytes(response.into_body(), 10000)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        body["code"].as_u64().unwrap() as u16
+    }
+
+    fn generate_jwt_with_jti(jti: &str) -> String {
+        let mut claims = Claims::new(
+            "admin".to_string(),
+            Audience::ManagementPlatform.as_str().to_string(),
+            "alice".to_string(),
+            vec!["example_role".to_string()],
+            "domain1".to_string(),
+            None,
+        );
+        let now = Utc::now();
+        claims.set_exp((now + Duration::seconds(7200)).timestamp() as usize);
+        claims.set_jti(jti.to_string());
+
+        let encoding_key = EncodingKey::from_secret("soybean-admin-rust".as_ref());
+        encode(&Header::default(), &claims, &encoding_key).unwrap()
+    }
+
     fn generate_jwt() -> String {
         let mut claims = Claims::new(
             "admin".to_string(),
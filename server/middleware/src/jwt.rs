@@ -3,7 +3,13 @@ use axum::{
 };
 use axum_casbin::CasbinVals;
 use headers::{authorization::Bearer, Authorization, HeaderMapExt};
-use server_core::web::{auth::User, jwt::JwtUtils, res::Res};
+use server_config::{CasbinConfig, JwtConfig, ServerConfig};
+use server_core::web::{
+    auth::User, jwt::JwtUtils, res::Res, token_revocation::TokenRevocationStore,
+};
+use server_global::global::get_config;
+
+use crate::policy_condition::request_attributes;
 
 pub async fn jwt_auth_middleware(
     mut req: Request<Body>,
@@ -24,10 +30,46 @@ pub async fn jwt_auth_middleware(
     match JwtUtils::validate_token(&token, audience).await {
         Ok(data) => {
             let claims = data.claims;
+            if let Some(jti) = claims.jti() {
+                match TokenRevocationStore::current().await.is_revoked(jti).await {
+                    Ok(false) => {},
+                    Ok(true) => {
+                        return Res::<String>::new_error(
+                            StatusCode::UNAUTHORIZED.as_u16(),
+                            "Token has been revoked",
+                        )
+                        .into_response();
+                    },
+                    Err(e) => {
+                        let fail_open = get_config::<JwtConfig>()
+                            .await
+                            .is_some_and(|config| config.revocation_fail_open);
+                        tracing::error!("Failed to check token revocation for {}: {}", jti, e);
+                        // 默认拒绝请求，避免吊销列表不可用时已注销的令牌继续生效
+                        if !fail_open {
+                            return Res::<String>::new_error(
+                                StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                                "Token revocation status unavailable",
+                            )
+                            .into_response();
+                        }
+                    },
+                }
+            }
+
             let user = User::from(claims);
+            let condition_headers = get_config::<CasbinConfig>()
+                .await
+                .map(|config| config.condition_headers.clone())
+                .unwrap_or_default();
+            let trusted_proxies = get_config::<ServerConfig>()
+                .await
+                .map(|config| config.trusted_proxies.clone())
+                .unwrap_or_default();
             let vals = CasbinVals {
                 subject: user.subject(),
                 domain: Option::from(user.domain()),
+                attributes: request_attributes(&req, &condition_headers, &trusted_proxies),
             };
             req.extensions_mut().insert(user);
             req.extensions_mut().insert(vals);
@@ -1,3 +1,5 @@
 mod jwt;
+mod policy_condition;
 
 pub use jwt::jwt_auth_middleware;
+pub use policy_condition::register_policy_conditions;
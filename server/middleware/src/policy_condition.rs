@@ -0,0 +1,144 @@
+use std::net::SocketAddr;
+
+use axum::{body::Body, extract::ConnectInfo, extract::Request};
+use axum_casbin::{register_condition, RequestAttributes};
+use chrono::{Datelike, Local, NaiveDateTime};
+use server_core::web::{
+    login_security::{ip_matches, within_login_hours},
+    util::ClientIp,
+    RequestId,
+};
+
+/// 请求时间格式，精确到分钟以便决策缓存命中
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// 注册策略条件函数
+///
+/// - `ip(10.0.0.0/8, 192.168.1.10)`：客户端 IP 命中任一 CIDR 或 IP
+/// - `time(09:00-18:00)`：请求时间处于时段内，开始晚于结束时视为跨天
+/// - `weekday(1-5)` / `weekday(1,3,5)`：请求日期为指定星期，周一为 1
+/// - `header(X-Tenant-Region=cn)`：请求头等于指定值，请求头需配置在 `condition_headers` 中
+pub fn register_policy_conditions() {
+    register_condition("ip", ip_condition);
+    register_condition("time", time_condition);
+    register_condition("weekday", weekday_condition);
+    register_condition("header", header_condition);
+}
+
+/// 收集策略条件所需的请求属性
+///
+/// 客户端 IP 按 `trusted_proxies` 从连接的对端地址解析，无法获取对端地址时为空。
+pub(crate) fn request_attributes(
+    req: &Request<Body>,
+    headers: &[String],
+    trusted_proxies: &[String],
+) -> RequestAttributes {
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| ClientIp::resolve(addr.ip(), req.headers(), trusted_proxies));
+
+    RequestAttributes {
+        ip,
+        time: Some(Local::now().format(TIME_FORMAT).to_string()),
+        headers: headers
+            .iter()
+            .filter_map(|name| {
+                let value = req.headers().get(name.as_str())?.to_str().ok()?;
+                Some((name.to_ascii_lowercase(), value.to_string()))
+            })
+            .collect(),
+        request_id: req
+            .extensions()
+            .get::<RequestId>()
+            .map(|request_id| request_id.to_string()),
+    }
+}
+
+fn request_time(attributes: &RequestAttributes) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(attributes.time.as_deref()?, TIME_FORMAT).ok()
+}
+
+fn ip_condition(attributes: &RequestAttributes, args: &str) -> bool {
+    let rules: Vec<String> = args.split(',').map(str::to_string).collect();
+    attributes
+        .ip
+        .as_deref()
+        .is_some_and(|ip| ip_matches(&rules, ip))
+}
+
+fn time_condition(attributes: &RequestAttributes, args: &str) -> bool {
+    let (Some(time), Some((start, end))) = (request_time(attributes), args.split_once('-')) else {
+        return false;
+    };
+    within_login_hours(start, end, time.time()).unwrap_or(false)
+}
+
+fn weekday_condition(attributes: &RequestAttributes, args: &str) -> bool {
+    let Some(time) = request_time(attributes) else {
+        return false;
+    };
+    let today = time.weekday().number_from_monday();
+
+    args.split(',').any(|day| {
+        let day = day.trim();
+        let (start, end) = day.split_once('-').unwrap_or((day, day));
+        match (start.trim().parse::<u32>(), end.trim().parse::<u32>()) {
+            (Ok(start), Ok(end)) => (start..=end).contains(&today),
+            _ => false,
+        }
+    })
+}
+
+fn header_condition(attributes: &RequestAttributes, args: &str) -> bool {
+    let Some((name, value)) = args.split_once('=') else {
+        return false;
+    };
+    attributes.header(name.trim()) == Some(value.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum_casbin::evaluate_condition;
+
+    use super::*;
+
+    fn attributes(time: &str) -> RequestAttributes {
+        RequestAttributes {
+            ip: Some("10.1.2.3".to_string()),
+            time: Some(time.to_string()),
+            headers: [("x-tenant-region".to_string(), "cn".to_string())].into(),
+            request_id: None,
+        }
+    }
+
+    #[test]
+    fn test_policy_conditions() {
+        register_policy_conditions();
+        // 2024-11-29 为周五
+        let attrs = attributes("2024-11-29T10:30");
+
+        assert!(evaluate_condition("ip(192.168.0.0/16, 10.0.0.0/8)", &attrs));
+        assert!(!evaluate_condition("ip(192.168.0.0/16)", &attrs));
+        assert!(evaluate_condition("time(09:00-18:00)", &attrs));
+        assert!(evaluate_condition("time(22:00-11:00)", &attrs));
+        assert!(!evaluate_condition("time(11:00-22:00)", &attrs));
+        assert!(evaluate_condition("weekday(1-5)", &attrs));
+        assert!(!evaluate_condition("weekday(6,7)", &attrs));
+        assert!(evaluate_condition("header(X-Tenant-Region=cn)", &attrs));
+        assert!(!evaluate_condition("header(X-Tenant-Region=us)", &attrs));
+        assert!(evaluate_condition(
+            "ip(10.0.0.0/8) && weekday(1-5) && time(09:00-18:00)",
+            &attrs
+        ));
+        assert!(!evaluate_condition(
+            "time(09:00-18:00) && unknown()",
+            &attrs
+        ));
+        assert!(!evaluate_condition("time(bad)", &attrs));
+        assert!(!evaluate_condition(
+            "ip(10.0.0.0/8)",
+            &RequestAttributes::default()
+        ));
+    }
+}
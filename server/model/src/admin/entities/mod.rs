@@ -5,14 +5,22 @@ pub mod prelude;
 pub mod casbin_rule;
 pub mod sea_orm_active_enums;
 pub mod sys_access_key;
+pub mod sys_audit_checkpoint;
 pub mod sys_domain;
 pub mod sys_endpoint;
+pub mod sys_field_mask;
 pub mod sys_login_log;
 pub mod sys_menu;
 pub mod sys_operation_log;
 pub mod sys_organization;
 pub mod sys_role;
+pub mod sys_role_data_scope;
 pub mod sys_role_menu;
 pub mod sys_tokens;
 pub mod sys_user;
+pub mod sys_user_identity;
+pub mod sys_user_organization;
+pub mod sys_user_password_history;
+pub mod sys_user_recovery_code;
 pub mod sys_user_role;
+pub mod sys_user_totp;
@@ -2,10 +2,15 @@
 
 pub use super::{
     casbin_rule::Entity as CasbinRule, sys_access_key::Entity as SysAccessKey,
-    sys_domain::Entity as SysDomain, sys_endpoint::Entity as SysEndpoint,
+    sys_audit_checkpoint::Entity as SysAuditCheckpoint, sys_domain::Entity as SysDomain,
+    sys_endpoint::Entity as SysEndpoint, sys_field_mask::Entity as SysFieldMask,
     sys_login_log::Entity as SysLoginLog, sys_menu::Entity as SysMenu,
     sys_operation_log::Entity as SysOperationLog, sys_organization::Entity as SysOrganization,
-    sys_role::Entity as SysRole, sys_role_menu::Entity as SysRoleMenu,
-    sys_tokens::Entity as SysTokens, sys_user::Entity as SysUser,
-    sys_user_role::Entity as SysUserRole,
+    sys_role::Entity as SysRole, sys_role_data_scope::Entity as SysRoleDataScope,
+    sys_role_menu::Entity as SysRoleMenu, sys_tokens::Entity as SysTokens,
+    sys_user::Entity as SysUser, sys_user_identity::Entity as SysUserIdentity,
+    sys_user_organization::Entity as SysUserOrganization,
+    sys_user_password_history::Entity as SysUserPasswordHistory,
+    sys_user_recovery_code::Entity as SysUserRecoveryCode, sys_user_role::Entity as SysUserRole,
+    sys_user_totp::Entity as SysUserTotp,
 };
@@ -24,3 +24,57 @@ pub enum Status {
     #[sea_orm(string_value = "ENABLED")]
     ENABLED,
 }
+/// 角色的数据范围，决定分页查询可返回的数据行
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Deserialize,
+    Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DataScope {
+    /// 全部数据
+    #[default]
+    #[sea_orm(string_value = "ALL")]
+    All,
+    /// 本域数据
+    #[sea_orm(string_value = "DOMAIN")]
+    Domain,
+    /// 本组织数据
+    #[sea_orm(string_value = "ORG")]
+    Org,
+    /// 本组织及下级组织数据
+    #[sea_orm(string_value = "ORG_AND_CHILDREN")]
+    OrgAndChildren,
+    /// 仅本人数据
+    #[sea_orm(string_value = "SELF_ONLY")]
+    SelfOnly,
+    /// 自定义组织数据
+    #[sea_orm(string_value = "CUSTOM")]
+    Custom,
+}
+/// 响应字段的脱敏方式
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Deserialize, Serialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MaskStrategy {
+    /// 从响应中移除
+    #[sea_orm(string_value = "HIDE")]
+    Hide,
+    /// 部分遮盖，如 `138****1234`
+    #[sea_orm(string_value = "PARTIAL")]
+    Partial,
+    /// 替换为 SHA-256 摘要
+    #[sea_orm(string_value = "HASH")]
+    Hash,
+}
@@ -30,6 +30,11 @@ pub struct Model {
     pub created_at: DateTime,
     #[sea_orm(column_type = "Text")]
     pub created_by: String,
+    pub chain_seq: Option<i64>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub prev_hash: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub hash: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
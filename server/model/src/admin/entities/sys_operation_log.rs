@@ -31,6 +31,8 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub user_agent: Option<String>,
     #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub headers: Option<JsonValue>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
     pub params: Option<JsonValue>,
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub body: Option<JsonValue>,
@@ -40,6 +42,11 @@ pub struct Model {
     pub end_time: DateTime,
     pub duration: i32,
     pub created_at: DateTime,
+    pub chain_seq: Option<i64>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub prev_hash: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub hash: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
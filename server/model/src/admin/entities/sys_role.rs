@@ -3,7 +3,7 @@
 use sea_orm::entity::prelude::*;
 use serde::Serialize;
 
-use super::sea_orm_active_enums::Status;
+use super::sea_orm_active_enums::{DataScope, Status};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
 #[sea_orm(table_name = "sys_role")]
@@ -19,6 +19,7 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub pid: String,
     pub status: Status,
+    pub data_scope: DataScope,
     pub created_at: DateTime,
     #[sea_orm(column_type = "Text")]
     pub created_by: String,
@@ -33,6 +34,8 @@ pub enum Relation {
     SysRoleMenu,
     #[sea_orm(has_many = "super::sys_user_role::Entity")]
     SysUserRole,
+    #[sea_orm(has_many = "super::sys_role_data_scope::Entity")]
+    SysRoleDataScope,
 }
 
 impl Related<super::sys_role_menu::Entity> for Entity {
@@ -47,4 +50,10 @@ impl Related<super::sys_user_role::Entity> for Entity {
     }
 }
 
+impl Related<super::sys_role_data_scope::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysRoleDataScope.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -32,6 +32,8 @@ pub struct Model {
     pub updated_at: Option<DateTime>,
     #[sea_orm(column_type = "Text", nullable)]
     pub updated_by: Option<String>,
+    pub password_changed_at: Option<DateTime>,
+    pub must_change_password: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -44,6 +46,8 @@ pub enum Relation {
     SysDomain,
     #[sea_orm(has_many = "super::sys_user_role::Entity")]
     SysUserRole,
+    #[sea_orm(has_one = "super::sys_user_organization::Entity")]
+    SysUserOrganization,
 }
 
 impl Related<super::sys_domain::Entity> for Entity {
@@ -58,4 +62,10 @@ impl Related<super::sys_user_role::Entity> for Entity {
     }
 }
 
+impl Related<super::sys_user_organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SysUserOrganization.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,22 +1,39 @@
 pub use sys_access_key::{AccessKeyPageRequest, CreateAccessKeyInput};
-pub use sys_authentication::LoginInput;
-pub use sys_authorization::{AssignPermissionDto, AssignRouteDto, AssignUserDto};
+pub use sys_audit_chain::{AuditLogType, VerifyAuditChainInput};
+pub use sys_authentication::{
+    LoginInput, OAuthCallbackInput, RefreshTokenInput, TotpCodeInput, TotpLoginInput,
+};
+pub use sys_authorization::{
+    AssignPermissionDto, AssignRouteDto, AssignUserDto, ExplainPermissionDto, ExportPolicyDto,
+    ImportPolicyDto, PolicyFormat, PolicyPageRequest, PolicyRuleDto, PolicyRulesDto, PolicyType,
+    ReachableEndpointsDto, ReplacePoliciesDto,
+};
 pub use sys_domain::{CreateDomainInput, DomainPageRequest, UpdateDomainInput};
 pub use sys_endpoint::EndpointPageRequest;
-pub use sys_login_log::LoginLogPageRequest;
-pub use sys_menu::{CreateMenuInput, UpdateMenuInput};
-pub use sys_operation_log::OperationLogPageRequest;
-pub use sys_organization::OrganizationPageRequest;
+pub use sys_field_mask::{CreateFieldMaskInput, FieldMaskPageRequest, UpdateFieldMaskInput};
+pub use sys_login_log::{LoginLogExportRequest, LoginLogPageRequest};
+pub use sys_menu::{CreateMenuInput, MenuPageRequest, UpdateMenuInput};
+pub use sys_online_session::OnlineSessionPageRequest;
+pub use sys_operation_log::{OperationLogExportRequest, OperationLogPageRequest};
+pub use sys_organization::{
+    AssignOrganizationUserInput, CreateOrganizationInput, MoveOrganizationInput,
+    OrganizationPageRequest, UpdateOrganizationInput,
+};
 pub use sys_role::{CreateRoleInput, RolePageRequest, UpdateRoleInput};
-pub use sys_user::{CreateUserInput, UpdateUserInput, UserPageRequest};
+pub use sys_user::{
+    ChangePasswordInput, CreateUserInput, ResetPasswordInput, UpdateUserInput, UserPageRequest,
+};
 
 mod sys_access_key;
+mod sys_audit_chain;
 mod sys_authentication;
 mod sys_authorization;
 mod sys_domain;
 mod sys_endpoint;
+mod sys_field_mask;
 mod sys_login_log;
 mod sys_menu;
+mod sys_online_session;
 mod sys_operation_log;
 mod sys_organization;
 mod sys_role;
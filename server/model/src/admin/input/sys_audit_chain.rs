@@ -0,0 +1,30 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// 审计日志类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLogType {
+    /// 操作日志
+    Operation,
+    /// 登录日志
+    Login,
+}
+
+impl AuditLogType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditLogType::Operation => "operation",
+            AuditLogType::Login => "login",
+        }
+    }
+}
+
+/// 校验一个域在时间范围内的哈希链，未登录请求的操作日志属于空字符串域
+#[derive(Debug, Deserialize)]
+pub struct VerifyAuditChainInput {
+    pub log_type: AuditLogType,
+    pub domain: String,
+    pub start_time: NaiveDateTime,
+    pub end_time: NaiveDateTime,
+}
@@ -7,4 +7,45 @@ pub struct LoginInput {
     pub identifier: String,
     #[validate(length(min = 6, message = "Password cannot be empty"))]
     pub password: String,
+    /// 登录域，未指定时登录内置域
+    #[serde(default = "default_login_domain")]
+    #[validate(length(min = 1, message = "Domain cannot be empty"))]
+    pub domain: String,
+}
+
+fn default_login_domain() -> String {
+    "built-in".to_string()
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenInput {
+    #[validate(length(min = 1, message = "Refresh token cannot be empty"))]
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpLoginInput {
+    #[validate(length(min = 1, message = "Challenge token cannot be empty"))]
+    pub challenge_token: String,
+    /// TOTP 验证码或恢复码
+    #[validate(length(min = 6, message = "Code cannot be empty"))]
+    pub code: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct TotpCodeInput {
+    /// TOTP 验证码；停用时也可使用恢复码
+    #[validate(length(min = 6, message = "Code cannot be empty"))]
+    pub code: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct OAuthCallbackInput {
+    /// 提供方回调携带的授权码
+    #[validate(length(min = 1, message = "Authorization code cannot be empty"))]
+    pub code: String,
+    #[validate(length(min = 1, message = "State cannot be empty"))]
+    pub state: String,
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use server_core::web::page::PageRequest;
 use validator::Validate;
 
 #[derive(Debug, Deserialize, Serialize, Validate)]
@@ -36,3 +37,163 @@ pub struct AssignUserDto {
     #[validate(length(min = 1, message = "Users array cannot be empty"))]
     pub user_ids: Vec<String>,
 }
+
+/// 权限判定调试，`user_id` 与 `subject` 二选一
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainPermissionDto {
+    /// 用户 ID，提供时使用该用户的全部角色判定
+    pub user_id: Option<String>,
+
+    /// 角色编码
+    pub subject: Option<String>,
+
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    #[validate(length(min = 1, message = "path cannot be empty"))]
+    pub path: String,
+
+    #[validate(length(min = 1, message = "method cannot be empty"))]
+    pub method: String,
+
+    /// 判定策略条件使用的客户端 IP
+    pub ip: Option<String>,
+
+    /// 判定策略条件使用的时间，格式为 `%Y-%m-%dT%H:%M`，默认为当前时间
+    pub time: Option<String>,
+}
+
+/// 查询可访问的接口，`user_id` 与 `subject` 二选一
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReachableEndpointsDto {
+    pub user_id: Option<String>,
+
+    pub subject: Option<String>,
+
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    /// 判定策略条件使用的客户端 IP
+    pub ip: Option<String>,
+
+    /// 判定策略条件使用的时间，格式为 `%Y-%m-%dT%H:%M`，默认为当前时间
+    pub time: Option<String>,
+}
+
+/// 授权数据的导入导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyFormat {
+    /// 结构化的授权数据包，包含角色、角色菜单、策略与用户角色
+    #[default]
+    Json,
+    /// Casbin 的 CSV 策略格式，仅包含 p 与 g 规则
+    Csv,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPolicyDto {
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    #[serde(default)]
+    pub format: PolicyFormat,
+}
+
+/// 导入授权数据，导入后域内的授权状态与导入内容一致
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPolicyDto {
+    /// 目标域，导入内容中的域会替换为该域
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    #[serde(default)]
+    pub format: PolicyFormat,
+
+    /// 导出得到的 JSON 数据包或 CSV 文本
+    #[validate(length(min = 1, message = "content cannot be empty"))]
+    pub content: String,
+
+    /// 仅计算差异，不写入
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// 策略规则类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyType {
+    /// 角色的接口权限，依次为角色、域、路径、方法、条件
+    P,
+    /// 角色继承关系，依次为角色、上级角色、域
+    G,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyPageRequest {
+    #[serde(flatten)]
+    pub page_details: PageRequest,
+    pub ptype: Option<PolicyType>,
+    /// 角色编码
+    pub subject: Option<String>,
+    pub domain: Option<String>,
+    /// p 规则的路径或 g 规则的上级角色
+    pub object: Option<String>,
+    /// p 规则的请求方法
+    pub action: Option<String>,
+}
+
+/// 单条策略规则
+#[derive(Debug, Clone, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRuleDto {
+    pub ptype: PolicyType,
+
+    /// 角色编码
+    #[validate(length(min = 1, message = "subject cannot be empty"))]
+    pub subject: String,
+
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    /// p 规则的路径或 g 规则的上级角色
+    #[validate(length(min = 1, message = "object cannot be empty"))]
+    pub object: String,
+
+    /// p 规则的请求方法，g 规则须为空
+    #[serde(default)]
+    pub action: String,
+
+    /// p 规则的条件，为空时不限制，g 规则须为空
+    #[serde(default)]
+    pub condition: String,
+}
+
+/// 新增或删除策略规则
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRulesDto {
+    #[validate(length(min = 1, message = "Rules array cannot be empty"), nested)]
+    pub rules: Vec<PolicyRuleDto>,
+}
+
+/// 整体替换域内某类策略规则，指定角色时只替换该角色的规则
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePoliciesDto {
+    pub ptype: PolicyType,
+
+    #[validate(length(min = 1, message = "domain cannot be empty"))]
+    pub domain: String,
+
+    pub subject: Option<String>,
+
+    /// 替换后的全部规则，为空时清除范围内的规则
+    #[validate(nested)]
+    #[serde(default)]
+    pub rules: Vec<PolicyRuleDto>,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use server_core::web::page::PageRequest;
+use validator::Validate;
+
+use crate::admin::entities::sea_orm_active_enums::MaskStrategy;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldMaskPageRequest {
+    #[serde(flatten)]
+    pub page_details: PageRequest,
+    pub domain: Option<String>,
+    pub keywords: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct FieldMaskInput {
+    #[validate(length(min = 1, message = "Domain cannot be empty"))]
+    pub domain: String,
+    #[validate(length(min = 1, message = "Role code cannot be empty"))]
+    pub role_code: String,
+    /// 生效的请求路径，为空时对全部路径生效，以 `/*` 结尾时按前缀匹配
+    #[serde(default)]
+    #[validate(length(max = 200, message = "Resource must not exceed 200 characters"))]
+    pub resource: String,
+    /// 响应 JSON 中的字段名，如 `phoneNumber`
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Field must be between 1 and 100 characters"
+    ))]
+    pub field: String,
+    pub strategy: MaskStrategy,
+    #[validate(length(max = 200, message = "Description must not exceed 200 characters"))]
+    pub description: Option<String>,
+}
+
+pub type CreateFieldMaskInput = FieldMaskInput;
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateFieldMaskInput {
+    pub id: String,
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub field_mask: FieldMaskInput,
+}
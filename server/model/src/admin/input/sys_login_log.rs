@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use server_core::web::page::PageRequest;
+use server_core::web::{export::ExportFormat, page::PageRequest};
+use validator::Validate;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginLogPageRequest {
@@ -7,3 +8,11 @@ pub struct LoginLogPageRequest {
     pub page_details: PageRequest,
     pub keywords: Option<String>,
 }
+
+/// 导出登录日志，过滤条件与分页查询相同
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct LoginLogExportRequest {
+    pub keywords: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use server_core::web::page::PageRequest;
+use validator::Validate;
+
+use crate::admin::entities::sea_orm_active_enums::Status;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrganizationPageRequest {
@@ -7,3 +10,50 @@ pub struct OrganizationPageRequest {
     pub page_details: PageRequest,
     pub keywords: Option<String>,
 }
+
+#[derive(Deserialize, Validate)]
+pub struct OrganizationInput {
+    /// 上级组织 ID，顶级组织为 "0"
+    #[validate(length(min = 1, message = "Parent ID cannot be empty"))]
+    pub pid: String,
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Code must be between 1 and 50 characters"
+    ))]
+    pub code: String,
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+    pub status: Status,
+    #[validate(length(max = 500, message = "Description must not exceed 500 characters"))]
+    pub description: Option<String>,
+}
+
+pub type CreateOrganizationInput = OrganizationInput;
+
+#[derive(Deserialize, Validate)]
+pub struct UpdateOrganizationInput {
+    pub id: String,
+    #[serde(flatten)]
+    pub organization: OrganizationInput,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct MoveOrganizationInput {
+    #[validate(length(min = 1, message = "ID cannot be empty"))]
+    pub id: String,
+    /// 新的上级组织 ID，移动到顶级时为 "0"
+    #[validate(length(min = 1, message = "Parent ID cannot be empty"))]
+    pub pid: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignOrganizationUserInput {
+    /// 组织的全部成员，未列出的现有成员将被移出该组织
+    pub user_ids: Vec<String>,
+}
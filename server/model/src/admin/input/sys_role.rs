@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use server_core::web::page::PageRequest;
 use validator::Validate;
 
-use crate::admin::entities::sea_orm_active_enums::Status;
+use crate::admin::entities::sea_orm_active_enums::{DataScope, Status};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RolePageRequest {
@@ -29,6 +29,12 @@ pub struct RoleInput {
     pub status: Status,
     #[validate(length(max = 200, message = "Description must not exceed 200 characters"))]
     pub description: Option<String>,
+    /// 数据范围，默认为全部数据
+    #[serde(default)]
+    pub data_scope: DataScope,
+    /// 自定义数据范围包含的组织 ID，仅在数据范围为 `CUSTOM` 时生效
+    #[serde(default)]
+    pub data_scope_org_ids: Vec<String>,
 }
 
 pub type CreateRoleInput = RoleInput;
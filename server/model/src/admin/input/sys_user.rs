@@ -43,6 +43,21 @@ pub struct UserInput {
 
 pub type CreateUserInput = UserInput;
 
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordInput {
+    #[validate(length(min = 1, message = "Old password cannot be empty"))]
+    pub old_password: String,
+    #[validate(length(max = 100, message = "Password must not exceed 100 characters"))]
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ResetPasswordInput {
+    #[validate(length(max = 100, message = "Password must not exceed 100 characters"))]
+    pub password: String,
+}
+
 #[derive(Deserialize, Validate)]
 pub struct UpdateUserInput {
     pub id: String,
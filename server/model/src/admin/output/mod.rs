@@ -1,11 +1,29 @@
-pub use sys_authentication::{AuthOutput, UserInfoOutput, UserRoute};
+pub use sys_audit_chain::{AuditChainBreak, AuditChainBreakReason, AuditChainVerification};
+pub use sys_authentication::{
+    AuthOutput, LoginOutput, MfaChallengeOutput, OAuthAuthorizeOutput, TotpEnrollOutput,
+    TotpRecoveryCodesOutput, UserInfoOutput, UserRoute,
+};
+pub use sys_authorization::{
+    AuthorizationBundle, BundleRole, BundleRoleMenu, BundleUserRole, PermissionExplanation,
+    PolicyChangeOutput, PolicyImportDiff, PolicyRule, ReachableEndpoint, SubjectExplanation,
+};
 pub use sys_domain::DomainOutput;
 pub use sys_endpoint::EndpointTree;
+pub use sys_export_job::{ExportJobOutput, ExportJobStatus};
 pub use sys_menu::{MenuRoute, MenuTree, RouteMeta};
+pub use sys_online_session::OnlineSessionOutput;
+pub use sys_organization::OrganizationTree;
+pub use sys_role::{EffectiveMenu, EffectivePermission, RoleEffectivePermissionsOutput};
 pub use sys_user::{UserWithDomainAndOrgOutput, UserWithoutPassword};
 
+mod sys_audit_chain;
 mod sys_authentication;
+mod sys_authorization;
 mod sys_domain;
 mod sys_endpoint;
+mod sys_export_job;
 mod sys_menu;
+mod sys_online_session;
+mod sys_organization;
+mod sys_role;
 mod sys_user;
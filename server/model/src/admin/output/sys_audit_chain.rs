@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+use crate::admin::input::AuditLogType;
+
+/// 哈希链的校验结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditChainVerification {
+    pub log_type: AuditLogType,
+    pub domain: String,
+    /// 范围内的记录均未被篡改
+    pub valid: bool,
+    /// 校验的记录数
+    pub checked: u64,
+    /// 校验范围的首尾链序号，范围内没有入链的记录时为空
+    pub first_seq: Option<i64>,
+    pub last_seq: Option<i64>,
+    /// 通过校验的签名检查点数
+    pub checkpoints: u64,
+    /// 第一个断开的位置
+    pub broken_link: Option<AuditChainBreak>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditChainBreak {
+    pub chain_seq: i64,
+    /// 断开位置的记录，记录缺失时为空
+    pub record_id: Option<String>,
+    pub reason: AuditChainBreakReason,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditChainBreakReason {
+    /// 链序号不连续，记录被删除或未写入
+    MissingRecord,
+    /// 记录保存的上一条哈希与上一条记录不一致
+    PrevHashMismatch,
+    /// 记录内容与保存的哈希不一致
+    HashMismatch,
+    /// 检查点签名无效或与记录的哈希不一致
+    CheckpointMismatch,
+}
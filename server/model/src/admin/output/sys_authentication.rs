@@ -8,6 +8,53 @@ pub struct AuthOutput {
     // 为了复用soybean-admin-nestjs前端,暂时弃用
     // pub access_token: String,
     pub refresh_token: String,
+    /// 密码已超过策略规定的有效期
+    #[serde(rename = "passwordExpired")]
+    pub password_expired: bool,
+    /// 管理员重置密码后需用户自行修改
+    #[serde(rename = "mustChangePassword")]
+    pub must_change_password: bool,
+}
+
+/// 密码登录结果
+///
+/// 未启用两步验证时直接返回令牌；已启用时返回挑战令牌，需再提交验证码完成登录。
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutput {
+    Authenticated(AuthOutput),
+    MfaRequired(MfaChallengeOutput),
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeOutput {
+    pub mfa_required: bool,
+    pub challenge_token: String,
+    /// 挑战令牌有效期（秒）
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpEnrollOutput {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpRecoveryCodesOutput {
+    /// 一次性恢复码，仅在启用时返回一次
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorizeOutput {
+    /// 跳转到身份提供方的授权地址
+    pub authorize_url: String,
+    pub state: String,
 }
 
 #[derive(Debug, Serialize)]
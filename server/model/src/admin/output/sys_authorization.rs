@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::admin::{
+    entities::{
+        sea_orm_active_enums::{DataScope, Status},
+        sys_endpoint::Model as SysEndpointModel,
+    },
+    input::{PolicyRuleDto, PolicyType},
+};
+
+/// 权限判定的解释结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionExplanation {
+    pub allowed: bool,
+    pub domain: String,
+    pub path: String,
+    pub method: String,
+    /// 逐个角色的判定过程，任一角色允许即放行
+    pub subjects: Vec<SubjectExplanation>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectExplanation {
+    pub subject: String,
+    pub allowed: bool,
+    /// 命中的策略，依次为角色、域、资源、操作、条件
+    pub matched_policies: Vec<Vec<String>>,
+    /// 经继承关系获得的上级角色
+    pub inherited_roles: Vec<String>,
+}
+
+/// 可访问的接口及授权来源
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReachableEndpoint {
+    #[serde(flatten)]
+    pub endpoint: SysEndpointModel,
+    /// 授予访问权限的角色
+    pub granted_by: String,
+}
+
+/// 域的完整授权数据，按编码引用角色、菜单与用户，便于在环境间迁移
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationBundle {
+    pub domain: String,
+    /// 策略与角色菜单引用到的角色
+    #[serde(default)]
+    pub roles: Vec<BundleRole>,
+    #[serde(default)]
+    pub role_menus: Vec<BundleRoleMenu>,
+    /// p 规则，依次为角色、域、资源、操作
+    #[serde(default)]
+    pub policies: Vec<Vec<String>>,
+    /// g 规则，依次为角色、上级角色、域
+    #[serde(default)]
+    pub groupings: Vec<Vec<String>>,
+    /// 域内用户的角色
+    #[serde(default)]
+    pub user_roles: Vec<BundleUserRole>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleRole {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// 上级角色编码，顶级角色为空
+    pub parent_code: Option<String>,
+    pub status: Status,
+    pub data_scope: DataScope,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleRoleMenu {
+    pub role_code: String,
+    pub route_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleUserRole {
+    pub username: String,
+    pub role_code: String,
+}
+
+/// 导入授权数据的差异，试运行时为将要执行的变更
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyImportDiff {
+    pub dry_run: bool,
+    pub roles_created: Vec<String>,
+    pub roles_updated: Vec<String>,
+    pub role_menus_added: Vec<BundleRoleMenu>,
+    pub role_menus_removed: Vec<BundleRoleMenu>,
+    pub policies_added: Vec<Vec<String>>,
+    pub policies_removed: Vec<Vec<String>>,
+    pub groupings_added: Vec<Vec<String>>,
+    pub groupings_removed: Vec<Vec<String>>,
+    pub user_roles_added: Vec<BundleUserRole>,
+    pub user_roles_removed: Vec<BundleUserRole>,
+}
+
+/// 策略规则，`id` 为 `casbin_rule` 表的主键
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRule {
+    pub id: i64,
+    pub ptype: PolicyType,
+    pub subject: String,
+    pub domain: String,
+    pub object: String,
+    pub action: String,
+    pub condition: String,
+}
+
+/// 策略变更结果，不含已存在或不存在而被跳过的规则
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyChangeOutput {
+    pub added: Vec<PolicyRuleDto>,
+    pub removed: Vec<PolicyRuleDto>,
+}
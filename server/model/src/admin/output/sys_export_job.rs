@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use server_core::web::export::ExportFormat;
+
+use crate::admin::input::AuditLogType;
+
+/// 异步导出任务
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJobOutput {
+    pub id: String,
+    pub log_type: AuditLogType,
+    pub format: ExportFormat,
+    pub status: ExportJobStatus,
+    /// 已导出的记录数
+    pub rows: u64,
+    /// 已写入的字节数
+    pub size: u64,
+    /// 下载链接，任务完成后每次查询重新签发
+    pub download_url: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use sea_orm::FromQueryResult;
+use serde::Serialize;
+
+/// 在线会话，不包含令牌本身
+#[derive(Clone, Debug, FromQueryResult, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnlineSessionOutput {
+    pub id: String,
+    pub user_id: String,
+    pub username: String,
+    pub domain: String,
+    pub login_time: NaiveDateTime,
+    pub ip: String,
+    pub port: Option<i32>,
+    pub address: String,
+    pub user_agent: String,
+    pub r#type: String,
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+
+/// 角色的有效权限，包含从上级角色继承的部分
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleEffectivePermissionsOutput {
+    pub role_id: String,
+    pub role_code: String,
+    pub domain: String,
+    /// 上级角色编码，由近及远
+    pub inherited_roles: Vec<String>,
+    pub permissions: Vec<EffectivePermission>,
+    pub menus: Vec<EffectiveMenu>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePermission {
+    pub path: String,
+    pub method: String,
+    /// 授予该权限的角色编码
+    pub source_role: String,
+    pub inherited: bool,
+    /// 策略条件，为空表示无条件
+    pub condition: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveMenu {
+    pub menu_id: i32,
+    /// 授予该菜单的角色编码
+    pub source_role: String,
+    pub inherited: bool,
+}
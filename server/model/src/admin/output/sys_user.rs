@@ -14,6 +14,10 @@ pub struct UserWithDomainAndOrgOutput {
     pub avatar: Option<String>,
     pub domain_code: String,
     pub domain_name: String,
+    pub password_changed_at: Option<NaiveDateTime>,
+    pub must_change_password: bool,
+    /// 用户所属组织的编码
+    pub org_code: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -4,7 +4,7 @@ use axum::{
     Router,
 };
 use server_api::admin::SysAccessKeyApi;
-use server_global::global::{add_route, RouteInfo};
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
 
 pub struct SysAccessKeyRouter;
 
@@ -14,8 +14,10 @@ impl SysAccessKeyRouter {
         let service_name = "SysAccessKeyApi";
 
         let routes = vec![
-            RouteInfo::new(base_path, Method::GET, service_name, "获取访问密钥列表"),
-            RouteInfo::new(base_path, Method::POST, service_name, "创建访问密钥"),
+            RouteInfo::new(base_path, Method::GET, service_name, "获取访问密钥列表")
+                .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(base_path, Method::POST, service_name, "创建访问密钥")
+                .with_operation_log(OperationLogOptions::REQUEST_ONLY),
             RouteInfo::new(
                 &format!("{}/:id", base_path),
                 Method::DELETE,
@@ -0,0 +1,28 @@
+use axum::{http::Method, routing::get, Router};
+use server_api::admin::SysAuditChainApi;
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
+
+pub struct SysAuditChainRouter;
+
+impl SysAuditChainRouter {
+    pub async fn init_audit_chain_router() -> Router {
+        let base_path = "/audit-chain";
+        let service_name = "SysAuditChainApi";
+
+        let routes = vec![RouteInfo::new(
+            &format!("{}/verify", base_path),
+            Method::GET,
+            service_name,
+            "校验审计日志哈希链",
+        )
+        .with_operation_log(OperationLogOptions::METADATA)];
+
+        for route in routes {
+            add_route(route).await;
+        }
+
+        let router = Router::new().route("/verify", get(SysAuditChainApi::verify_audit_chain));
+
+        Router::new().nest(base_path, router)
+    }
+}
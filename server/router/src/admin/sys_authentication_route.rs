@@ -4,18 +4,41 @@ use axum::{
     Router,
 };
 use server_api::admin::SysAuthenticationApi;
-use server_global::global::{add_route, RouteInfo};
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
 
 pub struct SysAuthenticationRouter;
 
 impl SysAuthenticationRouter {
     pub async fn init_authentication_router() -> Router {
-        let router = Router::new().route("/login", post(SysAuthenticationApi::login_handler));
+        let router = Router::new()
+            .route("/login", post(SysAuthenticationApi::login_handler))
+            .route(
+                "/login/totp",
+                post(SysAuthenticationApi::totp_login_handler),
+            )
+            .route(
+                "/oauth/{provider}/authorize",
+                get(SysAuthenticationApi::oauth_authorize),
+            )
+            .route(
+                "/oauth/{provider}/callback",
+                post(SysAuthenticationApi::oauth_callback),
+            )
+            .route("/refreshToken", post(SysAuthenticationApi::refresh_token));
         Router::new().nest("/auth", router)
     }
 
+    pub async fn init_well_known_router() -> Router {
+        let router = Router::new().route("/jwks.json", get(SysAuthenticationApi::jwks));
+        Router::new().nest("/.well-known", router)
+    }
+
     pub async fn init_protected_router() -> Router {
         let router = Router::new()
+            .route("/logout", post(SysAuthenticationApi::logout))
+            .route("/totp/enroll", post(SysAuthenticationApi::totp_enroll))
+            .route("/totp/verify", post(SysAuthenticationApi::totp_activate))
+            .route("/totp/disable", post(SysAuthenticationApi::totp_disable))
             .route("/getUserInfo", get(SysAuthenticationApi::get_user_info))
             .route("/getUserRoutes", get(SysAuthenticationApi::get_user_routes));
 
@@ -39,6 +62,56 @@ impl SysAuthenticationRouter {
                 service_name,
                 "分配路由",
             ),
+            RouteInfo::new(
+                &format!("{}/explain", base_path),
+                Method::POST,
+                service_name,
+                "解释权限判定",
+            ),
+            RouteInfo::new(
+                &format!("{}/reachable-endpoints", base_path),
+                Method::POST,
+                service_name,
+                "获取可访问的接口",
+            ),
+            RouteInfo::new(
+                &format!("{}/export", base_path),
+                Method::GET,
+                service_name,
+                "导出授权数据",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/import", base_path),
+                Method::POST,
+                service_name,
+                "导入授权数据",
+            )
+            .with_operation_log(OperationLogOptions::RESPONSE_ONLY),
+            RouteInfo::new(
+                &format!("{}/policies", base_path),
+                Method::GET,
+                service_name,
+                "获取策略规则列表",
+            ),
+            RouteInfo::new(
+                &format!("{}/policies", base_path),
+                Method::POST,
+                service_name,
+                "新增策略规则",
+            ),
+            RouteInfo::new(
+                &format!("{}/policies/remove", base_path),
+                Method::POST,
+                service_name,
+                "删除策略规则",
+            ),
+            RouteInfo::new(
+                &format!("{}/policies", base_path),
+                Method::PUT,
+                service_name,
+                "替换策略规则",
+            ),
         ];
 
         for route in routes {
@@ -51,7 +124,24 @@ impl SysAuthenticationRouter {
                 "/assign-permission",
                 post(SysAuthenticationApi::assign_permission),
             )
-            .route("/assign-routes", post(SysAuthenticationApi::assign_routes));
+            .route("/assign-routes", post(SysAuthenticationApi::assign_routes))
+            .route("/explain", post(SysAuthenticationApi::explain_permission))
+            .route(
+                "/reachable-endpoints",
+                post(SysAuthenticationApi::get_reachable_endpoints),
+            )
+            .route("/export", get(SysAuthenticationApi::export_policies))
+            .route("/import", post(SysAuthenticationApi::import_policies))
+            .route(
+                "/policies",
+                get(SysAuthenticationApi::get_paginated_policies)
+                    .post(SysAuthenticationApi::add_policies)
+                    .put(SysAuthenticationApi::replace_policies),
+            )
+            .route(
+                "/policies/remove",
+                post(SysAuthenticationApi::remove_policies),
+            );
 
         Router::new().nest(base_path, authorization_router)
     }
@@ -0,0 +1,47 @@
+use axum::{
+    http::Method,
+    routing::{delete, get, post, put},
+    Router,
+};
+use server_api::admin::SysFieldMaskApi;
+use server_global::global::{add_route, RouteInfo};
+
+pub struct SysFieldMaskRouter;
+
+impl SysFieldMaskRouter {
+    pub async fn init_field_mask_router() -> Router {
+        let base_path = "/field-mask";
+        let service_name = "SysFieldMaskApi";
+
+        let routes = vec![
+            RouteInfo::new(base_path, Method::GET, service_name, "获取字段脱敏规则列表"),
+            RouteInfo::new(base_path, Method::POST, service_name, "创建字段脱敏规则"),
+            RouteInfo::new(
+                &format!("{}/:id", base_path),
+                Method::GET,
+                service_name,
+                "获取字段脱敏规则详情",
+            ),
+            RouteInfo::new(base_path, Method::PUT, service_name, "更新字段脱敏规则"),
+            RouteInfo::new(
+                &format!("{}/:id", base_path),
+                Method::DELETE,
+                service_name,
+                "删除字段脱敏规则",
+            ),
+        ];
+
+        for route in routes {
+            add_route(route).await;
+        }
+
+        let router = Router::new()
+            .route("/", get(SysFieldMaskApi::get_paginated_field_masks))
+            .route("/", post(SysFieldMaskApi::create_field_mask))
+            .route("/{id}", get(SysFieldMaskApi::get_field_mask))
+            .route("/", put(SysFieldMaskApi::update_field_mask))
+            .route("/{id}", delete(SysFieldMaskApi::delete_field_mask));
+
+        Router::new().nest(base_path, router)
+    }
+}
@@ -1,6 +1,10 @@
-use axum::{http::Method, routing::get, Router};
+use axum::{
+    http::Method,
+    routing::{get, post},
+    Router,
+};
 use server_api::admin::SysLoginLogApi;
-use server_global::global::{add_route, RouteInfo};
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
 
 pub struct SysLoginLogRouter;
 
@@ -9,18 +13,47 @@ impl SysLoginLogRouter {
         let base_path = "/login-log";
         let service_name = "SysLoginLogApi";
 
-        let routes = vec![RouteInfo::new(
-            base_path,
-            Method::GET,
-            service_name,
-            "获取登录日志列表",
-        )];
+        let routes = vec![
+            RouteInfo::new(base_path, Method::GET, service_name, "获取登录日志列表")
+                .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export", base_path),
+                Method::GET,
+                service_name,
+                "导出登录日志",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export-jobs", base_path),
+                Method::POST,
+                service_name,
+                "创建登录日志导出任务",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export-jobs/:id", base_path),
+                Method::GET,
+                service_name,
+                "获取登录日志导出任务",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+        ];
 
         for route in routes {
             add_route(route).await;
         }
 
-        let router = Router::new().route("/", get(SysLoginLogApi::get_paginated_login_logs));
+        let router = Router::new()
+            .route("/", get(SysLoginLogApi::get_paginated_login_logs))
+            .route("/export", get(SysLoginLogApi::export_login_logs))
+            .route(
+                "/export-jobs",
+                post(SysLoginLogApi::create_login_log_export_job),
+            )
+            .route(
+                "/export-jobs/{id}",
+                get(SysLoginLogApi::get_login_log_export_job),
+            );
 
         Router::new().nest(base_path, router)
     }
@@ -0,0 +1,46 @@
+use axum::{
+    http::Method,
+    routing::{delete, get},
+    Router,
+};
+use server_api::admin::SysOnlineSessionApi;
+use server_global::global::{add_route, RouteInfo};
+
+pub struct SysOnlineSessionRouter;
+
+impl SysOnlineSessionRouter {
+    pub async fn init_online_session_router() -> Router {
+        let base_path = "/online-session";
+        let service_name = "SysOnlineSessionApi";
+
+        let routes = vec![
+            RouteInfo::new(base_path, Method::GET, service_name, "获取在线会话列表"),
+            RouteInfo::new(
+                &format!("{}/:id", base_path),
+                Method::DELETE,
+                service_name,
+                "踢出会话",
+            ),
+            RouteInfo::new(
+                &format!("{}/user/:userId", base_path),
+                Method::DELETE,
+                service_name,
+                "踢出用户所有会话",
+            ),
+        ];
+
+        for route in routes {
+            add_route(route).await;
+        }
+
+        let router = Router::new()
+            .route("/", get(SysOnlineSessionApi::get_paginated_sessions))
+            .route("/{id}", delete(SysOnlineSessionApi::kick_session))
+            .route(
+                "/user/{userId}",
+                delete(SysOnlineSessionApi::kick_user_sessions),
+            );
+
+        Router::new().nest(base_path, router)
+    }
+}
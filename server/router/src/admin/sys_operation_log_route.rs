@@ -1,6 +1,10 @@
-use axum::{http::Method, routing::get, Router};
+use axum::{
+    http::Method,
+    routing::{get, post},
+    Router,
+};
 use server_api::admin::SysOperationLogApi;
-use server_global::global::{add_route, RouteInfo};
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
 
 pub struct SysOperationLogRouter;
 
@@ -9,19 +13,55 @@ impl SysOperationLogRouter {
         let base_path = "/operation-log";
         let service_name = "SysOperationLogApi";
 
-        let routes = vec![RouteInfo::new(
-            base_path,
-            Method::GET,
-            service_name,
-            "获取操作日志列表",
-        )];
+        let routes = vec![
+            RouteInfo::new(base_path, Method::GET, service_name, "获取操作日志列表")
+                .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/metrics", base_path),
+                Method::GET,
+                service_name,
+                "获取操作日志写入指标",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export", base_path),
+                Method::GET,
+                service_name,
+                "导出操作日志",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export-jobs", base_path),
+                Method::POST,
+                service_name,
+                "创建操作日志导出任务",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+            RouteInfo::new(
+                &format!("{}/export-jobs/:id", base_path),
+                Method::GET,
+                service_name,
+                "获取操作日志导出任务",
+            )
+            .with_operation_log(OperationLogOptions::METADATA),
+        ];
 
         for route in routes {
             add_route(route).await;
         }
 
-        let router =
-            Router::new().route("/", get(SysOperationLogApi::get_paginated_operation_logs));
+        let router = Router::new()
+            .route("/", get(SysOperationLogApi::get_paginated_operation_logs))
+            .route("/metrics", get(SysOperationLogApi::get_audit_log_metrics))
+            .route("/export", get(SysOperationLogApi::export_operation_logs))
+            .route(
+                "/export-jobs",
+                post(SysOperationLogApi::create_operation_log_export_job),
+            )
+            .route(
+                "/export-jobs/{id}",
+                get(SysOperationLogApi::get_operation_log_export_job),
+            );
 
         Router::new().nest(base_path, router)
     }
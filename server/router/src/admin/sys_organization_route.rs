@@ -1,4 +1,8 @@
-use axum::{http::Method, routing::get, Router};
+use axum::{
+    http::Method,
+    routing::{delete, get, post, put},
+    Router,
+};
 use server_api::admin::SysOrganizationApi;
 use server_global::global::{add_route, RouteInfo};
 
@@ -9,18 +13,68 @@ impl SysOrganizationRouter {
         let base_path = "/org";
         let service_name = "SysOrganizationApi";
 
-        let routes = vec![RouteInfo::new(
-            base_path,
-            Method::GET,
-            service_name,
-            "获取组织列表",
-        )];
+        let routes = vec![
+            RouteInfo::new(base_path, Method::GET, service_name, "获取组织列表"),
+            RouteInfo::new(
+                &format!("{}/tree", base_path),
+                Method::GET,
+                service_name,
+                "获取组织树",
+            ),
+            RouteInfo::new(base_path, Method::POST, service_name, "创建组织"),
+            RouteInfo::new(
+                &format!("{}/:id", base_path),
+                Method::GET,
+                service_name,
+                "获取组织详情",
+            ),
+            RouteInfo::new(base_path, Method::PUT, service_name, "更新组织"),
+            RouteInfo::new(
+                &format!("{}/move", base_path),
+                Method::PUT,
+                service_name,
+                "移动组织",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id", base_path),
+                Method::DELETE,
+                service_name,
+                "删除组织",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/users", base_path),
+                Method::GET,
+                service_name,
+                "获取组织成员",
+            ),
+            RouteInfo::new(
+                &format!("{}/:id/users", base_path),
+                Method::PUT,
+                service_name,
+                "设置组织成员",
+            ),
+        ];
 
         for route in routes {
             add_route(route).await;
         }
 
-        let router = Router::new().route("/", get(SysOrganizationApi::get_paginated_organizations));
+        let router = Router::new()
+            .route("/", get(SysOrganizationApi::get_paginated_organizations))
+            .route("/tree", get(SysOrganizationApi::tree_organizations))
+            .route("/", post(SysOrganizationApi::create_organization))
+            .route("/{id}", get(SysOrganizationApi::get_organization))
+            .route("/", put(SysOrganizationApi::update_organization))
+            .route("/move", put(SysOrganizationApi::move_organization))
+            .route("/{id}", delete(SysOrganizationApi::delete_organization))
+            .route(
+                "/{id}/users",
+                get(SysOrganizationApi::get_organization_users),
+            )
+            .route(
+                "/{id}/users",
+                put(SysOrganizationApi::assign_organization_users),
+            );
 
         Router::new().nest(base_path, router)
     }
@@ -29,6 +29,12 @@ impl SysRoleRouter {
                 service_name,
                 "删除角色",
             ),
+            RouteInfo::new(
+                &format!("{}/:id/effective-permissions", base_path),
+                Method::GET,
+                service_name,
+                "获取角色有效权限",
+            ),
         ];
 
         for route in routes {
@@ -40,7 +46,11 @@ impl SysRoleRouter {
             .route("/", post(SysRoleApi::create_role))
             .route("/{id}", get(SysRoleApi::get_role))
             .route("/", put(SysRoleApi::update_role))
-            .route("/{id}", delete(SysRoleApi::delete_role));
+            .route("/{id}", delete(SysRoleApi::delete_role))
+            .route(
+                "/{id}/effective-permissions",
+                get(SysRoleApi::get_effective_permissions),
+            );
 
         Router::new().nest(base_path, router)
     }
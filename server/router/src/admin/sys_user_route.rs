@@ -4,7 +4,7 @@ use axum::{
     Router,
 };
 use server_api::admin::SysUserApi;
-use server_global::global::{add_route, RouteInfo};
+use server_global::global::{add_route, OperationLogOptions, RouteInfo};
 
 pub struct SysUserRouter;
 
@@ -21,14 +21,16 @@ impl SysUserRouter {
                 "获取所有用户",
             ),
             RouteInfo::new(base_path, Method::GET, service_name, "获取用户列表"),
-            RouteInfo::new(base_path, Method::POST, service_name, "创建用户"),
+            RouteInfo::new(base_path, Method::POST, service_name, "创建用户")
+                .with_operation_log(OperationLogOptions::RESPONSE_ONLY),
             RouteInfo::new(
                 &format!("{}/:id", base_path),
                 Method::GET,
                 service_name,
                 "获取用户详情",
             ),
-            RouteInfo::new(base_path, Method::PUT, service_name, "更新用户"),
+            RouteInfo::new(base_path, Method::PUT, service_name, "更新用户")
+                .with_operation_log(OperationLogOptions::RESPONSE_ONLY),
             RouteInfo::new(
                 &format!("{}/:id", base_path),
                 Method::DELETE,
@@ -36,17 +38,18 @@ impl SysUserRouter {
                 "删除用户",
             ),
             RouteInfo::new(
-                &format!("{}/add_policies", base_path),
-                Method::GET,
+                &format!("{}/:id/logout", base_path),
+                Method::POST,
                 service_name,
-                "添加用户策略",
+                "强制用户下线",
             ),
             RouteInfo::new(
-                &format!("{}/remove_policies", base_path),
-                Method::GET,
+                &format!("{}/:id/password", base_path),
+                Method::PUT,
                 service_name,
-                "删除用户策略",
-            ),
+                "重置用户密码",
+            )
+            .with_operation_log(OperationLogOptions::RESPONSE_ONLY),
         ];
 
         for route in routes {
@@ -60,9 +63,14 @@ impl SysUserRouter {
             .route("/{id}", get(SysUserApi::get_user))
             .route("/", put(SysUserApi::update_user))
             .route("/{id}", delete(SysUserApi::delete_user))
-            .route("/add_policies", get(SysUserApi::add_policies))
-            .route("/remove_policies", get(SysUserApi::remove_policies));
+            .route("/{id}/logout", post(SysUserApi::force_logout))
+            .route("/{id}/password", put(SysUserApi::reset_password));
 
         Router::new().nest(base_path, router)
     }
+
+    /// 当前用户自助操作的路由，仅需登录，不做权限校验
+    pub async fn init_user_self_router() -> Router {
+        Router::new().route("/user/password", put(SysUserApi::change_password))
+    }
 }
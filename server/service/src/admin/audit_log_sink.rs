@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel};
+use server_core::web::error::AppError;
+use server_model::admin::entities::{
+    prelude::SysOperationLog, sys_operation_log::Model as SysOperationLogModel,
+};
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::helper::{
+    db_helper,
+    mongo_helper::{self, MongoSource},
+};
+
+/// 操作日志写入目标
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// 写入目标名称，用于日志输出
+    fn name(&self) -> &str;
+
+    /// 是否为主数据库，哈希链的链头从主数据库读取
+    fn is_primary(&self) -> bool {
+        false
+    }
+
+    /// 批量写入操作日志，失败时整批重试
+    async fn write_batch(&self, records: &[SysOperationLogModel]) -> Result<(), AppError>;
+}
+
+/// 通过 SeaORM 写入主数据库
+pub struct DatabaseAuditSink;
+
+#[async_trait]
+impl AuditSink for DatabaseAuditSink {
+    fn name(&self) -> &str {
+        "database"
+    }
+
+    fn is_primary(&self) -> bool {
+        true
+    }
+
+    async fn write_batch(&self, records: &[SysOperationLogModel]) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        SysOperationLog::insert_many(
+            records
+                .iter()
+                .cloned()
+                .map(|record| record.into_active_model().reset_all()),
+        )
+        .exec(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(())
+    }
+}
+
+/// 写入 MongoDB 集合
+pub struct MongoAuditSink {
+    source: MongoSource,
+    database: String,
+    collection: String,
+}
+
+impl MongoAuditSink {
+    pub fn new(instance: Option<String>, database: String, collection: String) -> Self {
+        Self {
+            source: instance.map_or(MongoSource::Primary, MongoSource::Named),
+            database,
+            collection,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for MongoAuditSink {
+    fn name(&self) -> &str {
+        "mongo"
+    }
+
+    async fn write_batch(&self, records: &[SysOperationLogModel]) -> Result<(), AppError> {
+        let collection = mongo_helper::get_collection::<SysOperationLogModel>(
+            self.source.clone(),
+            &self.database,
+            &self.collection,
+        )
+        .await?;
+
+        collection
+            .insert_many(records)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(())
+    }
+}
+
+/// 以 JSON Lines 格式追加写入文件
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+}
+
+impl JsonLinesAuditSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonLinesAuditSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn write_batch(&self, records: &[SysOperationLogModel]) -> Result<(), AppError> {
+        let mut lines = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut lines, record).map_err(|e| AppError {
+                code: 500,
+                message: format!("Failed to serialize operation log: {}", e),
+            })?;
+            lines.push(b'\n');
+        }
+
+        let io_error = |e: std::io::Error| AppError {
+            code: 500,
+            message: format!(
+                "Failed to write operation log file '{}': {}",
+                self.path.display(),
+                e
+            ),
+        };
+
+        if let Some(parent) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(io_error)?;
+        file.write_all(&lines).await.map_err(io_error)?;
+        file.flush().await.map_err(io_error)?;
+
+        Ok(())
+    }
+}
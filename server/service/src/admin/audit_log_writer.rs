@@ -0,0 +1,237 @@
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::Duration,
+};
+
+use server_config::{AuditLogConfig, AuditSinkConfig};
+use server_core::sign::truncate_to_micros;
+use server_global::{
+    global::{self, OperationLogContext, AUDIT_LOG_METRICS},
+    project_error, project_info,
+};
+use server_model::admin::entities::{
+    prelude::SysOperationLog, sys_operation_log::Model as SysOperationLogModel,
+};
+use tokio::{
+    sync::{mpsc, Mutex},
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+use tracing::instrument;
+use ulid::Ulid;
+
+use super::audit_log_sink::{AuditSink, DatabaseAuditSink, JsonLinesAuditSink, MongoAuditSink};
+use crate::helper::audit_chain_helper::{self, ChainHeads};
+
+static WRITER_TASK: Mutex<Option<JoinHandle<()>>> = Mutex::const_new(None);
+
+/// 操作日志的批量写入任务
+///
+/// 从有界队列中读取操作日志，攒够一批或到达写入间隔时写入所有目标。
+pub struct AuditLogWriter {
+    sinks: Vec<Arc<dyn AuditSink>>,
+    batch_size: usize,
+    flush_interval: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+    /// 是否将日志接入哈希链
+    chained: bool,
+}
+
+impl AuditLogWriter {
+    pub fn new(config: &AuditLogConfig, chained: bool) -> Self {
+        let mut sinks: Vec<Arc<dyn AuditSink>> = config.sinks.iter().map(build_sink).collect();
+        // 主数据库最先写入，写入冲突重新入链后的记录才会写入其他目标
+        sinks.sort_by_key(|sink| !sink.is_primary());
+
+        Self {
+            sinks,
+            batch_size: config.batch_size.max(1),
+            flush_interval: Duration::from_millis(config.flush_interval_ms.max(1)),
+            max_retries: config.max_retries,
+            retry_backoff: Duration::from_millis(config.retry_backoff_ms),
+            chained,
+        }
+    }
+
+    /// 创建审计日志队列并启动写入任务
+    pub async fn start(config: &AuditLogConfig) {
+        let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+        let writer = Self::new(config, audit_chain_helper::is_enabled().await);
+
+        let mut task = WRITER_TASK.lock().await;
+        global::set_audit_log_queue(tx, Duration::from_millis(config.enqueue_timeout_ms)).await;
+        *task = Some(tokio::spawn(writer.run(rx)));
+        project_info!("Audit log writer started with sinks: {:?}", config.sinks);
+    }
+
+    /// 关闭审计日志队列并等待写入任务写出剩余的日志
+    pub async fn shutdown() {
+        global::close_audit_log_queue().await;
+
+        if let Some(task) = WRITER_TASK.lock().await.take() {
+            if let Err(e) = task.await {
+                project_error!("Audit log writer terminated abnormally: {:?}", e);
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn run(self, mut rx: mpsc::Receiver<OperationLogContext>) {
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut ticker = time::interval(self.flush_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                received = rx.recv_many(&mut batch, self.batch_size) => {
+                    if received == 0 {
+                        break;
+                    }
+                    if batch.len() >= self.batch_size {
+                        self.flush(&mut batch).await;
+                        ticker.reset();
+                    }
+                },
+                _ = ticker.tick() => {
+                    if !batch.is_empty() {
+                        self.flush(&mut batch).await;
+                    }
+                },
+            }
+        }
+
+        // 发送端已全部关闭，队列中的日志已读完
+        if !batch.is_empty() {
+            self.flush(&mut batch).await;
+        }
+        project_info!("Audit log writer stopped");
+    }
+
+    async fn flush(&self, batch: &mut Vec<OperationLogContext>) {
+        let mut records: Vec<SysOperationLogModel> = batch.drain(..).map(into_model).collect();
+
+        // 链头持有到全部目标写入完成，写入失败的记录不会成为后续记录的上一条
+        let mut heads = if self.chained {
+            Some(audit_chain_helper::lock_heads::<SysOperationLog>().await)
+        } else {
+            None
+        };
+        if let Some(heads) = heads.as_mut() {
+            if let Err(e) = heads.link(&mut records).await {
+                project_error!("Failed to link operation logs into hash chain: {:?}", e);
+            }
+        }
+
+        for sink in &self.sinks {
+            let chain = heads.as_mut().filter(|_| sink.is_primary());
+            if !self
+                .write_with_retry(sink.as_ref(), &mut records, chain)
+                .await
+            {
+                if let Some(heads) = heads.as_mut() {
+                    heads.reset(&records);
+                }
+            }
+        }
+    }
+
+    /// 写入一批日志，重试耗尽后返回 `false`
+    ///
+    /// 传入链头时，重试前将记录重新入链，避免其他实例已写入相同链序号时整批反复冲突。
+    async fn write_with_retry(
+        &self,
+        sink: &dyn AuditSink,
+        records: &mut [SysOperationLogModel],
+        mut heads: Option<&mut ChainHeads<SysOperationLog>>,
+    ) -> bool {
+        let count = records.len() as u64;
+        let mut attempt = 0;
+
+        loop {
+            match sink.write_batch(records).await {
+                Ok(()) => {
+                    AUDIT_LOG_METRICS
+                        .written
+                        .fetch_add(count, Ordering::Relaxed);
+                    return true;
+                },
+                Err(e) if attempt < self.max_retries => {
+                    AUDIT_LOG_METRICS.retried.fetch_add(1, Ordering::Relaxed);
+                    attempt += 1;
+                    project_error!(
+                        "Failed to write {} operation logs to {} sink, retry {}/{}: {:?}",
+                        count,
+                        sink.name(),
+                        attempt,
+                        self.max_retries,
+                        e
+                    );
+                    time::sleep(self.retry_backoff * 2u32.saturating_pow(attempt - 1)).await;
+
+                    if let Some(heads) = heads.as_deref_mut() {
+                        if let Err(e) = heads.relink(records).await {
+                            project_error!(
+                                "Failed to relink operation logs into hash chain: {:?}",
+                                e
+                            );
+                        }
+                    }
+                },
+                Err(e) => {
+                    AUDIT_LOG_METRICS.failed.fetch_add(count, Ordering::Relaxed);
+                    project_error!(
+                        "Dropped {} operation logs after retries to {} sink: {:?}",
+                        count,
+                        sink.name(),
+                        e
+                    );
+                    return false;
+                },
+            }
+        }
+    }
+}
+
+fn build_sink(config: &AuditSinkConfig) -> Arc<dyn AuditSink> {
+    match config {
+        AuditSinkConfig::Database => Arc::new(DatabaseAuditSink),
+        AuditSinkConfig::Mongo {
+            instance,
+            database,
+            collection,
+        } => Arc::new(MongoAuditSink::new(
+            instance.clone(),
+            database.clone(),
+            collection.clone(),
+        )),
+        AuditSinkConfig::File { path } => Arc::new(JsonLinesAuditSink::new(path)),
+    }
+}
+
+fn into_model(context: OperationLogContext) -> SysOperationLogModel {
+    SysOperationLogModel {
+        id: Ulid::new().to_string(),
+        user_id: context.user_id.unwrap_or_default(),
+        username: context.username.unwrap_or_default(),
+        domain: context.domain.unwrap_or_default(),
+        module_name: context.module_name,
+        description: context.description,
+        request_id: context.request_id,
+        method: context.method,
+        url: context.url,
+        ip: context.ip,
+        user_agent: context.user_agent,
+        headers: context.headers,
+        params: context.params,
+        body: context.body,
+        response: context.response,
+        start_time: truncate_to_micros(context.start_time),
+        end_time: truncate_to_micros(context.end_time),
+        duration: context.duration,
+        created_at: truncate_to_micros(context.created_at),
+        chain_seq: None,
+        prev_hash: None,
+        hash: None,
+    }
+}
@@ -1,5 +1,11 @@
 pub mod sys_access_key_error;
 pub mod sys_domain_error;
+pub mod sys_field_mask_error;
+pub mod sys_ldap_error;
 pub mod sys_menu_error;
+pub mod sys_oauth_error;
+pub mod sys_organization_error;
 pub mod sys_role_error;
+pub mod sys_token_error;
+pub mod sys_totp_error;
 pub mod sys_user_error;
@@ -0,0 +1,38 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum FieldMaskError {
+    #[error("Field mask rule not found")]
+    FieldMaskNotFound,
+    #[error("Field mask rule for this role, resource and field already exists")]
+    DuplicateRule,
+    #[error("Domain not found")]
+    DomainNotFound,
+    #[error("Role not found")]
+    RoleNotFound,
+}
+
+impl ApiError for FieldMaskError {
+    fn code(&self) -> u16 {
+        match self {
+            FieldMaskError::FieldMaskNotFound => 11001,
+            FieldMaskError::DuplicateRule => 11002,
+            FieldMaskError::DomainNotFound => 11003,
+            FieldMaskError::RoleNotFound => 11004,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<FieldMaskError> for AppError {
+    fn from(err: FieldMaskError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DirectoryLoginError {
+    #[error("Directory service unavailable")]
+    Unavailable,
+    #[error("Multiple directory entries match the username")]
+    AmbiguousUser,
+    #[error("Username is already used by a user of another domain")]
+    UsernameConflict,
+    #[error("Role mapped from directory group not found")]
+    MappedRoleNotFound,
+    #[error("Username belongs to a local account not linked to this directory entry")]
+    AccountNotLinked,
+}
+
+impl ApiError for DirectoryLoginError {
+    fn code(&self) -> u16 {
+        match self {
+            DirectoryLoginError::Unavailable => 9001,
+            DirectoryLoginError::AmbiguousUser => 9002,
+            DirectoryLoginError::UsernameConflict => 9003,
+            DirectoryLoginError::MappedRoleNotFound => 9004,
+            DirectoryLoginError::AccountNotLinked => 9005,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<DirectoryLoginError> for AppError {
+    fn from(err: DirectoryLoginError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use server_core::{
+    oauth::OAuthError,
+    web::error::{ApiError, AppError},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ExternalLoginError {
+    #[error("Identity provider not found")]
+    ProviderNotFound,
+    #[error("Authorization state is invalid or expired")]
+    StateInvalid,
+    #[error("External account is not linked to any user")]
+    IdentityNotLinked,
+    #[error("Default role for domain not found")]
+    DefaultRoleNotFound,
+    #[error(transparent)]
+    Provider(#[from] OAuthError),
+}
+
+impl ApiError for ExternalLoginError {
+    fn code(&self) -> u16 {
+        match self {
+            ExternalLoginError::ProviderNotFound => 8001,
+            ExternalLoginError::StateInvalid => 8002,
+            ExternalLoginError::IdentityNotLinked => 8003,
+            ExternalLoginError::DefaultRoleNotFound => 8004,
+            ExternalLoginError::Provider(_) => 8005,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<ExternalLoginError> for AppError {
+    fn from(err: ExternalLoginError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OrganizationError {
+    #[error("Organization not found")]
+    OrganizationNotFound,
+
+    #[error("Duplicate organization code")]
+    DuplicateOrganizationCode,
+
+    #[error("Parent organization not found")]
+    ParentNotFound,
+
+    #[error("Organization cannot be moved under itself or its descendants")]
+    CyclicParent,
+
+    #[error("Organization has child organizations")]
+    HasChildren,
+
+    #[error("One or more users not found")]
+    UserNotFound,
+}
+
+impl ApiError for OrganizationError {
+    fn code(&self) -> u16 {
+        match self {
+            OrganizationError::OrganizationNotFound => 10001,
+            OrganizationError::DuplicateOrganizationCode => 10002,
+            OrganizationError::ParentNotFound => 10003,
+            OrganizationError::CyclicParent => 10004,
+            OrganizationError::HasChildren => 10005,
+            OrganizationError::UserNotFound => 10006,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<OrganizationError> for AppError {
+    fn from(err: OrganizationError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
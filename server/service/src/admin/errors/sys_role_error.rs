@@ -8,6 +8,18 @@ pub enum RoleError {
 
     #[error("Duplicate role code")]
     DuplicateRoleCode,
+
+    #[error("Data scope organization not found")]
+    DataScopeOrganizationNotFound,
+
+    #[error("Parent role not found")]
+    ParentRoleNotFound,
+
+    #[error("Parent role cannot be the role itself or one of its descendants")]
+    CyclicParent,
+
+    #[error("Role has child roles")]
+    HasChildren,
 }
 
 impl ApiError for RoleError {
@@ -15,6 +27,10 @@ impl ApiError for RoleError {
         match self {
             RoleError::RoleNotFound => 4001,
             RoleError::DuplicateRoleCode => 4002,
+            RoleError::DataScopeOrganizationNotFound => 4003,
+            RoleError::ParentRoleNotFound => 4004,
+            RoleError::CyclicParent => 4005,
+            RoleError::HasChildren => 4006,
         }
     }
 
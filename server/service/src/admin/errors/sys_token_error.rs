@@ -0,0 +1,41 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TokenError {
+    #[error("Refresh token not found")]
+    RefreshTokenNotFound,
+    #[error("Refresh token is no longer valid")]
+    RefreshTokenInvalid,
+    #[error("Refresh token reuse detected, all related tokens have been revoked")]
+    RefreshTokenReused,
+    #[error("Refresh token has expired")]
+    RefreshTokenExpired,
+    #[error("Session not found")]
+    SessionNotFound,
+}
+
+impl ApiError for TokenError {
+    fn code(&self) -> u16 {
+        match self {
+            TokenError::RefreshTokenNotFound => 6001,
+            TokenError::RefreshTokenInvalid => 6002,
+            TokenError::RefreshTokenReused => 6003,
+            TokenError::SessionNotFound => 6004,
+            TokenError::RefreshTokenExpired => 6005,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<TokenError> for AppError {
+    fn from(err: TokenError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
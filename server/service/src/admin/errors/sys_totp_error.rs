@@ -0,0 +1,38 @@
+use server_core::web::error::{ApiError, AppError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TotpError {
+    #[error("Two-factor authentication is not enrolled")]
+    NotEnrolled,
+    #[error("Two-factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("Invalid verification code")]
+    InvalidCode,
+    #[error("Login challenge is invalid or expired")]
+    ChallengeInvalid,
+}
+
+impl ApiError for TotpError {
+    fn code(&self) -> u16 {
+        match self {
+            TotpError::NotEnrolled => 7001,
+            TotpError::AlreadyEnabled => 7002,
+            TotpError::InvalidCode => 7003,
+            TotpError::ChallengeInvalid => 7004,
+        }
+    }
+
+    fn message(&self) -> String {
+        format!("{}", self)
+    }
+}
+
+impl From<TotpError> for AppError {
+    fn from(err: TotpError) -> Self {
+        AppError {
+            code: err.code(),
+            message: err.message(),
+        }
+    }
+}
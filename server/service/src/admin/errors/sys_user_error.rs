@@ -13,6 +13,22 @@ pub enum UserError {
     UsernameAlreadyExists,
     #[error("Invalid user status")]
     InvalidUserStatus,
+    #[error("Account is locked due to too many failed login attempts")]
+    AccountLocked,
+    #[error("IP is locked due to too many failed login attempts")]
+    IpLocked,
+    #[error("Login from this IP is blocked")]
+    IpBlocked,
+    #[error("Login from this IP is not allowed")]
+    IpNotAllowed,
+    #[error("Login is not allowed at this time")]
+    OutsideLoginHours,
+    #[error("Maximum number of concurrent sessions reached")]
+    SessionLimitExceeded,
+    #[error("Password does not meet policy: {0}")]
+    PasswordPolicyViolation(String),
+    #[error("Password was used recently")]
+    PasswordReused,
 }
 
 impl ApiError for UserError {
@@ -23,6 +39,14 @@ impl ApiError for UserError {
             UserError::AuthenticationFailed => 1003,
             UserError::UsernameAlreadyExists => 1004,
             UserError::InvalidUserStatus => 1005,
+            UserError::AccountLocked => 1006,
+            UserError::IpLocked => 1007,
+            UserError::IpBlocked => 1008,
+            UserError::IpNotAllowed => 1009,
+            UserError::OutsideLoginHours => 1010,
+            UserError::SessionLimitExceeded => 1011,
+            UserError::PasswordPolicyViolation(_) => 1012,
+            UserError::PasswordReused => 1013,
         }
     }
 
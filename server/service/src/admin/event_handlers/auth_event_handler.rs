@@ -1,16 +1,11 @@
 use server_core::web::error::AppError;
 
-use crate::{
-    admin::events::{access_token_event::AccessTokenEvent, login_log_event::LoginLogEvent},
-    helper::db_helper,
-};
+use crate::{admin::events::login_log_event::LoginLogEvent, helper::db_helper};
 
 pub struct AuthEvent {
     pub user_id: String,
     pub username: String,
     pub domain: String,
-    pub access_token: String,
-    pub refresh_token: String,
     pub client_ip: String,
     pub client_port: Option<i32>,
     pub address: String,
@@ -27,35 +22,18 @@ impl AuthEventHandler {
 
         // 处理登录日志
         let login_log_event = LoginLogEvent {
-            user_id: event.user_id.clone(),
-            username: event.username.clone(),
-            domain: event.domain.clone(),
-            ip: event.client_ip.clone(),
-            port: event.client_port,
-            address: event.address.clone(),
-            user_agent: event.user_agent.clone(),
-            request_id: event.request_id.clone(),
-            login_type: event.login_type.clone(),
-        };
-
-        login_log_event.handle(&db).await?;
-
-        // 处理访问令牌
-        let access_token_event = AccessTokenEvent {
-            access_token: event.access_token,
-            refresh_token: event.refresh_token,
             user_id: event.user_id,
             username: event.username,
             domain: event.domain,
             ip: event.client_ip,
             port: event.client_port,
-            address: event.address.clone(),
+            address: event.address,
             user_agent: event.user_agent,
             request_id: event.request_id,
             login_type: event.login_type,
         };
 
-        access_token_event.handle(&db).await?;
+        login_log_event.handle(&db).await?;
 
         Ok(())
     }
@@ -1,5 +1,5 @@
 use chrono::Local;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, Set};
 use server_constant::definition::consts::TokenStatus;
 use server_core::web::error::AppError;
 use server_model::admin::entities::sys_tokens::ActiveModel as SysTokensActiveModel;
@@ -17,14 +17,18 @@ pub struct AccessTokenEvent {
     pub user_agent: String,
     pub request_id: String,
     pub login_type: String,
+    /// 令牌族标识，刷新产生的令牌沿用首次登录令牌的族标识
+    pub family_id: Option<String>,
 }
 
 impl AccessTokenEvent {
-    pub async fn handle(self, db: &DatabaseConnection) -> Result<(), AppError> {
+    pub async fn handle<C: ConnectionTrait>(self, db: &C) -> Result<(), AppError> {
         let now = Local::now().naive_local();
+        let id = Ulid::new().to_string();
+        let family_id = self.family_id.unwrap_or_else(|| id.clone());
 
         SysTokensActiveModel {
-            id: Set(Ulid::new().to_string()),
+            id: Set(id),
             access_token: Set(self.access_token),
             refresh_token: Set(self.refresh_token),
             status: Set(TokenStatus::Active.to_string()),
@@ -40,6 +44,7 @@ impl AccessTokenEvent {
             r#type: Set(self.login_type),
             created_at: Set(now),
             created_by: Set(self.username),
+            family_id: Set(Some(family_id)),
         }
         .insert(db)
         .await
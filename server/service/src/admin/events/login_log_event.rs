@@ -1,9 +1,14 @@
 use chrono::Local;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
-use server_core::web::error::AppError;
-use server_model::admin::entities::sys_login_log::ActiveModel as SysLoginLogActiveModel;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveModel};
+use server_core::{sign::truncate_to_micros, web::error::AppError};
+use server_global::project_error;
+use server_model::admin::entities::{
+    prelude::SysLoginLog, sys_login_log::Model as SysLoginLogModel,
+};
 use ulid::Ulid;
 
+use crate::helper::audit_chain_helper;
+
 pub struct LoginLogEvent {
     pub user_id: String,
     pub username: String,
@@ -18,25 +23,60 @@ pub struct LoginLogEvent {
 
 impl LoginLogEvent {
     pub async fn handle(self, db: &DatabaseConnection) -> Result<(), AppError> {
-        SysLoginLogActiveModel {
-            id: Set(Ulid::new().to_string()),
-            user_id: Set(self.user_id),
-            username: Set(self.username.clone()),
-            domain: Set(self.domain),
-            login_time: Set(Local::now().naive_local()),
-            ip: Set(self.ip),
-            port: Set(self.port),
-            address: Set(self.address),
-            user_agent: Set(self.user_agent),
-            request_id: Set(self.request_id),
-            r#type: Set(self.login_type),
-            created_at: Set(Local::now().naive_local()),
-            created_by: Set(self.username),
+        let now = truncate_to_micros(Local::now().naive_local());
+        let mut records = [SysLoginLogModel {
+            id: Ulid::new().to_string(),
+            user_id: self.user_id,
+            username: self.username.clone(),
+            domain: self.domain,
+            login_time: now,
+            ip: self.ip,
+            port: self.port,
+            address: self.address,
+            user_agent: self.user_agent,
+            request_id: self.request_id,
+            r#type: self.login_type,
+            created_at: now,
+            created_by: self.username,
+            chain_seq: None,
+            prev_hash: None,
+            hash: None,
+        }];
+
+        if !audit_chain_helper::is_enabled().await {
+            return insert(db, &records[0]).await;
         }
+
+        // 链头持有到记录写入完成，写入失败的记录不会成为后续记录的上一条
+        let mut heads = audit_chain_helper::lock_heads::<SysLoginLog>().await;
+
+        if let Err(e) = heads.link(&mut records).await {
+            project_error!("Failed to link login log into hash chain: {:?}", e);
+        }
+        if insert(db, &records[0]).await.is_ok() {
+            return Ok(());
+        }
+
+        // 其他实例可能已写入相同的链序号，重新读取链头入链后再写入一次
+        if let Err(e) = heads.relink(&mut records).await {
+            project_error!("Failed to relink login log into hash chain: {:?}", e);
+        }
+        let result = insert(db, &records[0]).await;
+        if result.is_err() {
+            heads.reset(&records);
+        }
+        result
+    }
+}
+
+async fn insert(db: &DatabaseConnection, record: &SysLoginLogModel) -> Result<(), AppError> {
+    record
+        .clone()
+        .into_active_model()
+        .reset_all()
         .insert(db)
         .await
         .map_err(AppError::from)?;
 
-        Ok(())
-    }
+    Ok(())
 }
@@ -1,10 +1,14 @@
+pub use audit_log_sink::{AuditSink, DatabaseAuditSink, JsonLinesAuditSink, MongoAuditSink};
+pub use audit_log_writer::AuditLogWriter;
 pub use errors::*;
+pub use server_global::global::AuditLogMetricsSnapshot;
 pub use server_model::admin::{
     entities::{
         prelude::{SysDomain, SysEndpoint, SysMenu, SysRole, SysUser},
         sys_access_key::Model as SysAccessKeyModel,
         sys_domain::Model as SysDomainModel,
         sys_endpoint::Model as SysEndpointModel,
+        sys_field_mask::Model as SysFieldMaskModel,
         sys_login_log::Model as SysLoginLogModel,
         sys_menu::Model as SysMenuModel,
         sys_operation_log::Model as SysOperationLogModel,
@@ -17,29 +21,42 @@ pub use server_model::admin::{
 pub use sys_access_key_service::{
     api_key_validate_listener, SysAccessKeyService, TAccessKeyService,
 };
+pub use sys_audit_chain_service::{
+    audit_checkpoint_task, SysAuditChainService, TAuditChainService,
+};
 pub use sys_auth_service::{
     auth_login_listener, jwt_created_listener, SysAuthService, TAuthService,
 };
-pub use sys_authorization_service::{SysAuthorizationService, TAuthorizationService};
+pub use sys_authorization_service::{
+    check_domain_access, SysAuthorizationService, TAuthorizationService,
+};
 pub use sys_domain_service::{SysDomainService, TDomainService};
 pub use sys_endpoint_service::{SysEndpointService, TEndpointService};
+pub use sys_field_mask_service::{SysFieldMaskService, TFieldMaskService};
 pub use sys_login_log_service::{SysLoginLogService, TLoginLogService};
 pub use sys_menu_service::{SysMenuService, TMenuService};
-pub use sys_operation_log_service::{
-    sys_operation_log_listener, SysOperationLogService, TOperationLogService,
-};
+pub use sys_online_session_service::{SysOnlineSessionService, TOnlineSessionService};
+pub use sys_operation_log_service::{SysOperationLogService, TOperationLogService};
 pub use sys_organization_service::{SysOrganizationService, TOrganizationService};
+pub(crate) use sys_role_service::granted_in_domain;
 pub use sys_role_service::{SysRoleService, TRoleService};
 pub use sys_user_service::{SysUserService, TUserService};
+
+pub use crate::helper::policy_csv_helper;
+mod audit_log_sink;
+mod audit_log_writer;
 pub mod dto;
 pub mod errors;
 mod sys_access_key_service;
+mod sys_audit_chain_service;
 mod sys_auth_service;
 mod sys_authorization_service;
 mod sys_domain_service;
 mod sys_endpoint_service;
+mod sys_field_mask_service;
 mod sys_login_log_service;
 mod sys_menu_service;
+mod sys_online_session_service;
 mod sys_operation_log_service;
 mod sys_organization_service;
 mod sys_role_service;
@@ -145,7 +145,6 @@ impl TAccessKeyService for SysAccessKeyService {
             access_key_secret: Set(access_key_secret),
             created_at: Set(Local::now().naive_local()),
             created_by: Set("TODO".to_string()),
-            ..Default::default()
         };
 
         let result = match self
@@ -0,0 +1,330 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Local;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use server_config::AuditChainConfig;
+use server_core::{
+    sign::{checkpoint_message, truncate_to_micros, CheckpointSigner, GENESIS_HASH},
+    web::error::AppError,
+};
+use server_global::{global, project_error, project_info};
+use server_model::admin::{
+    entities::{
+        prelude::{SysAuditCheckpoint, SysLoginLog, SysOperationLog},
+        sys_audit_checkpoint::{
+            ActiveModel as SysAuditCheckpointActiveModel, Column as SysAuditCheckpointColumn,
+            Model as SysAuditCheckpointModel,
+        },
+    },
+    input::{AuditLogType, VerifyAuditChainInput},
+    output::{AuditChainBreak, AuditChainBreakReason, AuditChainVerification},
+};
+use tokio::{fs, sync::OnceCell, time};
+use ulid::Ulid;
+
+use crate::helper::{
+    audit_chain_helper::{self, ChainLink, ChainedLog},
+    db_helper,
+};
+
+/// 校验时每次读取的记录数
+const VERIFY_PAGE_SIZE: u64 = 500;
+
+static CHECKPOINT_SIGNER: OnceCell<Option<Arc<CheckpointSigner>>> = OnceCell::const_new();
+
+#[async_trait]
+pub trait TAuditChainService {
+    /// 校验一个域在时间范围内的哈希链，返回第一个断开的位置
+    async fn verify_chain(
+        &self,
+        input: VerifyAuditChainInput,
+    ) -> Result<AuditChainVerification, AppError>;
+
+    /// 为各链上最新的记录生成签名检查点，返回生成的数量
+    async fn create_checkpoints(&self) -> Result<usize, AppError>;
+}
+
+pub struct SysAuditChainService;
+
+#[async_trait]
+impl TAuditChainService for SysAuditChainService {
+    async fn verify_chain(
+        &self,
+        input: VerifyAuditChainInput,
+    ) -> Result<AuditChainVerification, AppError> {
+        match input.log_type {
+            AuditLogType::Operation => verify::<SysOperationLog>(input).await,
+            AuditLogType::Login => verify::<SysLoginLog>(input).await,
+        }
+    }
+
+    async fn create_checkpoints(&self) -> Result<usize, AppError> {
+        let Some(signer) = checkpoint_signer().await else {
+            return Ok(0);
+        };
+
+        Ok(checkpoint::<SysOperationLog>(&signer).await?
+            + checkpoint::<SysLoginLog>(&signer).await?)
+    }
+}
+
+/// 按间隔生成签名检查点，未配置签名密钥时直接返回
+pub async fn audit_checkpoint_task(interval: Duration) {
+    if checkpoint_signer().await.is_none() {
+        return;
+    }
+
+    let mut ticker = time::interval(interval);
+    // 第一次立即触发，跳过以免每次启动都生成检查点
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match SysAuditChainService.create_checkpoints().await {
+            Ok(0) => {},
+            Ok(created) => project_info!("Created {} audit chain checkpoints", created),
+            Err(e) => project_error!("Failed to create audit chain checkpoints: {:?}", e),
+        }
+    }
+}
+
+/// 按配置的私钥文件创建的签名器，未配置或读取失败时为 `None`
+async fn checkpoint_signer() -> Option<Arc<CheckpointSigner>> {
+    CHECKPOINT_SIGNER
+        .get_or_init(|| async {
+            let config = global::get_config::<AuditChainConfig>().await?;
+            let path = config.signing_key_path.as_ref()?;
+
+            let pkcs8 = fs::read(path)
+                .await
+                .map_err(|e| project_error!("Failed to read checkpoint key '{}': {}", path, e))
+                .ok()?;
+            CheckpointSigner::from_pkcs8(&pkcs8)
+                .map(Arc::new)
+                .map_err(|e| project_error!("Invalid checkpoint key '{}': {}", path, e))
+                .ok()
+        })
+        .await
+        .clone()
+}
+
+async fn verify<E: ChainedLog>(
+    input: VerifyAuditChainInput,
+) -> Result<AuditChainVerification, AppError> {
+    let db = db_helper::get_db_connection().await?;
+
+    let mut result = AuditChainVerification {
+        log_type: E::LOG_TYPE,
+        domain: input.domain.clone(),
+        valid: true,
+        checked: 0,
+        first_seq: None,
+        last_seq: None,
+        checkpoints: 0,
+        broken_link: None,
+    };
+    let broken = |mut result: AuditChainVerification, chain_seq, record_id, reason| {
+        result.valid = false;
+        result.broken_link = Some(AuditChainBreak {
+            chain_seq,
+            record_id,
+            reason,
+        });
+        Ok(result)
+    };
+
+    // 时间范围内入链记录的序号范围，范围内的记录按序号逐条校验
+    let range = E::find()
+        .select_only()
+        .column_as(Expr::col(E::chain_seq_column()).min(), "first_seq")
+        .column_as(Expr::col(E::chain_seq_column()).max(), "last_seq")
+        .filter(E::domain_column().eq(&input.domain))
+        .filter(E::created_at_column().between(input.start_time, input.end_time))
+        .filter(E::chain_seq_column().is_not_null())
+        .into_tuple::<(Option<i64>, Option<i64>)>()
+        .one(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+    let Some((Some(first_seq), Some(last_seq))) = range else {
+        return Ok(result);
+    };
+    result.first_seq = Some(first_seq);
+    result.last_seq = Some(last_seq);
+
+    // 第一条记录接在上一条记录之后，链的第一条记录接在创世哈希之后
+    let mut expected_prev = if first_seq == 1 {
+        GENESIS_HASH.to_string()
+    } else {
+        let previous = E::find()
+            .filter(E::domain_column().eq(&input.domain))
+            .filter(E::chain_seq_column().eq(first_seq - 1))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        match previous.as_ref().and_then(ChainLink::from_model) {
+            Some(link) => link.hash,
+            None => {
+                return broken(
+                    result,
+                    first_seq - 1,
+                    None,
+                    AuditChainBreakReason::MissingRecord,
+                )
+            },
+        }
+    };
+
+    let signer = checkpoint_signer().await;
+    let mut checkpoints: HashMap<i64, Vec<SysAuditCheckpointModel>> = HashMap::new();
+    if let Some(ref signer) = signer {
+        SysAuditCheckpoint::find()
+            .filter(SysAuditCheckpointColumn::LogType.eq(E::LOG_TYPE.as_str()))
+            .filter(SysAuditCheckpointColumn::Domain.eq(&input.domain))
+            .filter(SysAuditCheckpointColumn::ChainSeq.between(first_seq, last_seq))
+            .filter(SysAuditCheckpointColumn::PublicKey.eq(signer.public_key()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .for_each(|checkpoint| {
+                checkpoints
+                    .entry(checkpoint.chain_seq)
+                    .or_default()
+                    .push(checkpoint)
+            });
+    }
+
+    let mut next_seq = first_seq;
+    while next_seq <= last_seq {
+        let records = E::find()
+            .filter(E::domain_column().eq(&input.domain))
+            .filter(E::chain_seq_column().between(next_seq, last_seq))
+            .order_by_asc(E::chain_seq_column())
+            .limit(VERIFY_PAGE_SIZE)
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        if records.is_empty() {
+            return broken(result, next_seq, None, AuditChainBreakReason::MissingRecord);
+        }
+
+        for record in &records {
+            let Some(link) = ChainLink::from_model(record) else {
+                return broken(result, next_seq, None, AuditChainBreakReason::HashMismatch);
+            };
+            if link.chain_seq != next_seq {
+                return broken(result, next_seq, None, AuditChainBreakReason::MissingRecord);
+            }
+            if link.prev_hash != expected_prev {
+                return broken(
+                    result,
+                    link.chain_seq,
+                    Some(link.id),
+                    AuditChainBreakReason::PrevHashMismatch,
+                );
+            }
+            if link.computed_hash() != link.hash {
+                return broken(
+                    result,
+                    link.chain_seq,
+                    Some(link.id),
+                    AuditChainBreakReason::HashMismatch,
+                );
+            }
+
+            if let (Some(signer), Some(checkpoints)) =
+                (signer.as_ref(), checkpoints.get(&link.chain_seq))
+            {
+                for checkpoint in checkpoints {
+                    let message = checkpoint_message(
+                        E::LOG_TYPE.as_str(),
+                        &input.domain,
+                        checkpoint.chain_seq,
+                        &checkpoint.hash,
+                        &checkpoint.created_at,
+                    );
+                    if checkpoint.hash != link.hash
+                        || !signer.verify(&message, &checkpoint.signature)
+                    {
+                        return broken(
+                            result,
+                            link.chain_seq,
+                            Some(link.id),
+                            AuditChainBreakReason::CheckpointMismatch,
+                        );
+                    }
+                    result.checkpoints += 1;
+                }
+            }
+
+            result.checked += 1;
+            expected_prev = link.hash;
+            next_seq += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+/// 为一种日志各个域的链头生成检查点，链头未变化的域跳过
+async fn checkpoint<E: ChainedLog>(signer: &CheckpointSigner) -> Result<usize, AppError> {
+    let db = db_helper::get_db_connection().await?;
+
+    let domains: Vec<String> = E::find()
+        .select_only()
+        .column(E::domain_column())
+        .filter(E::chain_seq_column().is_not_null())
+        .distinct()
+        .into_tuple()
+        .all(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+
+    let mut created = 0;
+    for domain in domains {
+        let Some(link) = audit_chain_helper::latest_link::<E>(&domain).await? else {
+            continue;
+        };
+
+        let latest = SysAuditCheckpoint::find()
+            .filter(SysAuditCheckpointColumn::LogType.eq(E::LOG_TYPE.as_str()))
+            .filter(SysAuditCheckpointColumn::Domain.eq(&domain))
+            .filter(SysAuditCheckpointColumn::PublicKey.eq(signer.public_key()))
+            .order_by_desc(SysAuditCheckpointColumn::ChainSeq)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        if latest.is_some_and(|checkpoint| checkpoint.chain_seq >= link.chain_seq) {
+            continue;
+        }
+
+        let signed_at = truncate_to_micros(Local::now().naive_local());
+        let message = checkpoint_message(
+            E::LOG_TYPE.as_str(),
+            &domain,
+            link.chain_seq,
+            &link.hash,
+            &signed_at,
+        );
+
+        SysAuditCheckpointActiveModel {
+            id: Set(Ulid::new().to_string()),
+            log_type: Set(E::LOG_TYPE.as_str().to_string()),
+            domain: Set(domain),
+            chain_seq: Set(link.chain_seq),
+            hash: Set(link.hash),
+            public_key: Set(signer.public_key().to_string()),
+            signature: Set(signer.sign(&message)),
+            created_at: Set(signed_at),
+        }
+        .insert(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+        created += 1;
+    }
+
+    Ok(created)
+}
@@ -1,15 +1,31 @@
-use std::any::Any;
+use std::{any::Any, collections::HashMap, str::FromStr, time::Duration};
 
 use async_trait::async_trait;
+use chrono::{Local, NaiveDateTime, TimeDelta, Utc};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
-    RelationTrait,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Set,
+    TransactionTrait,
 };
-use server_constant::definition::{consts::SystemEvent, Audience};
-use server_core::web::{
-    auth::Claims,
-    error::AppError,
-    jwt::{JwtError, JwtUtils},
+use server_config::{
+    JwtConfig, LdapConfig, LdapDirectoryConfig, LoginSecurityConfig, MfaConfig, OAuthConfig,
+    PasswordPolicyConfig, SessionConfig, SessionLimitStrategy,
+};
+use server_constant::definition::{
+    consts::{SystemEvent, TokenStatus},
+    Audience,
+};
+use server_core::{
+    ldap::{DirectoryUser, LdapAuthError, LdapAuthenticator},
+    oauth::{self, ExternalIdentity, OAuthState, OAuthStateStore, PkceChallenge},
+    web::{
+        auth::{Claims, User},
+        error::{ApiError, AppError},
+        jwt::{JwtError, JwtUtils},
+        login_security::{self, LoginAttemptStore},
+        mfa_challenge::{MfaChallenge, MfaChallengeStore},
+        token_revocation::TokenRevocationStore,
+    },
 };
 use server_global::global;
 use server_model::admin::{
@@ -18,25 +34,57 @@ use server_model::admin::{
         sea_orm_active_enums::Status,
         sys_domain::Column as SysDomainColumn,
         sys_menu::{Column as SysMenuColumn, Entity as SysMenuEntity, Model as SysMenuModel},
+        sys_organization::Column as SysOrganizationColumn,
         sys_role::{Column as SysRoleColumn, Entity as SysRoleEntity, Relation as SysRoleRelation},
         sys_role_menu::{Column as SysRoleMenuColumn, Entity as SysRoleMenuEntity},
-        sys_user::{Column as SysUserColumn, Relation as SysUserRelation},
-        sys_user_role::Relation as SysUserRoleRelation,
+        sys_tokens::{
+            Column as SysTokensColumn, Entity as SysTokensEntity, Model as SysTokensModel,
+        },
+        sys_user::{
+            ActiveModel as SysUserActiveModel, Column as SysUserColumn, Relation as SysUserRelation,
+        },
+        sys_user_identity::{
+            ActiveModel as SysUserIdentityActiveModel, Column as SysUserIdentityColumn,
+            Entity as SysUserIdentityEntity,
+        },
+        sys_user_organization::Relation as SysUserOrganizationRelation,
+        sys_user_recovery_code::{
+            ActiveModel as SysUserRecoveryCodeActiveModel, Column as SysUserRecoveryCodeColumn,
+            Entity as SysUserRecoveryCodeEntity,
+        },
+        sys_user_role::{
+            ActiveModel as SysUserRoleActiveModel, Column as SysUserRoleColumn,
+            Entity as SysUserRoleEntity, Relation as SysUserRoleRelation,
+        },
+        sys_user_totp::{
+            ActiveModel as SysUserTotpActiveModel, Column as SysUserTotpColumn,
+            Entity as SysUserTotpEntity, Model as SysUserTotpModel,
+        },
+    },
+    input::{LoginInput, OAuthCallbackInput, RefreshTokenInput, TotpCodeInput, TotpLoginInput},
+    output::{
+        AuthOutput, LoginOutput, MenuRoute, MfaChallengeOutput, OAuthAuthorizeOutput, RouteMeta,
+        TotpEnrollOutput, TotpRecoveryCodesOutput, UserRoute, UserWithDomainAndOrgOutput,
     },
-    input::LoginInput,
-    output::{AuthOutput, MenuRoute, RouteMeta, UserRoute, UserWithDomainAndOrgOutput},
 };
-use server_utils::{SecureUtil, TreeBuilder};
+use server_utils::{SecureUtil, TotpUtil, TreeBuilder};
 use thiserror::Error;
 use tokio::sync::mpsc;
-use tracing::{error, instrument};
+use tracing::instrument;
 use ulid::Ulid;
 
 use super::{
-    dto::sys_auth_dto::LoginContext, event_handlers::auth_event_handler::AuthEventHandler,
+    dto::sys_auth_dto::LoginContext,
+    event_handlers::auth_event_handler::AuthEventHandler,
+    events::access_token_event::AccessTokenEvent,
+    sys_role_service::{find_inherited_role_codes, granted_in_domain},
 };
 use crate::{
-    admin::{event_handlers::auth_event_handler::AuthEvent, sys_user_error::UserError},
+    admin::{
+        event_handlers::auth_event_handler::AuthEvent, sys_ldap_error::DirectoryLoginError,
+        sys_oauth_error::ExternalLoginError, sys_token_error::TokenError,
+        sys_totp_error::TotpError, sys_user_error::UserError,
+    },
     helper::db_helper,
     project_error, project_info,
 };
@@ -53,6 +101,17 @@ macro_rules! select_user_with_domain_and_org_info {
             .column_as(SysUserColumn::Avatar, "avatar")
             .column_as(SysDomainColumn::Code, "domain_code")
             .column_as(SysDomainColumn::Name, "domain_name")
+            .column_as(SysUserColumn::PasswordChangedAt, "password_changed_at")
+            .column_as(SysUserColumn::MustChangePassword, "must_change_password")
+            .column_as(SysOrganizationColumn::Code, "org_code")
+            .join(
+                JoinType::LeftJoin,
+                SysUserRelation::SysUserOrganization.def(),
+            )
+            .join(
+                JoinType::LeftJoin,
+                SysUserOrganizationRelation::SysOrganization.def(),
+            )
     }};
 }
 #[derive(Error, Debug)]
@@ -63,14 +122,78 @@ pub enum EventError {
     LoginHandlerError(String),
 }
 
+/// TOTP 校验允许的时钟偏差（时间步数）
+const TOTP_SKEW: u64 = 1;
+/// 每个用户生成的恢复码数量
+const RECOVERY_CODE_COUNT: usize = 10;
+/// 单个登录挑战允许的验证码错误次数
+const MAX_MFA_ATTEMPTS: u64 = 5;
+
 #[async_trait]
 pub trait TAuthService: Send + Sync {
+    /// 密码登录，用户启用了两步验证时返回挑战令牌
     async fn pwd_login(
         &self,
         input: LoginInput,
         context: LoginContext,
+    ) -> Result<LoginOutput, AppError>;
+
+    /// 带登录安全检查的密码登录
+    ///
+    /// 登录前校验 IP 黑白名单、登录时段与锁定状态；认证失败或用户不存在时累计失败次数，
+    /// 达到阈值后锁定账号或 IP，登录成功后清除账号的失败计数。
+    async fn pwd_login_with_security(
+        &self,
+        input: LoginInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError>;
+
+    /// 两步登录的第二步：使用挑战令牌与 TOTP 验证码或恢复码换取令牌
+    async fn totp_login(
+        &self,
+        input: TotpLoginInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError>;
+
+    /// 生成外部身份提供方的授权地址，state、nonce 与 PKCE 校验码保存在服务端
+    async fn oauth_authorize(&self, provider: &str) -> Result<OAuthAuthorizeOutput, AppError>;
+
+    /// 处理外部身份提供方的回调
+    ///
+    /// 按 `sys_user_identity` 中的绑定关系找到用户后登录；未绑定且提供方开启了自动创建时，
+    /// 在提供方所属域中新建用户并分配该域的默认角色。用户启用了两步验证时同样返回挑战令牌。
+    async fn oauth_login(
+        &self,
+        provider: &str,
+        input: OAuthCallbackInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError>;
+
+    /// 开始绑定 TOTP，返回密钥与 otpauth URI，验证通过前不生效
+    async fn totp_enroll(&self, user: &User) -> Result<TotpEnrollOutput, AppError>;
+
+    /// 校验验证码并启用 TOTP，返回一次性恢复码
+    async fn totp_activate(
+        &self,
+        user: &User,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError>;
+
+    /// 校验验证码或恢复码后停用 TOTP
+    async fn totp_disable(&self, user: &User, input: TotpCodeInput) -> Result<(), AppError>;
+
+    /// 使用刷新令牌换取新的令牌对
+    ///
+    /// 旧令牌被标记为已刷新；若已刷新的令牌被再次使用，视为令牌泄露，撤销整个令牌族。
+    async fn refresh_token(
+        &self,
+        input: RefreshTokenInput,
+        context: LoginContext,
     ) -> Result<AuthOutput, AppError>;
 
+    /// 退出登录，吊销当前访问令牌及其刷新令牌
+    async fn logout(&self, user: &User, access_token: &str) -> Result<(), AppError>;
+
     async fn get_user_routes(
         &self,
         role_codes: &[String],
@@ -106,29 +229,499 @@ impl TAuthService for SysAuthService {
         &self,
         input: LoginInput,
         context: LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        // 验证用户并获取角色，配置了 LDAP 目录的域通过目录认证
+        let ldap_config = global::get_config::<LdapConfig>().await.unwrap_or_default();
+        let (user, role_codes) = match ldap_config.directory_for(&context.domain) {
+            Some(directory) => {
+                self.verify_directory_user(
+                    &input.identifier,
+                    &input.password,
+                    &context.domain,
+                    directory,
+                )
+                .await?
+            },
+            None => {
+                self.verify_user(&input.identifier, &input.password, &context.domain)
+                    .await?
+            },
+        };
+
+        self.finish_primary_login(&user, role_codes, &context).await
+    }
+
+    #[instrument(skip(self, input), fields(username = %input.identifier, domain = %context.domain))]
+    async fn pwd_login_with_security(
+        &self,
+        input: LoginInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        let config = global::get_config::<LoginSecurityConfig>()
+            .await
+            .unwrap_or_default();
+        let store = LoginAttemptStore::current().await;
+
+        let username = input.identifier.clone();
+        let domain = context.domain.clone();
+        let client_ip = context.client_ip.clone();
+
+        self.check_login_security(&username, &domain, &client_ip, &config, &store)
+            .await?;
+
+        match self.pwd_login(input, context).await {
+            Ok(output) => {
+                store.reset(&user_attempt_key(&domain, &username)).await;
+                Ok(output)
+            },
+            Err(e) => {
+                if e.code == UserError::WrongPassword.code()
+                    || e.code == UserError::AuthenticationFailed.code()
+                    || e.code == UserError::UserNotFound.code()
+                {
+                    self.record_login_failure(&username, &domain, &client_ip, &config, &store)
+                        .await;
+                }
+                Err(e)
+            },
+        }
+    }
+
+    #[instrument(skip(self, input), fields(domain = %context.domain))]
+    async fn totp_login(
+        &self,
+        input: TotpLoginInput,
+        context: LoginContext,
     ) -> Result<AuthOutput, AppError> {
-        // 验证用户并获取角色
-        let (user, role_codes) = self
-            .verify_user(&input.identifier, &input.password, &context.domain)
+        let store = MfaChallengeStore::current().await;
+        let challenge = store
+            .get(&input.challenge_token)
+            .await
+            .ok_or_else(|| AppError::from(TotpError::ChallengeInvalid))?;
+        // 挑战令牌绑定了密码登录时的域
+        let context = LoginContext {
+            domain: challenge.domain.clone(),
+            ..context
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        if !verify_second_factor(db.as_ref(), &challenge.user_id, &input.code).await? {
+            // 限制单个挑战的尝试次数，超出后需重新进行密码登录
+            let config = global::get_config::<MfaConfig>().await.unwrap_or_default();
+            let attempts = LoginAttemptStore::current()
+                .await
+                .record_failure(
+                    &format!("mfa:{}", input.challenge_token),
+                    Duration::from_secs(config.challenge_ttl),
+                )
+                .await;
+            if attempts >= MAX_MFA_ATTEMPTS {
+                store.remove(&input.challenge_token).await;
+            }
+            return Err(TotpError::InvalidCode.into());
+        }
+
+        store.remove(&input.challenge_token).await;
+
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&challenge.user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&challenge.domain))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        self.complete_login(&user, role_codes, &context).await
+    }
+
+    async fn oauth_authorize(&self, provider: &str) -> Result<OAuthAuthorizeOutput, AppError> {
+        let identity_provider = oauth::get_provider(provider)
+            .await
+            .ok_or(ExternalLoginError::ProviderNotFound)?;
+        let config = global::get_config::<OAuthConfig>()
+            .await
+            .unwrap_or_default();
+
+        let pkce = PkceChallenge::generate();
+        let nonce = oauth::generate_random_token();
+        let state = OAuthStateStore::current()
+            .await
+            .issue(
+                &OAuthState {
+                    provider: provider.to_string(),
+                    nonce: nonce.clone(),
+                    pkce_verifier: pkce.verifier,
+                },
+                Duration::from_secs(config.state_ttl),
+            )
+            .await;
+
+        let authorize_url = identity_provider
+            .authorization_url(&state, &nonce, &pkce.challenge)
+            .await
+            .map_err(ExternalLoginError::from)?;
+
+        Ok(OAuthAuthorizeOutput {
+            authorize_url,
+            state,
+        })
+    }
+
+    #[instrument(skip(self, input, context), fields(provider = %provider))]
+    async fn oauth_login(
+        &self,
+        provider: &str,
+        input: OAuthCallbackInput,
+        context: LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        let config = global::get_config::<OAuthConfig>()
+            .await
+            .unwrap_or_default();
+        let provider_config = config
+            .provider(provider)
+            .ok_or(ExternalLoginError::ProviderNotFound)?;
+        let identity_provider = oauth::get_provider(provider)
+            .await
+            .ok_or(ExternalLoginError::ProviderNotFound)?;
+
+        let state = OAuthStateStore::current()
+            .await
+            .take(&input.state)
+            .await
+            .filter(|state| state.provider == provider)
+            .ok_or(ExternalLoginError::StateInvalid)?;
+        let identity = identity_provider
+            .exchange_code(&input.code, &state.pkce_verifier, &state.nonce)
+            .await
+            .map_err(ExternalLoginError::from)?;
+
+        let domain = provider_config.domain.clone();
+        let context = LoginContext {
+            domain: domain.clone(),
+            login_type: format!("OAUTH:{}", provider),
+            ..context
+        };
+
+        // 外部登录同样受域的 IP 访问控制、登录时段与锁定状态约束，需在创建账号前检查
+        let security_config = global::get_config::<LoginSecurityConfig>()
+            .await
+            .unwrap_or_default();
+        let store = LoginAttemptStore::current().await;
+        self.check_client_access(&domain, &context.client_ip, &security_config, &store)
             .await?;
 
-        // 生成认证输出
+        let db = db_helper::get_db_connection().await?;
+        let linked = SysUserIdentityEntity::find()
+            .filter(SysUserIdentityColumn::Provider.eq(provider))
+            .filter(SysUserIdentityColumn::Subject.eq(&identity.subject))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        let user_id = match linked {
+            Some(link) => link.user_id,
+            None if provider_config.auto_provision => {
+                provision_external_user(
+                    db.as_ref(),
+                    &identity,
+                    &domain,
+                    config.default_roles.get(&domain),
+                )
+                .await?
+            },
+            None => return Err(ExternalLoginError::IdentityNotLinked.into()),
+        };
+
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(&domain))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+
+        if store
+            .is_locked(&user_attempt_key(&domain, &user.username))
+            .await
+        {
+            return Err(UserError::AccountLocked.into());
+        }
+
+        SysUserIdentityEntity::update_many()
+            .col_expr(
+                SysUserIdentityColumn::LastLoginAt,
+                Expr::value(Local::now().naive_local()),
+            )
+            .filter(SysUserIdentityColumn::Provider.eq(provider))
+            .filter(SysUserIdentityColumn::Subject.eq(&identity.subject))
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        self.finish_primary_login(&user, role_codes, &context).await
+    }
+
+    #[instrument(skip(self, user), fields(user_id = %user.user_id()))]
+    async fn totp_enroll(&self, user: &User) -> Result<TotpEnrollOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let user_id = user.user_id();
+
+        if find_user_totp(db.as_ref(), &user_id)
+            .await?
+            .is_some_and(|totp| totp.enabled)
+        {
+            return Err(TotpError::AlreadyEnabled.into());
+        }
+
+        let config = global::get_config::<MfaConfig>().await.unwrap_or_default();
+        let secret = TotpUtil::generate_secret();
+        let account = format!("{}@{}", user.username(), user.domain());
+        let otpauth_uri = TotpUtil::otpauth_uri(&config.totp_issuer, &account, &secret);
+
+        // 覆盖尚未启用的绑定
+        let txn = db.begin().await.map_err(AppError::from)?;
+        SysUserTotpEntity::delete_by_id(user_id.clone())
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        SysUserTotpActiveModel {
+            user_id: Set(user_id),
+            secret: Set(secret.clone()),
+            enabled: Set(false),
+            last_used_step: Set(None),
+            created_at: Set(Local::now().naive_local()),
+            updated_at: Set(None),
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::from)?;
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(TotpEnrollOutput {
+            secret,
+            otpauth_uri,
+        })
+    }
+
+    #[instrument(skip(self, user, input), fields(user_id = %user.user_id()))]
+    async fn totp_activate(
+        &self,
+        user: &User,
+        input: TotpCodeInput,
+    ) -> Result<TotpRecoveryCodesOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let user_id = user.user_id();
+
+        let totp = find_user_totp(db.as_ref(), &user_id)
+            .await?
+            .ok_or_else(|| AppError::from(TotpError::NotEnrolled))?;
+        if totp.enabled {
+            return Err(TotpError::AlreadyEnabled.into());
+        }
+
+        let step = TotpUtil::verify_code(&totp.secret, &input.code, unix_now(), TOTP_SKEW)
+            .ok_or_else(|| AppError::from(TotpError::InvalidCode))?;
+
+        let recovery_codes = TotpUtil::generate_recovery_codes(RECOVERY_CODE_COUNT);
+        let now = Local::now().naive_local();
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+        SysUserTotpEntity::update_many()
+            .col_expr(SysUserTotpColumn::Enabled, Expr::value(true))
+            .col_expr(SysUserTotpColumn::LastUsedStep, Expr::value(step as i64))
+            .col_expr(SysUserTotpColumn::UpdatedAt, Expr::value(now))
+            .filter(SysUserTotpColumn::UserId.eq(&user_id))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+        SysUserRecoveryCodeEntity::delete_many()
+            .filter(SysUserRecoveryCodeColumn::UserId.eq(&user_id))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+        for code in &recovery_codes {
+            let code_hash =
+                SecureUtil::hash_password(TotpUtil::normalize_recovery_code(code).as_bytes())
+                    .map_err(|e| AppError {
+                        code: 500,
+                        message: e.to_string(),
+                    })?;
+            SysUserRecoveryCodeActiveModel {
+                id: Set(Ulid::new().to_string()),
+                user_id: Set(user_id.clone()),
+                code_hash: Set(code_hash),
+                used_at: Set(None),
+                created_at: Set(now),
+            }
+            .insert(&txn)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(TotpRecoveryCodesOutput { recovery_codes })
+    }
+
+    #[instrument(skip(self, user, input), fields(user_id = %user.user_id()))]
+    async fn totp_disable(&self, user: &User, input: TotpCodeInput) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let user_id = user.user_id();
+
+        if !verify_second_factor(db.as_ref(), &user_id, &input.code).await? {
+            return Err(TotpError::InvalidCode.into());
+        }
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+        SysUserTotpEntity::delete_by_id(user_id.clone())
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        SysUserRecoveryCodeEntity::delete_many()
+            .filter(SysUserRecoveryCodeColumn::UserId.eq(&user_id))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, input, context))]
+    async fn refresh_token(
+        &self,
+        input: RefreshTokenInput,
+        context: LoginContext,
+    ) -> Result<AuthOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let token = SysTokensEntity::find()
+            .filter(SysTokensColumn::RefreshToken.eq(&input.refresh_token))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(TokenError::RefreshTokenNotFound))?;
+
+        let family_id = token.family_id.clone().unwrap_or_else(|| token.id.clone());
+        let status = TokenStatus::from_str(&token.status)
+            .map_err(|_| AppError::from(TokenError::RefreshTokenInvalid))?;
+
+        // 已刷新的令牌被重放，撤销整个令牌族
+        if status == TokenStatus::Refreshed {
+            Self::revoke_token_family(db.as_ref(), &family_id).await?;
+            return Err(AppError::from(TokenError::RefreshTokenReused));
+        }
+
+        if !status.can_refresh() {
+            return Err(AppError::from(TokenError::RefreshTokenInvalid));
+        }
+
+        let family_start = session_started_at(db.as_ref(), std::slice::from_ref(&token)).await?[0];
+        if Local::now().naive_local() - family_start > session_ttl().await {
+            return Err(AppError::from(TokenError::RefreshTokenExpired));
+        }
+
+        // 续期的会话同样受并发会话数限制
+        self.enforce_session_limit(&token.user_id, Some(&family_id))
+            .await?;
+
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&token.user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+
+        // 仅当令牌仍为活跃状态时才标记为已刷新，防止并发刷新同一令牌
+        let updated = SysTokensEntity::update_many()
+            .col_expr(
+                SysTokensColumn::Status,
+                Expr::value(TokenStatus::Refreshed.to_string()),
+            )
+            .filter(SysTokensColumn::Id.eq(&token.id))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+        if updated.rows_affected == 0 {
+            txn.rollback().await.map_err(AppError::from)?;
+            Self::revoke_token_family(db.as_ref(), &family_id).await?;
+            return Err(AppError::from(TokenError::RefreshTokenReused));
+        }
+
         let auth_output = generate_auth_output(
             user.id.clone(),
             user.username.clone(),
             role_codes,
             user.domain_code.clone(),
-            None,
+            user.org_code.clone(),
             context.audience,
         )
         .await?;
 
-        // 发送认证事件
-        self.send_login_event(&user, &auth_output, &context).await;
+        AccessTokenEvent {
+            access_token: auth_output.token.clone(),
+            refresh_token: auth_output.refresh_token.clone(),
+            user_id: user.id,
+            username: user.username,
+            domain: user.domain_code,
+            ip: context.client_ip,
+            port: context.client_port,
+            address: context.address,
+            user_agent: context.user_agent,
+            request_id: context.request_id,
+            login_type: token.r#type,
+            family_id: Some(family_id),
+        }
+        .handle(&txn)
+        .await?;
+
+        txn.commit().await.map_err(AppError::from)?;
 
         Ok(auth_output)
     }
 
+    #[instrument(skip(self, user, access_token), fields(user_id = %user.user_id()))]
+    async fn logout(&self, user: &User, access_token: &str) -> Result<(), AppError> {
+        if let Some(jti) = user.jti() {
+            TokenRevocationStore::current()
+                .await
+                .revoke(&jti, remaining_ttl(user.exp()))
+                .await;
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let tokens = SysTokensEntity::find()
+            .filter(SysTokensColumn::AccessToken.eq(access_token))
+            .filter(SysTokensColumn::UserId.eq(user.user_id()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        revoke_tokens(db.as_ref(), tokens).await?;
+
+        Ok(())
+    }
+
     #[instrument(skip(self), fields(roles = ?role_codes, domain = %domain))]
     async fn get_user_routes(
         &self,
@@ -144,6 +737,9 @@ impl TAuthService for SysAuthService {
 
         let db = db_helper::get_db_connection().await?;
 
+        // 合并从上级角色继承的菜单
+        let role_codes = find_inherited_role_codes(role_codes).await?;
+
         let menu_ids = SysRoleMenuEntity::find()
             .select_only()
             .column(SysRoleMenuColumn::MenuId)
@@ -151,7 +747,7 @@ impl TAuthService for SysAuthService {
                 JoinType::InnerJoin,
                 SysRoleEntity::has_many(SysRoleMenuEntity).into(),
             )
-            .filter(SysRoleColumn::Code.is_in(role_codes.to_vec()))
+            .filter(SysRoleColumn::Code.is_in(role_codes))
             .filter(SysRoleMenuColumn::Domain.eq(domain))
             .distinct()
             .into_tuple::<i32>()
@@ -245,12 +841,103 @@ impl SysAuthService {
             return Err(AppError::from(UserError::WrongPassword));
         }
 
+        // 旧系统迁移的哈希或参数过时的哈希，登录成功后按当前 Argon2 参数重新生成
+        if SecureUtil::needs_rehash(&user.password) {
+            Self::rehash_password(db.as_ref(), &user, password).await;
+        }
+
         // 获取角色
         let role_codes = self.get_user_roles(&user.id, &db).await?;
 
         Ok((user, role_codes))
     }
 
+    /// 通过 LDAP 目录验证用户身份，并同步本地用户与目录组映射的角色
+    async fn verify_directory_user(
+        &self,
+        identifier: &str,
+        password: &str,
+        domain: &str,
+        directory: &LdapDirectoryConfig,
+    ) -> Result<(UserWithDomainAndOrgOutput, Vec<String>), AppError> {
+        let directory_user = match LdapAuthenticator::new(directory)
+            .authenticate(identifier, password)
+            .await
+        {
+            Ok(Some(directory_user)) => directory_user,
+            Ok(None) if directory.local_fallback => {
+                return self.verify_user(identifier, password, domain).await;
+            },
+            Ok(None) => return Err(UserError::UserNotFound.into()),
+            // 与本地密码错误一致，计入登录失败次数
+            Err(LdapAuthError::InvalidCredentials) => return Err(UserError::WrongPassword.into()),
+            Err(LdapAuthError::AmbiguousUser) => {
+                return Err(DirectoryLoginError::AmbiguousUser.into());
+            },
+            Err(LdapAuthError::Unavailable(e)) => {
+                project_error!("LDAP directory for domain {} unavailable: {}", domain, e);
+                return Err(DirectoryLoginError::Unavailable.into());
+            },
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        let user_id = sync_directory_user(
+            db.as_ref(),
+            &directory_user,
+            domain,
+            &directory.map_roles(&directory_user.groups),
+            &directory.managed_roles(),
+        )
+        .await?;
+
+        let user = select_user_with_domain_and_org_info!(SysUser::find())
+            .filter(SysUserColumn::Id.eq(&user_id))
+            .filter(SysUserColumn::Status.eq(Status::ENABLED))
+            .filter(SysDomainColumn::Code.eq(domain))
+            .join(JoinType::InnerJoin, SysUserRelation::SysDomain.def())
+            .into_model::<UserWithDomainAndOrgOutput>()
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(UserError::UserNotFound))?;
+
+        let role_codes = self.get_user_roles(&user.id, &db).await?;
+
+        Ok((user, role_codes))
+    }
+
+    /// 重新哈希用户密码，失败时仅记录日志，不影响本次登录
+    async fn rehash_password(
+        db: &DatabaseConnection,
+        user: &UserWithDomainAndOrgOutput,
+        password: &str,
+    ) {
+        let password_hash = match SecureUtil::hash_password(password.as_bytes()) {
+            Ok(password_hash) => password_hash,
+            Err(e) => {
+                project_error!("Failed to rehash password for user {}: {}", user.id, e);
+                return;
+            },
+        };
+
+        // 仅在密码未被并发修改时更新
+        let result = SysUser::update_many()
+            .col_expr(SysUserColumn::Password, Expr::value(password_hash))
+            .filter(SysUserColumn::Id.eq(&user.id))
+            .filter(SysUserColumn::Password.eq(&user.password))
+            .exec(db)
+            .await;
+
+        match result {
+            Ok(_) => project_info!("Rehashed password for user {}", user.id),
+            Err(e) => project_error!(
+                "Failed to update rehashed password for user {}: {}",
+                user.id,
+                e
+            ),
+        }
+    }
+
     /// 获取用户角色
     async fn get_user_roles(
         &self,
@@ -267,18 +954,32 @@ impl SysAuthService {
             .map_err(AppError::from)
     }
 
-    async fn send_login_event(
-        &self,
-        user: &UserWithDomainAndOrgOutput,
-        auth_output: &AuthOutput,
-        context: &LoginContext,
-    ) {
+    /// 撤销令牌族中所有未撤销的令牌
+    async fn revoke_token_family<C: ConnectionTrait>(
+        db: &C,
+        family_id: &str,
+    ) -> Result<(), AppError> {
+        let tokens = SysTokensEntity::find()
+            .filter(
+                Condition::any()
+                    .add(SysTokensColumn::FamilyId.eq(family_id))
+                    .add(SysTokensColumn::Id.eq(family_id)),
+            )
+            .filter(SysTokensColumn::Status.ne(TokenStatus::Revoked.to_string()))
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+
+        revoke_tokens(db, tokens).await?;
+
+        Ok(())
+    }
+
+    async fn send_login_event(&self, user: &UserWithDomainAndOrgOutput, context: &LoginContext) {
         let auth_event = AuthEvent {
             user_id: user.id.clone(),
             username: user.username.clone(),
             domain: user.domain_code.clone(),
-            access_token: auth_output.token.clone(),
-            refresh_token: auth_output.refresh_token.clone(),
             client_ip: context.client_ip.clone(),
             client_port: context.client_port,
             address: context.address.clone(),
@@ -293,30 +994,760 @@ impl SysAuthService {
         );
     }
 
+    /// 登录前的安全检查：IP 黑名单 → IP 白名单 → 登录时段 → IP 锁定 → 账号锁定
     async fn check_login_security(
         &self,
-        _username: &str,
-        _client_ip: &str,
+        username: &str,
+        domain: &str,
+        client_ip: &str,
+        config: &LoginSecurityConfig,
+        store: &LoginAttemptStore,
     ) -> Result<(), AppError> {
-        // TODO: 实现登录安全检查
-        // 1. 检查登录失败次数
-        // 2. 检查 IP 黑名单
-        // 3. 检查账号是否被锁定
-        // 4. 检查是否在允许的时间范围内
+        self.check_client_access(domain, client_ip, config, store)
+            .await?;
+
+        if store.is_locked(&user_attempt_key(domain, username)).await {
+            return Err(UserError::AccountLocked.into());
+        }
+
         Ok(())
     }
 
-    #[allow(dead_code)]
-    async fn pwd_login_with_security(
+    /// 与账号无关的安全检查：IP 黑名单 → IP 白名单 → 登录时段 → IP 锁定
+    async fn check_client_access(
         &self,
-        input: LoginInput,
-        context: LoginContext,
+        domain: &str,
+        client_ip: &str,
+        config: &LoginSecurityConfig,
+        store: &LoginAttemptStore,
+    ) -> Result<(), AppError> {
+        if let Some(policy) = config.domains.get(domain) {
+            if login_security::ip_matches(&policy.ip_denylist, client_ip) {
+                return Err(UserError::IpBlocked.into());
+            }
+
+            if !policy.ip_allowlist.is_empty()
+                && !login_security::ip_matches(&policy.ip_allowlist, client_ip)
+            {
+                return Err(UserError::IpNotAllowed.into());
+            }
+
+            if let Some(hours) = &policy.login_hours {
+                match login_security::within_login_hours(
+                    &hours.start,
+                    &hours.end,
+                    Local::now().time(),
+                ) {
+                    Some(true) => {},
+                    Some(false) => return Err(UserError::OutsideLoginHours.into()),
+                    None => project_error!(
+                        "Invalid login hours for domain {}: {}-{}",
+                        domain,
+                        hours.start,
+                        hours.end
+                    ),
+                }
+            }
+        }
+
+        if store.is_locked(&ip_attempt_key(client_ip)).await {
+            return Err(UserError::IpLocked.into());
+        }
+
+        Ok(())
+    }
+
+    /// 第一步认证通过后完成登录：已启用两步验证时签发挑战令牌，否则直接签发令牌
+    async fn finish_primary_login(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        role_codes: Vec<String>,
+        context: &LoginContext,
+    ) -> Result<LoginOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        if find_user_totp(db.as_ref(), &user.id)
+            .await?
+            .is_some_and(|totp| totp.enabled)
+        {
+            let config = global::get_config::<MfaConfig>().await.unwrap_or_default();
+            let challenge = MfaChallenge {
+                user_id: user.id.clone(),
+                domain: user.domain_code.clone(),
+            };
+            let challenge_token = MfaChallengeStore::current()
+                .await
+                .issue(&challenge, Duration::from_secs(config.challenge_ttl))
+                .await;
+
+            return Ok(LoginOutput::MfaRequired(MfaChallengeOutput {
+                mfa_required: true,
+                challenge_token,
+                expires_in: config.challenge_ttl,
+            }));
+        }
+
+        self.complete_login(user, role_codes, context)
+            .await
+            .map(LoginOutput::Authenticated)
+    }
+
+    /// 签发令牌、写入令牌记录并发送登录事件
+    async fn complete_login(
+        &self,
+        user: &UserWithDomainAndOrgOutput,
+        role_codes: Vec<String>,
+        context: &LoginContext,
     ) -> Result<AuthOutput, AppError> {
-        self.check_login_security(&input.identifier, &context.client_ip)
-            .await?;
+        // 检查并发会话数
+        self.enforce_session_limit(&user.id, None).await?;
+
+        // 生成认证输出
+        let mut auth_output = generate_auth_output(
+            user.id.clone(),
+            user.username.clone(),
+            role_codes,
+            user.domain_code.clone(),
+            user.org_code.clone(),
+            context.audience,
+        )
+        .await?;
+
+        // 标记密码状态，由前端引导用户修改密码
+        let policy_config = global::get_config::<PasswordPolicyConfig>()
+            .await
+            .unwrap_or_default();
+        let max_age_days = policy_config.policy_for(&user.domain_code).max_age_days;
+        auth_output.password_expired = max_age_days > 0
+            && user.password_changed_at.is_some_and(|changed_at| {
+                Local::now().naive_local() - changed_at > TimeDelta::days(max_age_days as i64)
+            });
+        auth_output.must_change_password = user.must_change_password;
+
+        // 令牌记录同步写入，登录后可立即刷新
+        let db = db_helper::get_db_connection().await?;
+        AccessTokenEvent {
+            access_token: auth_output.token.clone(),
+            refresh_token: auth_output.refresh_token.clone(),
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            domain: user.domain_code.clone(),
+            ip: context.client_ip.clone(),
+            port: context.client_port,
+            address: context.address.clone(),
+            user_agent: context.user_agent.clone(),
+            request_id: context.request_id.clone(),
+            login_type: context.login_type.clone(),
+            family_id: None,
+        }
+        .handle(db.as_ref())
+        .await?;
 
-        self.pwd_login(input, context).await
+        // 发送认证事件，异步记录登录日志
+        self.send_login_event(user, context).await;
+
+        Ok(auth_output)
     }
+
+    /// 检查用户的在线会话数，超出限制时按配置踢出最早的会话或拒绝登录
+    ///
+    /// 会话在刷新令牌过期前都计入在线会话数。续期时传入会话的令牌族标识，
+    /// 该会话按新会话参与计算。
+    async fn enforce_session_limit(
+        &self,
+        user_id: &str,
+        current_family: Option<&str>,
+    ) -> Result<(), AppError> {
+        let config = global::get_config::<SessionConfig>()
+            .await
+            .unwrap_or_default();
+        if config.max_concurrent == 0 {
+            return Ok(());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let sessions = SysTokensEntity::find()
+            .filter(SysTokensColumn::UserId.eq(user_id))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .filter(|session| {
+                current_family.is_none_or(|family| {
+                    session.family_id.as_deref().unwrap_or(&session.id) != family
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let started_at = session_started_at(db.as_ref(), &sessions).await?;
+        let mut sessions: Vec<_> = started_at.into_iter().zip(sessions).collect();
+        sessions.sort_by_key(|(started_at, _)| *started_at);
+
+        let started_at: Vec<_> = sessions.iter().map(|(started_at, _)| *started_at).collect();
+        let excess = login_security::sessions_over_limit(
+            &started_at,
+            Local::now().naive_local(),
+            session_ttl().await,
+            config.max_concurrent,
+        );
+        if excess.is_empty() {
+            return Ok(());
+        }
+
+        match config.limit_strategy {
+            SessionLimitStrategy::RejectNew => Err(UserError::SessionLimitExceeded.into()),
+            SessionLimitStrategy::EvictOldest => {
+                let evicted: Vec<SysTokensModel> = excess
+                    .into_iter()
+                    .map(|index| sessions[index].1.clone())
+                    .collect();
+                revoke_tokens(db.as_ref(), evicted).await?;
+                Ok(())
+            },
+        }
+    }
+
+    /// 记录登录失败，达到阈值时锁定对应的账号或 IP
+    async fn record_login_failure(
+        &self,
+        username: &str,
+        domain: &str,
+        client_ip: &str,
+        config: &LoginSecurityConfig,
+        store: &LoginAttemptStore,
+    ) {
+        let window = Duration::from_secs(config.failure_window);
+        let lockout = Duration::from_secs(config.lockout_duration);
+
+        let user_key = user_attempt_key(domain, username);
+        let failures = store.record_failure(&user_key, window).await;
+        if config.max_failed_attempts > 0 && failures >= config.max_failed_attempts {
+            project_info!(
+                "Account {} locked after {} failed logins",
+                user_key,
+                failures
+            );
+            store.lock(&user_key, lockout).await;
+            store.reset(&user_key).await;
+        }
+
+        let ip_key = ip_attempt_key(client_ip);
+        let failures = store.record_failure(&ip_key, window).await;
+        if config.ip_max_failed_attempts > 0 && failures >= config.ip_max_failed_attempts {
+            project_info!("IP {} locked after {} failed logins", client_ip, failures);
+            store.lock(&ip_key, lockout).await;
+            store.reset(&ip_key).await;
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
+
+async fn find_user_totp<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+) -> Result<Option<SysUserTotpModel>, AppError> {
+    SysUserTotpEntity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 校验已启用用户的 TOTP 验证码或恢复码
+///
+/// 验证码所在时间步不得早于或等于上次使用的时间步，防止重放；恢复码使用后即失效。
+async fn verify_second_factor<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+    code: &str,
+) -> Result<bool, AppError> {
+    let totp = find_user_totp(db, user_id)
+        .await?
+        .filter(|totp| totp.enabled)
+        .ok_or_else(|| AppError::from(TotpError::NotEnrolled))?;
+
+    if let Some(step) = TotpUtil::verify_code(&totp.secret, code, unix_now(), TOTP_SKEW) {
+        let updated = SysUserTotpEntity::update_many()
+            .col_expr(SysUserTotpColumn::LastUsedStep, Expr::value(step as i64))
+            .col_expr(
+                SysUserTotpColumn::UpdatedAt,
+                Expr::value(Local::now().naive_local()),
+            )
+            .filter(SysUserTotpColumn::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(SysUserTotpColumn::LastUsedStep.is_null())
+                    .add(SysUserTotpColumn::LastUsedStep.lt(step as i64)),
+            )
+            .exec(db)
+            .await
+            .map_err(AppError::from)?;
+        return Ok(updated.rows_affected > 0);
+    }
+
+    let normalized = TotpUtil::normalize_recovery_code(code);
+    let recovery_codes = SysUserRecoveryCodeEntity::find()
+        .filter(SysUserRecoveryCodeColumn::UserId.eq(user_id))
+        .filter(SysUserRecoveryCodeColumn::UsedAt.is_null())
+        .all(db)
+        .await
+        .map_err(AppError::from)?;
+
+    for recovery_code in recovery_codes {
+        if SecureUtil::verify_password(normalized.as_bytes(), &recovery_code.code_hash)
+            .unwrap_or(false)
+        {
+            let updated = SysUserRecoveryCodeEntity::update_many()
+                .col_expr(
+                    SysUserRecoveryCodeColumn::UsedAt,
+                    Expr::value(Local::now().naive_local()),
+                )
+                .filter(SysUserRecoveryCodeColumn::Id.eq(&recovery_code.id))
+                .filter(SysUserRecoveryCodeColumn::UsedAt.is_null())
+                .exec(db)
+                .await
+                .map_err(AppError::from)?;
+            return Ok(updated.rows_affected > 0);
+        }
+    }
+
+    Ok(false)
+}
+
+/// 为未绑定的外部账号创建用户并绑定，返回新用户 ID
+///
+/// 用户名优先使用提供方的 `preferred_username`，其次为邮箱前缀，已被占用时追加随机后缀；
+/// 邮箱已被其他用户使用时不写入，避免按邮箱隐式关联已有账号。用户获得一个随机密码，
+/// 只能通过外部登录或管理员重置密码后登录。默认角色须在目标域内获得授权。
+async fn provision_external_user(
+    db: &DatabaseConnection,
+    identity: &ExternalIdentity,
+    domain: &str,
+    default_role: Option<&String>,
+) -> Result<String, AppError> {
+    let role = match default_role {
+        Some(code) => Some(
+            SysRoleEntity::find()
+                .filter(SysRoleColumn::Code.eq(code))
+                .filter(granted_in_domain(domain))
+                .one(db)
+                .await
+                .map_err(AppError::from)?
+                .ok_or(ExternalLoginError::DefaultRoleNotFound)?,
+        ),
+        None => None,
+    };
+
+    let base_username = identity
+        .preferred_username
+        .clone()
+        .or_else(|| {
+            identity
+                .email
+                .as_ref()
+                .and_then(|email| email.split('@').next().map(str::to_string))
+        })
+        .filter(|username| !username.is_empty())
+        .unwrap_or_else(|| format!("{}_{}", identity.provider, identity.subject));
+    let username_taken = SysUser::find()
+        .filter(SysUserColumn::Username.eq(&base_username))
+        .one(db)
+        .await
+        .map_err(AppError::from)?
+        .is_some();
+    let username = if username_taken {
+        let suffix = Ulid::new().to_string();
+        format!("{}_{}", base_username, suffix[20..].to_lowercase())
+    } else {
+        base_username
+    };
+
+    let email = match &identity.email {
+        Some(email) => SysUser::find()
+            .filter(SysUserColumn::Email.eq(email))
+            .one(db)
+            .await
+            .map_err(AppError::from)?
+            .is_none()
+            .then(|| email.clone()),
+        None => None,
+    };
+
+    let password =
+        SecureUtil::hash_password(oauth::generate_random_token().as_bytes()).map_err(|e| {
+            AppError {
+                code: 500,
+                message: e.to_string(),
+            }
+        })?;
+
+    let now = Local::now().naive_local();
+    let user_id = Ulid::new().to_string();
+    let txn = db.begin().await.map_err(AppError::from)?;
+
+    SysUserActiveModel {
+        id: Set(user_id.clone()),
+        domain: Set(domain.to_string()),
+        nick_name: Set(identity.name.clone().unwrap_or_else(|| username.clone())),
+        username: Set(username),
+        password: Set(password),
+        built_in: Set(false),
+        email: Set(email),
+        status: Set(Status::ENABLED),
+        created_at: Set(now),
+        created_by: Set(format!("oauth:{}", identity.provider)),
+        password_changed_at: Set(Some(now)),
+        must_change_password: Set(false),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(AppError::from)?;
+
+    SysUserIdentityActiveModel {
+        id: Set(Ulid::new().to_string()),
+        user_id: Set(user_id.clone()),
+        provider: Set(identity.provider.clone()),
+        subject: Set(identity.subject.clone()),
+        email: Set(identity.email.clone()),
+        created_at: Set(now),
+        last_login_at: Set(None),
+    }
+    .insert(&txn)
+    .await
+    .map_err(AppError::from)?;
+
+    if let Some(role) = role {
+        SysUserRoleActiveModel {
+            user_id: Set(user_id.clone()),
+            role_id: Set(role.id),
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::from)?;
+    }
+
+    txn.commit().await.map_err(AppError::from)?;
+
+    project_info!(
+        "Provisioned user {} for {} identity {}",
+        user_id,
+        identity.provider,
+        identity.subject
+    );
+    Ok(user_id)
+}
+
+/// 按目录信息创建或更新本地用户，并同步目录组映射的角色，返回用户 ID
+///
+/// 目录条目以 DN 关联本地用户：首次登录时以登录名创建用户、设置随机密码并记录关联；
+/// 已关联的用户更新昵称与邮箱。登录名已被未关联的本地账号占用时拒绝登录，需管理员显式关联。
+/// 只有 `managed_roles` 中在该域获得授权的角色随目录组增删，手工分配的其他角色保持不变。
+async fn sync_directory_user(
+    db: &DatabaseConnection,
+    directory_user: &DirectoryUser,
+    domain: &str,
+    role_codes: &[String],
+    managed_roles: &[String],
+) -> Result<String, AppError> {
+    let roles = SysRoleEntity::find()
+        .filter(SysRoleColumn::Code.is_in(managed_roles.iter().cloned()))
+        .filter(granted_in_domain(domain))
+        .all(db)
+        .await
+        .map_err(AppError::from)?;
+    let granted: Vec<String> = roles
+        .iter()
+        .filter(|role| role_codes.contains(&role.code))
+        .map(|role| role.id.clone())
+        .collect();
+    if granted.len() != role_codes.len() {
+        return Err(DirectoryLoginError::MappedRoleNotFound.into());
+    }
+
+    let now = Local::now().naive_local();
+    let txn = db.begin().await.map_err(AppError::from)?;
+
+    // 只登录到已与该目录条目关联的账号，不按用户名接管本地账号
+    let provider = directory_provider(domain);
+    let linked = SysUserIdentityEntity::find()
+        .filter(SysUserIdentityColumn::Provider.eq(&provider))
+        .filter(SysUserIdentityColumn::Subject.eq(&directory_user.dn))
+        .one(&txn)
+        .await
+        .map_err(AppError::from)?;
+    let existing = match &linked {
+        Some(link) => Some(
+            SysUser::find_by_id(&link.user_id)
+                .filter(SysUserColumn::Domain.eq(domain))
+                .one(&txn)
+                .await
+                .map_err(AppError::from)?
+                .ok_or_else(|| AppError::from(UserError::UserNotFound))?,
+        ),
+        None => {
+            let taken = SysUser::find()
+                .filter(SysUserColumn::Username.eq(&directory_user.username))
+                .one(&txn)
+                .await
+                .map_err(AppError::from)?;
+            match taken {
+                Some(user) if user.domain != domain => {
+                    return Err(DirectoryLoginError::UsernameConflict.into());
+                },
+                Some(_) => return Err(DirectoryLoginError::AccountNotLinked.into()),
+                None => None,
+            }
+        },
+    };
+
+    // 邮箱已被其他用户使用时不写入
+    let email = match &directory_user.email {
+        Some(email) => {
+            let mut query = SysUser::find().filter(SysUserColumn::Email.eq(email));
+            if let Some(user) = &existing {
+                query = query.filter(SysUserColumn::Id.ne(&user.id));
+            }
+            query
+                .one(&txn)
+                .await
+                .map_err(AppError::from)?
+                .is_none()
+                .then(|| email.clone())
+        },
+        None => None,
+    };
+
+    let user_id = match existing {
+        Some(user) => {
+            let user_id = user.id.clone();
+            let mut active: SysUserActiveModel = user.into();
+            if let Some(display_name) = &directory_user.display_name {
+                active.nick_name = Set(display_name.clone());
+            }
+            if email.is_some() {
+                active.email = Set(email);
+            }
+            active.updated_at = Set(Some(now));
+            active.updated_by = Set(Some("ldap".to_string()));
+            active.update(&txn).await.map_err(AppError::from)?;
+
+            SysUserIdentityEntity::update_many()
+                .col_expr(SysUserIdentityColumn::LastLoginAt, Expr::value(now))
+                .col_expr(
+                    SysUserIdentityColumn::Email,
+                    Expr::value(directory_user.email.clone()),
+                )
+                .filter(SysUserIdentityColumn::Provider.eq(&provider))
+                .filter(SysUserIdentityColumn::Subject.eq(&directory_user.dn))
+                .exec(&txn)
+                .await
+                .map_err(AppError::from)?;
+            user_id
+        },
+        None => {
+            let password = SecureUtil::hash_password(oauth::generate_random_token().as_bytes())
+                .map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+            let user_id = Ulid::new().to_string();
+            SysUserActiveModel {
+                id: Set(user_id.clone()),
+                domain: Set(domain.to_string()),
+                username: Set(directory_user.username.clone()),
+                nick_name: Set(directory_user
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| directory_user.username.clone())),
+                password: Set(password),
+                built_in: Set(false),
+                email: Set(email),
+                status: Set(Status::ENABLED),
+                created_at: Set(now),
+                created_by: Set("ldap".to_string()),
+                password_changed_at: Set(Some(now)),
+                must_change_password: Set(false),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+            SysUserIdentityActiveModel {
+                id: Set(Ulid::new().to_string()),
+                user_id: Set(user_id.clone()),
+                provider: Set(provider.clone()),
+                subject: Set(directory_user.dn.clone()),
+                email: Set(directory_user.email.clone()),
+                created_at: Set(now),
+                last_login_at: Set(Some(now)),
+            }
+            .insert(&txn)
+            .await
+            .map_err(AppError::from)?;
+            project_info!(
+                "Provisioned user {} from directory entry {}",
+                user_id,
+                directory_user.dn
+            );
+            user_id
+        },
+    };
+
+    let assigned: Vec<String> = SysUserRoleEntity::find()
+        .filter(SysUserRoleColumn::UserId.eq(&user_id))
+        .all(&txn)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|user_role| user_role.role_id)
+        .collect();
+
+    let revoked: Vec<String> = roles
+        .iter()
+        .map(|role| role.id.clone())
+        .filter(|role_id| assigned.contains(role_id) && !granted.contains(role_id))
+        .collect();
+    if !revoked.is_empty() {
+        SysUserRoleEntity::delete_many()
+            .filter(SysUserRoleColumn::UserId.eq(&user_id))
+            .filter(SysUserRoleColumn::RoleId.is_in(revoked))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+    }
+
+    for role_id in granted.into_iter().filter(|id| !assigned.contains(id)) {
+        SysUserRoleActiveModel {
+            user_id: Set(user_id.clone()),
+            role_id: Set(role_id),
+        }
+        .insert(&txn)
+        .await
+        .map_err(AppError::from)?;
+    }
+
+    txn.commit().await.map_err(AppError::from)?;
+
+    Ok(user_id)
+}
+
+/// 目录身份在 `sys_user_identity` 中的提供方标识，按域区分
+fn directory_provider(domain: &str) -> String {
+    format!("ldap:{}", domain)
+}
+
+fn user_attempt_key(domain: &str, username: &str) -> String {
+    format!("user:{}:{}", domain, username)
+}
+
+fn ip_attempt_key(client_ip: &str) -> String {
+    format!("ip:{}", client_ip)
+}
+
+fn remaining_ttl(exp: Option<usize>) -> Duration {
+    let now = Utc::now().timestamp() as usize;
+    Duration::from_secs(exp.unwrap_or(now).saturating_sub(now) as u64)
+}
+
+/// 会话的在线时长，即刷新令牌的有效期，会话在此期间可以续期
+pub(crate) async fn session_ttl() -> TimeDelta {
+    let expire = global::get_config::<JwtConfig>()
+        .await
+        .map_or(0, |config| config.refresh_expire);
+    TimeDelta::seconds(expire)
+}
+
+/// 令牌所属会话（令牌族）首次登录的时间，与 `tokens` 一一对应
+///
+/// 刷新令牌的有效期从该时间开始计算，刷新不会延长。
+async fn session_started_at<C: ConnectionTrait>(
+    db: &C,
+    tokens: &[SysTokensModel],
+) -> Result<Vec<NaiveDateTime>, AppError> {
+    let root_ids: Vec<&String> = tokens
+        .iter()
+        .filter_map(|token| token.family_id.as_ref().filter(|id| **id != token.id))
+        .collect();
+    let roots: HashMap<String, NaiveDateTime> = if root_ids.is_empty() {
+        HashMap::new()
+    } else {
+        SysTokensEntity::find()
+            .filter(SysTokensColumn::Id.is_in(root_ids))
+            .all(db)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|root| (root.id, root.login_time))
+            .collect()
+    };
+
+    Ok(tokens
+        .iter()
+        .map(|token| {
+            token
+                .family_id
+                .as_ref()
+                .and_then(|id| roots.get(id))
+                .copied()
+                .unwrap_or(token.login_time)
+        })
+        .collect())
+}
+
+/// 吊销令牌记录
+///
+/// 将访问令牌的 jti 加入吊销列表，并将记录标记为已撤销，使刷新令牌同时失效。
+pub(crate) async fn revoke_tokens<C: ConnectionTrait>(
+    db: &C,
+    tokens: Vec<SysTokensModel>,
+) -> Result<u64, AppError> {
+    if tokens.is_empty() {
+        return Ok(0);
+    }
+
+    let store = TokenRevocationStore::current().await;
+    for token in &tokens {
+        match JwtUtils::decode_claims(&token.access_token).await {
+            Ok(claims) => {
+                if let Some(jti) = claims.jti() {
+                    store.revoke(jti, remaining_ttl(claims.exp())).await;
+                }
+            },
+            Err(e) => project_error!("Failed to decode token {}: {}", token.id, e),
+        }
+    }
+
+    let ids: Vec<String> = tokens.into_iter().map(|token| token.id).collect();
+    let result = SysTokensEntity::update_many()
+        .col_expr(
+            SysTokensColumn::Status,
+            Expr::value(TokenStatus::Revoked.to_string()),
+        )
+        .filter(SysTokensColumn::Id.is_in(ids))
+        .exec(db)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(result.rows_affected)
+}
+
+/// 吊销用户所有未撤销的令牌，返回被吊销的令牌数量
+pub(crate) async fn revoke_user_tokens<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+) -> Result<u64, AppError> {
+    let tokens = SysTokensEntity::find()
+        .filter(SysTokensColumn::UserId.eq(user_id))
+        .filter(SysTokensColumn::Status.ne(TokenStatus::Revoked.to_string()))
+        .all(db)
+        .await
+        .map_err(AppError::from)?;
+
+    revoke_tokens(db, tokens).await
 }
 
 #[instrument(skip(sender, auth_event))]
@@ -335,7 +1766,7 @@ pub async fn generate_auth_output(
     username: String,
     role_codes: Vec<String>,
     domain_code: String,
-    organization_name: Option<String>,
+    organization_code: Option<String>,
     audience: Audience,
 ) -> Result<AuthOutput, JwtError> {
     let claims = Claims::new(
@@ -344,7 +1775,7 @@ pub async fn generate_auth_output(
         username,
         role_codes,
         domain_code,
-        organization_name,
+        organization_code,
     );
 
     let token = JwtUtils::generate_token(&claims).await?;
@@ -352,6 +1783,8 @@ pub async fn generate_auth_output(
     Ok(AuthOutput {
         token,
         refresh_token: Ulid::new().to_string(),
+        password_expired: false,
+        must_change_password: false,
     })
 }
 
@@ -374,8 +1807,6 @@ async fn handle_auth_event(auth_event: &AuthEvent) -> Result<(), EventError> {
         user_id: auth_event.user_id.clone(),
         username: auth_event.username.clone(),
         domain: auth_event.domain.clone(),
-        access_token: auth_event.access_token.clone(),
-        refresh_token: auth_event.refresh_token.clone(),
         client_ip: auth_event.client_ip.clone(),
         address: auth_event.address.clone(),
         client_port: auth_event.client_port,
@@ -1,24 +1,54 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
-use axum_casbin::casbin::{CoreApi, MgmtApi, RbacApi};
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
-use server_core::web::error::AppError;
-use server_model::admin::entities::{
-    prelude::{SysDomain, SysEndpoint, SysMenu, SysRole, SysRoleMenu, SysUser, SysUserRole},
-    sys_domain::Column as SysDomainColumn,
-    sys_endpoint::Column as SysEndpointColumn,
-    sys_menu::Column as SysMenuColumn,
-    sys_role::Column as SysRoleColumn,
-    sys_role_menu::{ActiveModel as SysRoleMenuActiveModel, Column as SysRoleMenuColumn},
-    sys_user_role::{ActiveModel as SysUserRoleActiveModel, Column as SysUserRoleColumn},
+use axum_casbin::{
+    casbin::{function_map::key_match2, CachedApi, CoreApi, RbacApi},
+    evaluate_condition, with_context, RequestAttributes,
+};
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData};
+use server_model::admin::{
+    entities::{
+        casbin_rule::{Column as CasbinRuleColumn, Model as CasbinRuleModel},
+        prelude::{
+            CasbinRule, SysDomain, SysEndpoint, SysMenu, SysRole, SysRoleMenu, SysUser, SysUserRole,
+        },
+        sys_domain::Column as SysDomainColumn,
+        sys_endpoint::Column as SysEndpointColumn,
+        sys_menu::Column as SysMenuColumn,
+        sys_role::{ActiveModel as SysRoleActiveModel, Column as SysRoleColumn},
+        sys_role_menu::{ActiveModel as SysRoleMenuActiveModel, Column as SysRoleMenuColumn},
+        sys_user::Column as SysUserColumn,
+        sys_user_role::{ActiveModel as SysUserRoleActiveModel, Column as SysUserRoleColumn},
+    },
+    input::{
+        ExplainPermissionDto, PolicyFormat, PolicyPageRequest, PolicyRuleDto, PolicyType,
+        ReachableEndpointsDto, ReplacePoliciesDto,
+    },
+    output::{
+        AuthorizationBundle, BundleRole, BundleRoleMenu, BundleUserRole, PermissionExplanation,
+        PolicyChangeOutput, PolicyImportDiff, PolicyRule, ReachableEndpoint, SubjectExplanation,
+    },
 };
 use thiserror::Error;
 use tokio::sync::RwLock;
+use ulid::Ulid;
 
-use crate::helper::db_helper;
+use super::{sys_role_error::RoleError, sys_role_service::ROOT_PID};
+use crate::helper::{db_helper, policy_csv_helper};
+
+/// 内置域，其用户可以管理所有域的授权数据
+const BUILT_IN_DOMAIN: &str = "built-in";
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum AuthorizationError {
     #[error("Domain not found")]
     DomainNotFound,
@@ -30,17 +60,50 @@ pub enum AuthorizationError {
     RoutesNotFound,
     #[error("One or more users not found")]
     UsersNotFound,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Either user ID or subject is required")]
+    SubjectRequired,
+    #[error("Invalid policy content: {0}")]
+    InvalidPolicyContent(String),
+    #[error("Unknown roles: {0}")]
+    UnknownRoles(String),
+    #[error("Unknown routes: {0}")]
+    UnknownRoutes(String),
+    #[error("Unknown users in domain: {0}")]
+    UnknownUsers(String),
+    #[error("Unknown domains: {0}")]
+    UnknownDomains(String),
+    #[error("Unknown endpoints: {0}")]
+    UnknownEndpoints(String),
+    #[error("Cannot access domain: {0}")]
+    CrossDomainAccess(String),
 }
 
 impl From<AuthorizationError> for AppError {
     fn from(error: AuthorizationError) -> Self {
+        let code = match error {
+            AuthorizationError::CrossDomainAccess(_) => 403,
+            _ => 400,
+        };
         AppError {
-            code: 400,
+            code,
             message: error.to_string(),
         }
     }
 }
 
+/// 校验用户能否管理目标域的数据
+///
+/// 只有内置域的用户可以管理其他域，其余用户只能管理所在的域。
+pub fn check_domain_access(user: &User, domain: &str) -> Result<(), AppError> {
+    let user_domain = user.domain();
+    if user_domain == BUILT_IN_DOMAIN || user_domain == domain {
+        return Ok(());
+    }
+    Err(AuthorizationError::CrossDomainAccess(domain.to_string()).into())
+}
+
 #[async_trait]
 pub trait TAuthorizationService: Send + Sync {
     /// 为角色分配权限
@@ -49,7 +112,7 @@ pub trait TAuthorizationService: Send + Sync {
         domain: String,
         role_id: String,
         permissions: Vec<String>,
-        enforcer: Arc<RwLock<impl CoreApi + MgmtApi + RbacApi + Send + Sync>>,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
     ) -> Result<(), AppError>;
 
     /// 为角色分配路由
@@ -62,6 +125,74 @@ pub trait TAuthorizationService: Send + Sync {
 
     /// 为角色分配用户
     async fn assign_users(&self, role_id: String, user_ids: Vec<String>) -> Result<(), AppError>;
+
+    /// 解释角色或用户对接口的访问判定
+    async fn explain_permission(
+        &self,
+        input: ExplainPermissionDto,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<PermissionExplanation, AppError>;
+
+    /// 列出角色或用户在域内可访问的全部接口
+    async fn get_reachable_endpoints(
+        &self,
+        input: ReachableEndpointsDto,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<Vec<ReachableEndpoint>, AppError>;
+
+    /// 导出域的完整授权数据
+    async fn export_policies(
+        &self,
+        domain: String,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<AuthorizationBundle, AppError>;
+
+    /// 导入授权数据，使域内的授权状态与导入内容一致，返回变更差异
+    ///
+    /// JSON 数据包会同步角色、角色菜单、p 与 g 规则及域内用户的角色；CSV 只同步 p 与 g 规则。
+    /// 角色为全局数据，只会新增或更新，不会删除；只有内置域的用户导入时才会同步角色，
+    /// 其他用户导入的规则只能引用已存在的角色。
+    async fn import_policies(
+        &self,
+        domain: String,
+        format: PolicyFormat,
+        content: String,
+        dry_run: bool,
+        operator: &User,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<PolicyImportDiff, AppError>;
+
+    /// 分页查询策略规则，直接读取 `casbin_rule` 表，不受策略按需加载的影响
+    ///
+    /// 内置域以外的用户只能查询所在域的规则。
+    async fn find_paginated_policies(
+        &self,
+        params: PolicyPageRequest,
+        user: &User,
+    ) -> Result<PaginatedData<PolicyRule>, AppError>;
+
+    /// 新增策略规则，已存在的规则会被跳过
+    async fn add_policies(
+        &self,
+        rules: Vec<PolicyRuleDto>,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError>;
+
+    /// 删除策略规则，不存在的规则会被跳过
+    ///
+    /// 删除时不校验角色与接口是否存在，以便清理引用了已删除数据的规则。
+    async fn remove_policies(
+        &self,
+        rules: Vec<PolicyRuleDto>,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError>;
+
+    /// 将域内某类规则替换为给定的规则，指定角色时只替换该角色的规则
+    async fn replace_policies(
+        &self,
+        input: ReplacePoliciesDto,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError>;
 }
 
 #[derive(Clone)]
@@ -81,7 +212,7 @@ impl SysAuthorizationService {
             .await
             .map_err(AppError::from)?;
 
-        let domain = domain.ok_or_else(|| AuthorizationError::DomainNotFound)?;
+        let domain = domain.ok_or(AuthorizationError::DomainNotFound)?;
 
         let role = SysRole::find()
             .filter(SysRoleColumn::Id.eq(role_id))
@@ -89,7 +220,7 @@ impl SysAuthorizationService {
             .await
             .map_err(AppError::from)?;
 
-        let role = role.ok_or_else(|| AuthorizationError::RoleNotFound)?;
+        let role = role.ok_or(AuthorizationError::RoleNotFound)?;
 
         Ok((domain.code, role_id.to_string(), role.code))
     }
@@ -103,87 +234,510 @@ impl SysAuthorizationService {
             .await
             .map_err(AppError::from)?;
 
-        let role = role.ok_or_else(|| AuthorizationError::RoleNotFound)?;
+        let role = role.ok_or(AuthorizationError::RoleNotFound)?;
 
         Ok(role.code)
     }
 
+    /// 获取待判定的角色编码，提供用户 ID 时为该用户的全部角色
+    async fn resolve_subjects(
+        &self,
+        user_id: Option<String>,
+        subject: Option<String>,
+    ) -> Result<Vec<String>, AppError> {
+        let Some(user_id) = user_id else {
+            return subject
+                .map(|subject| vec![subject])
+                .ok_or_else(|| AuthorizationError::SubjectRequired.into());
+        };
+
+        let db = db_helper::get_db_connection().await?;
+        SysUser::find_by_id(&user_id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(AuthorizationError::UserNotFound)?;
+
+        let role_ids: Vec<String> = SysUserRole::find()
+            .filter(SysUserRoleColumn::UserId.eq(&user_id))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|user_role| user_role.role_id)
+            .collect();
+
+        SysRole::find()
+            .filter(SysRoleColumn::Id.is_in(role_ids))
+            .all(db.as_ref())
+            .await
+            .map(|roles| roles.into_iter().map(|role| role.code).collect())
+            .map_err(AppError::from)
+    }
+
+    async fn check_domain(&self, domain_code: &str) -> Result<String, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(domain_code))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .map(|domain| domain.code)
+            .ok_or_else(|| AuthorizationError::DomainNotFound.into())
+    }
+
+    /// 校验策略规则的格式及引用的域、角色与接口是否存在
+    async fn check_policy_rules(&self, rules: &[PolicyRuleDto]) -> Result<(), AppError> {
+        for rule in rules {
+            let invalid = match rule.ptype {
+                PolicyType::P if rule.action.is_empty() => Some("p rule requires an action"),
+                PolicyType::G if !rule.action.is_empty() || !rule.condition.is_empty() => {
+                    Some("g rule cannot have an action or condition")
+                },
+                PolicyType::G if rule.subject == rule.object => Some("role cannot inherit itself"),
+                _ => None,
+            };
+            if let Some(reason) = invalid {
+                return Err(AuthorizationError::InvalidPolicyContent(format!(
+                    "{} [{}]",
+                    reason,
+                    casbin_rule(rule).join(", ")
+                ))
+                .into());
+            }
+        }
+
+        let db = db_helper::get_db_connection().await?;
+
+        let domains: BTreeSet<&String> = rules.iter().map(|rule| &rule.domain).collect();
+        let known_domains: HashSet<String> = SysDomain::find()
+            .filter(SysDomainColumn::Code.is_in(domains.iter().copied()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|domain| domain.code)
+            .collect();
+        let unknown_domains: Vec<&String> = domains
+            .into_iter()
+            .filter(|domain| !known_domains.contains(*domain))
+            .collect();
+        if !unknown_domains.is_empty() {
+            return Err(AuthorizationError::UnknownDomains(joined(unknown_domains)).into());
+        }
+
+        let roles: BTreeSet<&String> = rules
+            .iter()
+            .flat_map(|rule| match rule.ptype {
+                PolicyType::P => vec![&rule.subject],
+                PolicyType::G => vec![&rule.subject, &rule.object],
+            })
+            .collect();
+        let known_roles: HashSet<String> = SysRole::find()
+            .filter(SysRoleColumn::Code.is_in(roles.iter().copied()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|role| role.code)
+            .collect();
+        let unknown_roles: Vec<&String> = roles
+            .into_iter()
+            .filter(|role| !known_roles.contains(*role))
+            .collect();
+        if !unknown_roles.is_empty() {
+            return Err(AuthorizationError::UnknownRoles(joined(unknown_roles)).into());
+        }
+
+        let endpoints: BTreeSet<(&String, &String)> = rules
+            .iter()
+            .filter(|rule| rule.ptype == PolicyType::P)
+            .map(|rule| (&rule.object, &rule.action))
+            .collect();
+        if endpoints.is_empty() {
+            return Ok(());
+        }
+        let known_endpoints: HashSet<(String, String)> = SysEndpoint::find()
+            .filter(SysEndpointColumn::Path.is_in(endpoints.iter().map(|(path, _)| *path)))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|endpoint| (endpoint.path, endpoint.method))
+            .collect();
+        let unknown_endpoints: Vec<String> = endpoints
+            .into_iter()
+            .filter(|(path, method)| {
+                !known_endpoints.contains(&(path.to_string(), method.to_string()))
+            })
+            .map(|(path, method)| format!("{} {}", method, path))
+            .collect();
+        if !unknown_endpoints.is_empty() {
+            return Err(AuthorizationError::UnknownEndpoints(joined(&unknown_endpoints)).into());
+        }
+
+        Ok(())
+    }
+
     /// 同步角色权限
     async fn sync_role_permissions(
         &self,
         role_code: &str,
         domain: &str,
         new_permissions: Vec<server_model::admin::entities::sys_endpoint::Model>,
-        enforcer: Arc<RwLock<impl CoreApi + MgmtApi + RbacApi + Send + Sync>>,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
     ) -> Result<(), AppError> {
         let mut enforcer_write = enforcer.write().await;
-        let existing_permissions =
+        let existing_policies =
             enforcer_write.get_filtered_policy(0, vec![role_code.to_string(), domain.to_string()]);
 
-        println!("existing_permissions: {:?}", existing_permissions);
+        // 重新分配权限时保留接口上已配置的条件
+        let conditions: HashMap<(&str, &str), &str> = existing_policies
+            .iter()
+            .filter_map(|policy| {
+                Some((
+                    (policy.get(2)?.as_str(), policy.get(3)?.as_str()),
+                    policy.get(4).map_or("", String::as_str),
+                ))
+            })
+            .collect();
 
         let new_policies: Vec<Vec<String>> = new_permissions
             .iter()
             .map(|perm| {
+                let condition = conditions
+                    .get(&(perm.path.as_str(), perm.method.as_str()))
+                    .copied()
+                    .unwrap_or_default();
                 vec![
                     role_code.to_string(),
                     domain.to_string(),
                     perm.path.clone(),
                     perm.method.clone(),
+                    condition.to_string(),
                 ]
             })
             .collect();
 
-        println!("new_policies: {:?}", new_policies);
+        let (policies_to_remove, policies_to_add) = diff_rules(existing_policies, new_policies);
+        apply_rule_changes(
+            &mut *enforcer_write,
+            RuleKind::Policy,
+            policies_to_remove,
+            policies_to_add,
+        )
+        .await
+    }
 
-        let existing_policies: Vec<Vec<String>> = existing_permissions
-            .iter()
-            .map(|perm| {
-                vec![
-                    perm[0].clone(),
-                    perm[1].clone(),
-                    perm[2].clone(),
-                    perm[3].clone(),
-                ]
-            })
-            .collect();
+    /// 域内用户的角色，同时返回用户名到用户 ID 的映射
+    async fn find_domain_user_roles(
+        &self,
+        domain: &str,
+        role_codes: &HashMap<String, String>,
+    ) -> Result<(BTreeSet<BundleUserRole>, HashMap<String, String>), AppError> {
+        let db = db_helper::get_db_connection().await?;
 
-        println!("existing_policies: {:?}", existing_policies);
+        let users: HashMap<String, String> = SysUser::find()
+            .filter(SysUserColumn::Domain.eq(domain))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|user| (user.id, user.username))
+            .collect();
 
-        let policies_to_remove: Vec<Vec<String>> = existing_policies
-            .iter()
-            .filter(|policy| !new_policies.contains(policy))
-            .cloned()
+        let user_roles = SysUserRole::find()
+            .filter(SysUserRoleColumn::UserId.is_in(users.keys().cloned()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .filter_map(|user_role| {
+                Some(BundleUserRole {
+                    username: users.get(&user_role.user_id)?.clone(),
+                    role_code: role_codes.get(&user_role.role_id)?.clone(),
+                })
+            })
             .collect();
 
-        let policies_to_add: Vec<Vec<String>> = new_policies
-            .iter()
-            .filter(|policy| !existing_policies.contains(policy))
-            .cloned()
+        let user_ids = users
+            .into_iter()
+            .map(|(id, username)| (username, id))
             .collect();
 
-        if !policies_to_remove.is_empty() {
-            let _ = enforcer_write
-                .remove_policies(policies_to_remove)
-                .await
-                .map_err(|e| AppError {
-                    code: 500,
-                    message: e.to_string(),
-                })?;
+        Ok((user_roles, user_ids))
+    }
+
+    /// 域内的角色菜单，按角色编码与路由名称表示
+    async fn find_domain_role_menus(
+        &self,
+        domain: &str,
+        role_codes: &HashMap<String, String>,
+        route_names: &HashMap<i32, String>,
+    ) -> Result<BTreeSet<BundleRoleMenu>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        SysRoleMenu::find()
+            .filter(SysRoleMenuColumn::Domain.eq(domain))
+            .all(db.as_ref())
+            .await
+            .map(|role_menus| {
+                role_menus
+                    .into_iter()
+                    .filter_map(|role_menu| {
+                        Some(BundleRoleMenu {
+                            role_code: role_codes.get(&role_menu.role_id)?.clone(),
+                            route_name: route_names.get(&role_menu.menu_id)?.clone(),
+                        })
+                    })
+                    .collect()
+            })
+            .map_err(AppError::from)
+    }
+}
+
+/// 区分 p 规则与 g 规则，二者使用不同的管理接口
+#[derive(Clone, Copy)]
+enum RuleKind {
+    Policy,
+    Grouping,
+}
+
+impl From<PolicyType> for RuleKind {
+    fn from(ptype: PolicyType) -> Self {
+        match ptype {
+            PolicyType::P => RuleKind::Policy,
+            PolicyType::G => RuleKind::Grouping,
         }
+    }
+}
 
-        if !policies_to_add.is_empty() {
-            let _ = enforcer_write
-                .add_policies(policies_to_add)
-                .await
-                .map_err(|e| AppError {
-                    code: 500,
-                    message: e.to_string(),
-                })?;
+fn policy_type_name(ptype: PolicyType) -> &'static str {
+    match ptype {
+        PolicyType::P => "p",
+        PolicyType::G => "g",
+    }
+}
+
+/// 策略规则在 Casbin 中的表示，p 规则为角色、域、路径、方法、条件，g 规则为角色、上级角色、域
+fn casbin_rule(rule: &PolicyRuleDto) -> Vec<String> {
+    match rule.ptype {
+        PolicyType::P => vec![
+            rule.subject.clone(),
+            rule.domain.clone(),
+            rule.object.clone(),
+            rule.action.clone(),
+            rule.condition.clone(),
+        ],
+        PolicyType::G => vec![
+            rule.subject.clone(),
+            rule.object.clone(),
+            rule.domain.clone(),
+        ],
+    }
+}
+
+fn policy_rule_dto(ptype: PolicyType, rule: Vec<String>) -> PolicyRuleDto {
+    let mut values = rule.into_iter();
+    let mut next = || values.next().unwrap_or_default();
+    let (subject, domain, object, action, condition) = match ptype {
+        PolicyType::P => (next(), next(), next(), next(), next()),
+        PolicyType::G => {
+            let (subject, object, domain) = (next(), next(), next());
+            (subject, domain, object, String::new(), String::new())
+        },
+    };
+    PolicyRuleDto {
+        ptype,
+        subject,
+        domain,
+        object,
+        action,
+        condition,
+    }
+}
+
+fn policy_rule(model: CasbinRuleModel) -> Option<PolicyRule> {
+    let ptype = match model.ptype.as_str() {
+        "p" => PolicyType::P,
+        "g" => PolicyType::G,
+        _ => return None,
+    };
+    let rule = policy_rule_dto(
+        ptype,
+        [model.v0, model.v1, model.v2, model.v3, model.v4]
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect(),
+    );
+    Some(PolicyRule {
+        id: model.id,
+        ptype,
+        subject: rule.subject,
+        domain: rule.domain,
+        object: rule.object,
+        action: rule.action,
+        condition: rule.condition,
+    })
+}
+
+/// 按规则类型筛选策略，g 规则没有请求方法，按方法筛选时不包含 g 规则
+fn policy_filter(ptype: PolicyType, params: &PolicyPageRequest) -> Option<Condition> {
+    let (domain_column, object_column) = match ptype {
+        PolicyType::P => (CasbinRuleColumn::V1, CasbinRuleColumn::V2),
+        PolicyType::G => (CasbinRuleColumn::V2, CasbinRuleColumn::V1),
+    };
+    let mut condition = Condition::all().add(CasbinRuleColumn::Ptype.eq(policy_type_name(ptype)));
+
+    if let Some(ref subject) = params.subject {
+        condition = condition.add(CasbinRuleColumn::V0.eq(subject));
+    }
+    if let Some(ref domain) = params.domain {
+        condition = condition.add(domain_column.eq(domain));
+    }
+    if let Some(ref object) = params.object {
+        condition = condition.add(object_column.eq(object));
+    }
+    if let Some(ref action) = params.action {
+        if ptype == PolicyType::G {
+            return None;
         }
+        condition = condition.add(CasbinRuleColumn::V3.eq(action));
+    }
 
-        Ok(())
+    Some(condition)
+}
+
+/// 筛选出执行器中存在或不存在的规则，同时去除重复的规则
+fn filter_rules(
+    enforcer: &impl RbacApi,
+    rules: Vec<PolicyRuleDto>,
+    existing: bool,
+) -> Vec<PolicyRuleDto> {
+    let mut seen = HashSet::new();
+    rules
+        .into_iter()
+        .filter(|rule| {
+            let values = casbin_rule(rule);
+            let exists = match rule.ptype {
+                PolicyType::P => enforcer.has_policy(values.clone()),
+                PolicyType::G => enforcer.has_grouping_policy(values.clone()),
+            };
+            exists == existing && seen.insert((rule.ptype, values))
+        })
+        .collect()
+}
+
+/// 按规则类型拆分为 p 规则与 g 规则
+fn partition_rules(rules: &[PolicyRuleDto]) -> (Vec<Vec<String>>, Vec<Vec<String>>) {
+    let (policies, groupings): (Vec<_>, Vec<_>) =
+        rules.iter().partition(|rule| rule.ptype == PolicyType::P);
+    (
+        policies.into_iter().map(casbin_rule).collect(),
+        groupings.into_iter().map(casbin_rule).collect(),
+    )
+}
+
+/// 计算从现有规则到目标规则需要删除与新增的规则
+fn diff_rules(
+    existing: Vec<Vec<String>>,
+    desired: Vec<Vec<String>>,
+) -> (Vec<Vec<String>>, Vec<Vec<String>>) {
+    let existing: BTreeSet<Vec<String>> = existing.into_iter().collect();
+    let desired: BTreeSet<Vec<String>> = desired.into_iter().collect();
+
+    (
+        existing.difference(&desired).cloned().collect(),
+        desired.difference(&existing).cloned().collect(),
+    )
+}
+
+async fn apply_rule_changes(
+    enforcer: &mut (impl CoreApi + RbacApi),
+    kind: RuleKind,
+    to_remove: Vec<Vec<String>>,
+    to_add: Vec<Vec<String>>,
+) -> Result<(), AppError> {
+    let casbin_error = |e: axum_casbin::casbin::Error| AppError {
+        code: 500,
+        message: e.to_string(),
+    };
+
+    if !to_remove.is_empty() {
+        match kind {
+            RuleKind::Policy => enforcer.remove_policies(to_remove).await,
+            RuleKind::Grouping => enforcer.remove_grouping_policies(to_remove).await,
+        }
+        .map_err(casbin_error)?;
     }
+
+    if !to_add.is_empty() {
+        match kind {
+            RuleKind::Policy => enforcer.add_policies(to_add).await,
+            RuleKind::Grouping => enforcer.add_grouping_policies(to_add).await,
+        }
+        .map_err(casbin_error)?;
+    }
+
+    Ok(())
+}
+
+/// 计算撤销导入需要删除与新增的规则，按当前规则计算以应对只应用了部分变更的情况
+fn restore_rules(
+    current: Vec<Vec<String>>,
+    removed: &[Vec<String>],
+    added: &[Vec<String>],
+) -> (Vec<Vec<String>>, Vec<Vec<String>>) {
+    let original = current
+        .iter()
+        .filter(|rule| !added.contains(rule))
+        .chain(removed)
+        .cloned()
+        .collect();
+    diff_rules(current, original)
+}
+
+/// 判定策略条件使用的请求属性，未指定时间时使用当前时间
+fn condition_attributes(ip: Option<String>, time: Option<String>) -> RequestAttributes {
+    RequestAttributes {
+        ip,
+        time: time.or_else(|| Some(Local::now().format("%Y-%m-%dT%H:%M").to_string())),
+        ..Default::default()
+    }
+}
+
+/// p 规则的列数，依次为角色、域、资源、操作、条件
+const POLICY_FIELDS: usize = 5;
+
+/// 将规则的域替换为目标域，规则列数不足时视为无效内容
+fn retarget_rules(
+    rules: Vec<Vec<String>>,
+    domain_index: usize,
+    domain: &str,
+) -> Result<Vec<Vec<String>>, AppError> {
+    rules
+        .into_iter()
+        .map(|mut rule| match rule.get_mut(domain_index) {
+            Some(value) => {
+                *value = domain.to_string();
+                Ok(rule)
+            },
+            None => Err(AuthorizationError::InvalidPolicyContent(format!(
+                "rule [{}] has no domain column",
+                rule.join(", ")
+            ))
+            .into()),
+        })
+        .collect()
+}
+
+fn joined<'a>(values: impl IntoIterator<Item = &'a String>) -> String {
+    values
+        .into_iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 #[async_trait]
@@ -193,7 +747,7 @@ impl TAuthorizationService for SysAuthorizationService {
         domain: String,
         role_id: String,
         permissions: Vec<String>,
-        enforcer: Arc<RwLock<impl CoreApi + MgmtApi + RbacApi + Send + Sync>>,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
     ) -> Result<(), AppError> {
         let (domain_code, _, role_code) = self.check_domain_and_role(&domain, &role_id).await?;
 
@@ -266,7 +820,6 @@ impl TAuthorizationService for SysAuthorizationService {
                     role_id: sea_orm::Set(role_id.clone()),
                     menu_id: sea_orm::Set(*route_id),
                     domain: sea_orm::Set(domain_code.clone()),
-                    ..Default::default()
                 })
                 .collect();
 
@@ -339,7 +892,6 @@ impl TAuthorizationService for SysAuthorizationService {
                 .map(|user_id| SysUserRoleActiveModel {
                     role_id: sea_orm::Set(role_id.clone()),
                     user_id: sea_orm::Set(user_id.clone()),
-                    ..Default::default()
                 })
                 .collect();
 
@@ -365,4 +917,720 @@ impl TAuthorizationService for SysAuthorizationService {
 
         Ok(())
     }
+
+    async fn explain_permission(
+        &self,
+        input: ExplainPermissionDto,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<PermissionExplanation, AppError> {
+        let ExplainPermissionDto {
+            user_id,
+            subject,
+            domain,
+            path,
+            method,
+            ip,
+            time,
+        } = input;
+        let attributes = condition_attributes(ip, time);
+        let subjects = self.resolve_subjects(user_id, subject).await?;
+
+        let enforcer_read = enforcer.read().await;
+        let mut explanations = Vec::with_capacity(subjects.len());
+        for subject in subjects {
+            let allowed = enforcer_read
+                .enforce(with_context(
+                    &*enforcer_read,
+                    vec![
+                        subject.clone(),
+                        domain.clone(),
+                        path.clone(),
+                        method.clone(),
+                    ],
+                    &attributes,
+                ))
+                .map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+
+            let mut inherited_roles =
+                enforcer_read.get_implicit_roles_for_user(&subject, Some(&domain));
+            inherited_roles.sort();
+
+            // 按模型匹配器 `g(r.sub, p.sub, r.dom) && r.dom == p.dom && keyMatch2(r.obj, p.obj)
+            // && r.act == p.act` 及策略条件找出角色自身及其上级角色命中的策略
+            let matched_policies: Vec<Vec<String>> = std::iter::once(&subject)
+                .chain(inherited_roles.iter())
+                .flat_map(|role| {
+                    enforcer_read.get_filtered_policy(0, vec![role.clone(), domain.clone()])
+                })
+                .filter(|policy| {
+                    policy.get(2).is_some_and(|obj| key_match2(&path, obj))
+                        && policy.get(3).is_some_and(|act| act == &method)
+                        && evaluate_condition(policy.get(4).map_or("", String::as_str), &attributes)
+                })
+                .collect();
+
+            explanations.push(SubjectExplanation {
+                subject,
+                allowed,
+                matched_policies,
+                inherited_roles,
+            });
+        }
+
+        Ok(PermissionExplanation {
+            allowed: explanations.iter().any(|explanation| explanation.allowed),
+            domain,
+            path,
+            method,
+            subjects: explanations,
+        })
+    }
+
+    async fn get_reachable_endpoints(
+        &self,
+        input: ReachableEndpointsDto,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<Vec<ReachableEndpoint>, AppError> {
+        let ReachableEndpointsDto {
+            user_id,
+            subject,
+            domain,
+            ip,
+            time,
+        } = input;
+        let attributes = condition_attributes(ip, time);
+        let subjects = self.resolve_subjects(user_id, subject).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let endpoints = SysEndpoint::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let enforcer_read = enforcer.read().await;
+        let mut reachable = Vec::new();
+        for endpoint in endpoints {
+            for subject in &subjects {
+                let allowed = enforcer_read
+                    .enforce(with_context(
+                        &*enforcer_read,
+                        vec![
+                            subject.clone(),
+                            domain.clone(),
+                            endpoint.path.clone(),
+                            endpoint.method.clone(),
+                        ],
+                        &attributes,
+                    ))
+                    .map_err(|e| AppError {
+                        code: 500,
+                        message: e.to_string(),
+                    })?;
+                if allowed {
+                    reachable.push(ReachableEndpoint {
+                        endpoint,
+                        granted_by: subject.clone(),
+                    });
+                    break;
+                }
+            }
+        }
+
+        Ok(reachable)
+    }
+
+    async fn export_policies(
+        &self,
+        domain: String,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<AuthorizationBundle, AppError> {
+        let domain = self.check_domain(&domain).await?;
+        let db = db_helper::get_db_connection().await?;
+
+        let (mut policies, mut groupings) = {
+            let enforcer_read = enforcer.read().await;
+            (
+                enforcer_read.get_filtered_policy(1, vec![domain.clone()]),
+                enforcer_read.get_filtered_grouping_policy(2, vec![domain.clone()]),
+            )
+        };
+        policies.sort();
+        groupings.sort();
+
+        let roles = SysRole::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        let role_codes: HashMap<String, String> = roles
+            .iter()
+            .map(|role| (role.id.clone(), role.code.clone()))
+            .collect();
+        let route_names: HashMap<i32, String> = SysMenu::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|menu| (menu.id, menu.route_name))
+            .collect();
+
+        let role_menus = self
+            .find_domain_role_menus(&domain, &role_codes, &route_names)
+            .await?;
+        let (user_roles, _) = self.find_domain_user_roles(&domain, &role_codes).await?;
+
+        // 导出引用到的角色及其全部上级角色，保证导入时上级角色存在
+        let mut referenced: HashSet<&str> = policies
+            .iter()
+            .filter_map(|rule| rule.first())
+            .chain(groupings.iter().flat_map(|rule| rule.iter().take(2)))
+            .chain(role_menus.iter().map(|role_menu| &role_menu.role_code))
+            .chain(user_roles.iter().map(|user_role| &user_role.role_code))
+            .map(String::as_str)
+            .collect();
+        let roles_by_code: HashMap<&str, _> = roles
+            .iter()
+            .map(|role| (role.code.as_str(), role))
+            .collect();
+        let mut pending: Vec<&str> = referenced.iter().copied().collect();
+        while let Some(code) = pending.pop() {
+            if let Some(parent) = roles_by_code
+                .get(code)
+                .and_then(|role| role_codes.get(&role.pid))
+            {
+                if referenced.insert(parent.as_str()) {
+                    pending.push(parent.as_str());
+                }
+            }
+        }
+
+        let mut bundle_roles: Vec<BundleRole> = roles
+            .iter()
+            .filter(|role| referenced.contains(role.code.as_str()))
+            .map(|role| BundleRole {
+                code: role.code.clone(),
+                name: role.name.clone(),
+                description: role.description.clone(),
+                parent_code: role_codes.get(&role.pid).cloned(),
+                status: role.status.clone(),
+                data_scope: role.data_scope,
+            })
+            .collect();
+        bundle_roles.sort_by(|a, b| a.code.cmp(&b.code));
+
+        Ok(AuthorizationBundle {
+            domain,
+            roles: bundle_roles,
+            role_menus: role_menus.into_iter().collect(),
+            policies,
+            groupings,
+            user_roles: user_roles.into_iter().collect(),
+        })
+    }
+
+    async fn import_policies(
+        &self,
+        domain: String,
+        format: PolicyFormat,
+        content: String,
+        dry_run: bool,
+        operator: &User,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<PolicyImportDiff, AppError> {
+        let domain = self.check_domain(&domain).await?;
+        let bundle = match format {
+            PolicyFormat::Json => serde_json::from_str::<AuthorizationBundle>(&content)
+                .map_err(|e| AuthorizationError::InvalidPolicyContent(e.to_string()))?,
+            PolicyFormat::Csv => policy_csv_helper::from_csv(&content)
+                .map_err(AuthorizationError::InvalidPolicyContent)?,
+        };
+        // CSV 只包含 p 与 g 规则，角色、角色菜单与用户角色保持不变
+        let full = format == PolicyFormat::Json;
+        let policies: Vec<Vec<String>> = retarget_rules(bundle.policies, 1, &domain)?
+            .into_iter()
+            .map(|mut rule| {
+                // 不含条件列的策略视为无条件
+                if rule.len() < POLICY_FIELDS {
+                    rule.resize(POLICY_FIELDS, String::new());
+                }
+                rule
+            })
+            .collect();
+        let groupings = retarget_rules(bundle.groupings, 2, &domain)?;
+
+        let db = db_helper::get_db_connection().await?;
+        let mut diff = PolicyImportDiff {
+            dry_run,
+            ..Default::default()
+        };
+
+        // 角色：按编码新增或更新
+        let roles = SysRole::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        let mut role_ids: HashMap<String, String> = roles
+            .iter()
+            .map(|role| (role.code.clone(), role.id.clone()))
+            .collect();
+        // 角色为所有域共用，只有内置域的用户可以新增或更新
+        let manage_roles = full && operator.domain() == BUILT_IN_DOMAIN;
+        let bundle_roles = if manage_roles {
+            bundle.roles
+        } else {
+            Vec::new()
+        };
+        let mut new_roles = Vec::new();
+        for role in &bundle_roles {
+            if !role_ids.contains_key(&role.code) {
+                role_ids.insert(role.code.clone(), Ulid::new().to_string());
+                new_roles.push(role);
+            }
+        }
+
+        let mut unknown_roles: BTreeSet<&String> = bundle_roles
+            .iter()
+            .filter_map(|role| role.parent_code.as_ref())
+            .chain(policies.iter().filter_map(|rule| rule.first()))
+            .chain(groupings.iter().flat_map(|rule| rule.iter().take(2)))
+            .filter(|code| !role_ids.contains_key(*code))
+            .collect();
+        if full {
+            unknown_roles.extend(
+                bundle
+                    .role_menus
+                    .iter()
+                    .map(|role_menu| &role_menu.role_code)
+                    .chain(
+                        bundle
+                            .user_roles
+                            .iter()
+                            .map(|user_role| &user_role.role_code),
+                    )
+                    .filter(|code| !role_ids.contains_key(*code)),
+            );
+        }
+        if !unknown_roles.is_empty() {
+            return Err(AuthorizationError::UnknownRoles(joined(unknown_roles)).into());
+        }
+
+        let parent_id = |role: &BundleRole| {
+            role.parent_code
+                .as_ref()
+                .and_then(|code| role_ids.get(code))
+                .cloned()
+                .unwrap_or_else(|| ROOT_PID.to_string())
+        };
+
+        // 上级关系不能形成环
+        let mut parents: HashMap<String, String> = roles
+            .iter()
+            .map(|role| (role.id.clone(), role.pid.clone()))
+            .collect();
+        for role in &bundle_roles {
+            parents.insert(role_ids[&role.code].clone(), parent_id(role));
+        }
+        for role in &bundle_roles {
+            let mut visited = HashSet::new();
+            let mut current = role_ids[&role.code].as_str();
+            while let Some(parent) = parents.get(current) {
+                if !visited.insert(current) {
+                    return Err(RoleError::CyclicParent.into());
+                }
+                current = parent.as_str();
+            }
+        }
+
+        let roles_by_code: HashMap<&str, _> = roles
+            .iter()
+            .map(|role| (role.code.as_str(), role))
+            .collect();
+        let updated_roles: Vec<&BundleRole> = bundle_roles
+            .iter()
+            .filter(|role| {
+                roles_by_code
+                    .get(role.code.as_str())
+                    .is_some_and(|existing| {
+                        existing.name != role.name
+                            || existing.description != role.description
+                            || existing.status != role.status
+                            || existing.data_scope != role.data_scope
+                            || existing.pid != parent_id(role)
+                    })
+            })
+            .collect();
+        diff.roles_created = new_roles.iter().map(|role| role.code.clone()).collect();
+        diff.roles_updated = updated_roles.iter().map(|role| role.code.clone()).collect();
+
+        let role_codes: HashMap<String, String> = role_ids
+            .iter()
+            .map(|(code, id)| (id.clone(), code.clone()))
+            .collect();
+
+        // 角色菜单与用户角色
+        let mut menu_ids: HashMap<String, i32> = HashMap::new();
+        let mut user_ids: HashMap<String, String> = HashMap::new();
+        if full {
+            let menus = SysMenu::find()
+                .all(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            let route_names: HashMap<i32, String> = menus
+                .iter()
+                .map(|menu| (menu.id, menu.route_name.clone()))
+                .collect();
+            menu_ids = menus
+                .into_iter()
+                .map(|menu| (menu.route_name, menu.id))
+                .collect();
+
+            let unknown_routes: BTreeSet<&String> = bundle
+                .role_menus
+                .iter()
+                .map(|role_menu| &role_menu.route_name)
+                .filter(|name| !menu_ids.contains_key(*name))
+                .collect();
+            if !unknown_routes.is_empty() {
+                return Err(AuthorizationError::UnknownRoutes(joined(unknown_routes)).into());
+            }
+
+            let existing = self
+                .find_domain_role_menus(&domain, &role_codes, &route_names)
+                .await?;
+            let desired: BTreeSet<BundleRoleMenu> = bundle.role_menus.into_iter().collect();
+            diff.role_menus_added = desired.difference(&existing).cloned().collect();
+            diff.role_menus_removed = existing.difference(&desired).cloned().collect();
+
+            let (existing, domain_users) =
+                self.find_domain_user_roles(&domain, &role_codes).await?;
+            user_ids = domain_users;
+            let unknown_users: BTreeSet<&String> = bundle
+                .user_roles
+                .iter()
+                .map(|user_role| &user_role.username)
+                .filter(|username| !user_ids.contains_key(*username))
+                .collect();
+            if !unknown_users.is_empty() {
+                return Err(AuthorizationError::UnknownUsers(joined(unknown_users)).into());
+            }
+
+            let desired: BTreeSet<BundleUserRole> = bundle.user_roles.into_iter().collect();
+            diff.user_roles_added = desired.difference(&existing).cloned().collect();
+            diff.user_roles_removed = existing.difference(&desired).cloned().collect();
+        }
+
+        // p 与 g 规则
+        let mut enforcer_write = enforcer.write().await;
+        (diff.policies_removed, diff.policies_added) = diff_rules(
+            enforcer_write.get_filtered_policy(1, vec![domain.clone()]),
+            policies,
+        );
+        (diff.groupings_removed, diff.groupings_added) = diff_rules(
+            enforcer_write.get_filtered_grouping_policy(2, vec![domain.clone()]),
+            groupings,
+        );
+
+        if dry_run {
+            return Ok(diff);
+        }
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+        let now = Local::now().naive_local();
+
+        for role in &new_roles {
+            SysRoleActiveModel {
+                id: Set(role_ids[&role.code].clone()),
+                pid: Set(parent_id(role)),
+                code: Set(role.code.clone()),
+                name: Set(role.name.clone()),
+                description: Set(role.description.clone()),
+                status: Set(role.status.clone()),
+                data_scope: Set(role.data_scope),
+                created_at: Set(now),
+                created_by: Set(operator.user_id()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        for role in &updated_roles {
+            SysRoleActiveModel {
+                id: Set(role_ids[&role.code].clone()),
+                pid: Set(parent_id(role)),
+                name: Set(role.name.clone()),
+                description: Set(role.description.clone()),
+                status: Set(role.status.clone()),
+                data_scope: Set(role.data_scope),
+                updated_at: Set(Some(now)),
+                updated_by: Set(Some(operator.user_id())),
+                ..Default::default()
+            }
+            .update(&txn)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        for role_menu in &diff.role_menus_removed {
+            SysRoleMenu::delete_many()
+                .filter(
+                    SysRoleMenuColumn::RoleId
+                        .eq(&role_ids[&role_menu.role_code])
+                        .and(SysRoleMenuColumn::MenuId.eq(menu_ids[&role_menu.route_name]))
+                        .and(SysRoleMenuColumn::Domain.eq(&domain)),
+                )
+                .exec(&txn)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        if !diff.role_menus_added.is_empty() {
+            SysRoleMenu::insert_many(diff.role_menus_added.iter().map(|role_menu| {
+                SysRoleMenuActiveModel {
+                    role_id: Set(role_ids[&role_menu.role_code].clone()),
+                    menu_id: Set(menu_ids[&role_menu.route_name]),
+                    domain: Set(domain.clone()),
+                }
+            }))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        for user_role in &diff.user_roles_removed {
+            SysUserRole::delete_many()
+                .filter(
+                    SysUserRoleColumn::UserId
+                        .eq(&user_ids[&user_role.username])
+                        .and(SysUserRoleColumn::RoleId.eq(&role_ids[&user_role.role_code])),
+                )
+                .exec(&txn)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        if !diff.user_roles_added.is_empty() {
+            SysUserRole::insert_many(diff.user_roles_added.iter().map(|user_role| {
+                SysUserRoleActiveModel {
+                    user_id: Set(user_ids[&user_role.username].clone()),
+                    role_id: Set(role_ids[&user_role.role_code].clone()),
+                }
+            }))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        }
+
+        // 策略经执行器写入，失败时回滚数据库事务并撤销已应用的规则
+        let applied = async {
+            apply_rule_changes(
+                &mut *enforcer_write,
+                RuleKind::Policy,
+                diff.policies_removed.clone(),
+                diff.policies_added.clone(),
+            )
+            .await?;
+            apply_rule_changes(
+                &mut *enforcer_write,
+                RuleKind::Grouping,
+                diff.groupings_removed.clone(),
+                diff.groupings_added.clone(),
+            )
+            .await
+        }
+        .await;
+
+        let committed = match applied {
+            Ok(()) => txn.commit().await.map_err(AppError::from),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = committed {
+            let (to_remove, to_add) = restore_rules(
+                enforcer_write.get_filtered_policy(1, vec![domain.clone()]),
+                &diff.policies_removed,
+                &diff.policies_added,
+            );
+            let _ =
+                apply_rule_changes(&mut *enforcer_write, RuleKind::Policy, to_remove, to_add).await;
+            let (to_remove, to_add) = restore_rules(
+                enforcer_write.get_filtered_grouping_policy(2, vec![domain.clone()]),
+                &diff.groupings_removed,
+                &diff.groupings_added,
+            );
+            let _ = apply_rule_changes(&mut *enforcer_write, RuleKind::Grouping, to_remove, to_add)
+                .await;
+            return Err(e);
+        }
+
+        Ok(diff)
+    }
+
+    async fn find_paginated_policies(
+        &self,
+        mut params: PolicyPageRequest,
+        user: &User,
+    ) -> Result<PaginatedData<PolicyRule>, AppError> {
+        if user.domain() != BUILT_IN_DOMAIN {
+            params.domain = Some(user.domain());
+        }
+
+        let ptypes = match params.ptype {
+            Some(ptype) => vec![ptype],
+            None => vec![PolicyType::P, PolicyType::G],
+        };
+        let filters: Vec<Condition> = ptypes
+            .into_iter()
+            .filter_map(|ptype| policy_filter(ptype, &params))
+            .collect();
+        if filters.is_empty() {
+            return Ok(PaginatedData {
+                current: params.page_details.current,
+                size: params.page_details.size,
+                total: 0,
+                records: Vec::new(),
+            });
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let query = CasbinRule::find()
+            .filter(
+                filters
+                    .into_iter()
+                    .fold(Condition::any(), |condition, filter| condition.add(filter)),
+            )
+            .order_by_desc(CasbinRuleColumn::Ptype)
+            .order_by_asc(CasbinRuleColumn::Id);
+
+        let total = query
+            .clone()
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let paginator = query.paginate(db.as_ref(), params.page_details.size);
+        let records = paginator
+            .fetch_page(params.page_details.current - 1)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .filter_map(policy_rule)
+            .collect();
+
+        Ok(PaginatedData {
+            current: params.page_details.current,
+            size: params.page_details.size,
+            total,
+            records,
+        })
+    }
+
+    async fn add_policies(
+        &self,
+        rules: Vec<PolicyRuleDto>,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError> {
+        self.check_policy_rules(&rules).await?;
+
+        let mut enforcer_write = enforcer.write().await;
+        // 批量新增时只要有一条规则已存在就会整体失败，因此先排除已存在的规则
+        let added = filter_rules(&*enforcer_write, rules, false);
+        let (policies, groupings) = partition_rules(&added);
+
+        apply_rule_changes(&mut *enforcer_write, RuleKind::Policy, Vec::new(), policies).await?;
+        apply_rule_changes(
+            &mut *enforcer_write,
+            RuleKind::Grouping,
+            Vec::new(),
+            groupings,
+        )
+        .await?;
+        enforcer_write.get_mut_cache().clear();
+
+        Ok(PolicyChangeOutput {
+            added,
+            removed: Vec::new(),
+        })
+    }
+
+    async fn remove_policies(
+        &self,
+        rules: Vec<PolicyRuleDto>,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError> {
+        let mut enforcer_write = enforcer.write().await;
+        let removed = filter_rules(&*enforcer_write, rules, true);
+        let (policies, groupings) = partition_rules(&removed);
+
+        apply_rule_changes(&mut *enforcer_write, RuleKind::Policy, policies, Vec::new()).await?;
+        apply_rule_changes(
+            &mut *enforcer_write,
+            RuleKind::Grouping,
+            groupings,
+            Vec::new(),
+        )
+        .await?;
+        enforcer_write.get_mut_cache().clear();
+
+        Ok(PolicyChangeOutput {
+            added: Vec::new(),
+            removed,
+        })
+    }
+
+    async fn replace_policies(
+        &self,
+        input: ReplacePoliciesDto,
+        enforcer: Arc<RwLock<impl RbacApi + CachedApi<u64, bool>>>,
+    ) -> Result<PolicyChangeOutput, AppError> {
+        let outside_scope = input.rules.iter().find(|rule| {
+            rule.ptype != input.ptype
+                || rule.domain != input.domain
+                || input
+                    .subject
+                    .as_ref()
+                    .is_some_and(|subject| subject != &rule.subject)
+        });
+        if let Some(rule) = outside_scope {
+            return Err(AuthorizationError::InvalidPolicyContent(format!(
+                "rule [{}] is outside the replaced scope",
+                casbin_rule(rule).join(", ")
+            ))
+            .into());
+        }
+        self.check_policy_rules(&input.rules).await?;
+
+        let subject = input.subject.unwrap_or_default();
+        let mut enforcer_write = enforcer.write().await;
+        // 空字符串在筛选时匹配任意值
+        let existing = match input.ptype {
+            PolicyType::P => enforcer_write.get_filtered_policy(0, vec![subject, input.domain]),
+            PolicyType::G => enforcer_write
+                .get_filtered_grouping_policy(0, vec![subject, String::new(), input.domain]),
+        };
+        let desired = input.rules.iter().map(casbin_rule).collect();
+        let (to_remove, to_add) = diff_rules(existing, desired);
+
+        apply_rule_changes(
+            &mut *enforcer_write,
+            input.ptype.into(),
+            to_remove.clone(),
+            to_add.clone(),
+        )
+        .await?;
+        enforcer_write.get_mut_cache().clear();
+
+        let to_dto = |rules: Vec<Vec<String>>| {
+            rules
+                .into_iter()
+                .map(|rule| policy_rule_dto(input.ptype, rule))
+                .collect()
+        };
+        Ok(PolicyChangeOutput {
+            added: to_dto(to_add),
+            removed: to_dto(to_remove),
+        })
+    }
 }
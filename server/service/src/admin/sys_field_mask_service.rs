@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, Set,
+};
+use server_core::web::{
+    auth::User,
+    error::AppError,
+    field_mask::{self, FieldMaskRule, FieldMaskRuleLoader},
+    page::PaginatedData,
+};
+use server_model::admin::{
+    entities::{
+        prelude::{SysDomain, SysFieldMask, SysRole},
+        sea_orm_active_enums::MaskStrategy,
+        sys_domain::Column as SysDomainColumn,
+        sys_field_mask::{
+            ActiveModel as SysFieldMaskActiveModel, Column as SysFieldMaskColumn,
+            Model as SysFieldMaskModel,
+        },
+        sys_role::Column as SysRoleColumn,
+    },
+    input::{CreateFieldMaskInput, FieldMaskPageRequest, UpdateFieldMaskInput},
+};
+use ulid::Ulid;
+
+use crate::{admin::sys_field_mask_error::FieldMaskError, helper::db_helper};
+
+#[async_trait]
+pub trait TFieldMaskService {
+    async fn find_paginated_field_masks(
+        &self,
+        params: FieldMaskPageRequest,
+    ) -> Result<PaginatedData<SysFieldMaskModel>, AppError>;
+
+    async fn create_field_mask(
+        &self,
+        input: CreateFieldMaskInput,
+        user: User,
+    ) -> Result<SysFieldMaskModel, AppError>;
+    async fn get_field_mask(&self, id: &str) -> Result<SysFieldMaskModel, AppError>;
+    async fn update_field_mask(
+        &self,
+        input: UpdateFieldMaskInput,
+        user: User,
+    ) -> Result<SysFieldMaskModel, AppError>;
+    async fn delete_field_mask(&self, id: &str) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct SysFieldMaskService;
+
+impl SysFieldMaskService {
+    async fn check_field_mask(
+        &self,
+        id: Option<&str>,
+        input: &CreateFieldMaskInput,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let domain_exists = SysDomain::find()
+            .filter(SysDomainColumn::Code.eq(&input.domain))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .is_some();
+        if !domain_exists {
+            return Err(FieldMaskError::DomainNotFound.into());
+        }
+
+        let role_exists = SysRole::find()
+            .filter(SysRoleColumn::Code.eq(&input.role_code))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .is_some();
+        if !role_exists {
+            return Err(FieldMaskError::RoleNotFound.into());
+        }
+
+        let duplicate = SysFieldMask::find()
+            .filter(SysFieldMaskColumn::Domain.eq(&input.domain))
+            .filter(SysFieldMaskColumn::RoleCode.eq(&input.role_code))
+            .filter(SysFieldMaskColumn::Resource.eq(input.resource.trim()))
+            .filter(SysFieldMaskColumn::Field.eq(&input.field))
+            .filter(SysFieldMaskColumn::Id.ne(id.unwrap_or("-1")))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .is_some();
+        if duplicate {
+            return Err(FieldMaskError::DuplicateRule.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TFieldMaskService for SysFieldMaskService {
+    async fn find_paginated_field_masks(
+        &self,
+        params: FieldMaskPageRequest,
+    ) -> Result<PaginatedData<SysFieldMaskModel>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let mut query = SysFieldMask::find();
+
+        if let Some(ref domain) = params.domain {
+            query = query.filter(SysFieldMaskColumn::Domain.eq(domain));
+        }
+
+        if let Some(ref keywords) = params.keywords {
+            let condition = Condition::any()
+                .add(SysFieldMaskColumn::RoleCode.contains(keywords))
+                .add(SysFieldMaskColumn::Field.contains(keywords))
+                .add(SysFieldMaskColumn::Resource.contains(keywords));
+            query = query.filter(condition);
+        }
+
+        let total = query
+            .clone()
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let paginator = query.paginate(db.as_ref(), params.page_details.size);
+        let records = paginator
+            .fetch_page(params.page_details.current - 1)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(PaginatedData {
+            current: params.page_details.current,
+            size: params.page_details.size,
+            total,
+            records,
+        })
+    }
+
+    async fn create_field_mask(
+        &self,
+        input: CreateFieldMaskInput,
+        user: User,
+    ) -> Result<SysFieldMaskModel, AppError> {
+        self.check_field_mask(None, &input).await?;
+
+        let db = db_helper::get_db_connection().await?;
+
+        let field_mask = SysFieldMaskActiveModel {
+            id: Set(Ulid::new().to_string()),
+            domain: Set(input.domain),
+            role_code: Set(input.role_code),
+            resource: Set(input.resource.trim().to_string()),
+            field: Set(input.field),
+            strategy: Set(input.strategy),
+            description: Set(input.description),
+            created_at: Set(Local::now().naive_local()),
+            created_by: Set(user.user_id()),
+            ..Default::default()
+        };
+
+        let result = field_mask
+            .insert(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        field_mask::invalidate_field_masks(&result.domain);
+        Ok(result)
+    }
+
+    async fn get_field_mask(&self, id: &str) -> Result<SysFieldMaskModel, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        SysFieldMask::find_by_id(id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| FieldMaskError::FieldMaskNotFound.into())
+    }
+
+    async fn update_field_mask(
+        &self,
+        input: UpdateFieldMaskInput,
+        user: User,
+    ) -> Result<SysFieldMaskModel, AppError> {
+        let existing = self.get_field_mask(&input.id).await?;
+        self.check_field_mask(Some(&input.id), &input.field_mask)
+            .await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let previous_domain = existing.domain.clone();
+
+        let mut field_mask: SysFieldMaskActiveModel = existing.into();
+        field_mask.domain = Set(input.field_mask.domain);
+        field_mask.role_code = Set(input.field_mask.role_code);
+        field_mask.resource = Set(input.field_mask.resource.trim().to_string());
+        field_mask.field = Set(input.field_mask.field);
+        field_mask.strategy = Set(input.field_mask.strategy);
+        field_mask.description = Set(input.field_mask.description);
+        field_mask.updated_at = Set(Some(Local::now().naive_local()));
+        field_mask.updated_by = Set(Some(user.user_id()));
+
+        let updated = field_mask
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        field_mask::invalidate_field_masks(&previous_domain);
+        field_mask::invalidate_field_masks(&updated.domain);
+        Ok(updated)
+    }
+
+    async fn delete_field_mask(&self, id: &str) -> Result<(), AppError> {
+        let existing = self.get_field_mask(id).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        SysFieldMask::delete_by_id(id)
+            .exec(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        field_mask::invalidate_field_masks(&existing.domain);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FieldMaskRuleLoader for SysFieldMaskService {
+    async fn load_rules(&self, domain: &str) -> Result<Vec<FieldMaskRule>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let rules = SysFieldMask::find()
+            .filter(SysFieldMaskColumn::Domain.eq(domain))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(rules
+            .into_iter()
+            .map(|rule| FieldMaskRule {
+                role: rule.role_code,
+                resource: rule.resource,
+                field: rule.field,
+                strategy: match rule.strategy {
+                    MaskStrategy::Hide => field_mask::MaskStrategy::Hide,
+                    MaskStrategy::Partial => field_mask::MaskStrategy::Partial,
+                    MaskStrategy::Hash => field_mask::MaskStrategy::Hash,
+                },
+            })
+            .collect())
+    }
+}
@@ -1,46 +1,110 @@
 use async_trait::async_trait;
-use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-use server_core::web::{error::AppError, page::PaginatedData};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Select,
+};
+use server_core::web::{auth::User, error::AppError, export::ExportFile, page::PaginatedData};
 use server_model::admin::{
     entities::{
         prelude::SysLoginLog,
         sys_login_log::{Column as SysLoginLogColumn, Model as SysLoginLogModel},
     },
-    input::LoginLogPageRequest,
+    input::{AuditLogType, LoginLogExportRequest, LoginLogPageRequest},
+    output::ExportJobOutput,
 };
 
-use crate::helper::db_helper;
+use crate::helper::{data_scope_helper::DataScopeFilter, db_helper, export_helper};
+
+/// 导出的列，按导出顺序排列
+const EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "domain",
+    "userId",
+    "username",
+    "loginTime",
+    "ip",
+    "port",
+    "address",
+    "userAgent",
+    "requestId",
+    "type",
+    "createdAt",
+    "createdBy",
+    "chainSeq",
+    "prevHash",
+    "hash",
+];
 
 #[async_trait]
 pub trait TLoginLogService {
+    /// 分页查询登录日志，按当前用户角色的数据范围过滤
     async fn find_paginated_login_logs(
         &self,
         params: LoginLogPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<SysLoginLogModel>, AppError>;
+
+    /// 按创建时间顺序流式导出符合条件的登录日志，按当前用户角色的数据范围过滤
+    async fn export_login_logs(
+        &self,
+        params: LoginLogExportRequest,
+        user: &User,
+    ) -> Result<ExportFile, AppError>;
+
+    /// 创建登录日志的异步导出任务，数据范围在创建时确定
+    async fn create_login_log_export_job(
+        &self,
+        params: LoginLogExportRequest,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError>;
+
+    /// 查询当前用户创建的登录日志导出任务
+    async fn get_login_log_export_job(
+        &self,
+        id: &str,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError>;
 }
 
 pub struct SysLoginLogService;
 
+/// 分页查询与导出共用的过滤条件
+async fn filtered_query(
+    keywords: Option<&str>,
+    user: &User,
+) -> Result<Select<SysLoginLog>, AppError> {
+    let mut query = SysLoginLog::find();
+
+    if let Some(condition) = DataScopeFilter::resolve(user)
+        .await?
+        .condition(SysLoginLogColumn::Domain, SysLoginLogColumn::UserId)
+    {
+        query = query.filter(condition);
+    }
+
+    if let Some(keywords) = keywords {
+        let condition = Condition::any()
+            .add(SysLoginLogColumn::Domain.contains(keywords))
+            .add(SysLoginLogColumn::Username.contains(keywords))
+            .add(SysLoginLogColumn::Ip.contains(keywords))
+            .add(SysLoginLogColumn::Address.contains(keywords))
+            .add(SysLoginLogColumn::UserAgent.contains(keywords));
+        query = query.filter(condition);
+    }
+
+    Ok(query)
+}
+
 #[async_trait]
 impl TLoginLogService for SysLoginLogService {
     async fn find_paginated_login_logs(
         &self,
         params: LoginLogPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<SysLoginLogModel>, AppError> {
         let db = db_helper::get_db_connection().await?;
-        let mut query = SysLoginLog::find();
-
-        if let Some(ref keywords) = params.keywords {
-            let condition = Condition::any()
-                .add(SysLoginLogColumn::Domain.contains(keywords))
-                .add(SysLoginLogColumn::Username.contains(keywords))
-                .add(SysLoginLogColumn::Ip.contains(keywords))
-                .add(SysLoginLogColumn::Address.contains(keywords))
-                .add(SysLoginLogColumn::UserAgent.contains(keywords));
-            query = query.filter(condition);
-        }
-
-        query = query.order_by_desc(SysLoginLogColumn::CreatedAt);
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_desc(SysLoginLogColumn::CreatedAt);
 
         let total = query
             .clone()
@@ -61,4 +125,48 @@ impl TLoginLogService for SysLoginLogService {
             records,
         })
     }
+
+    async fn export_login_logs(
+        &self,
+        params: LoginLogExportRequest,
+        user: &User,
+    ) -> Result<ExportFile, AppError> {
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_asc(SysLoginLogColumn::CreatedAt);
+
+        Ok(export_helper::export_file(
+            query,
+            AuditLogType::Login,
+            params.format,
+            EXPORT_COLUMNS,
+        ))
+    }
+
+    async fn create_login_log_export_job(
+        &self,
+        params: LoginLogExportRequest,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError> {
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_asc(SysLoginLogColumn::CreatedAt);
+
+        export_helper::create_export_job(
+            query,
+            AuditLogType::Login,
+            params.format,
+            EXPORT_COLUMNS,
+            &user.user_id(),
+        )
+        .await
+    }
+
+    async fn get_login_log_export_job(
+        &self,
+        id: &str,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError> {
+        export_helper::get_export_job(id, AuditLogType::Login, &user.user_id()).await
+    }
 }
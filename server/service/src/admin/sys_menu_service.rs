@@ -16,6 +16,7 @@ use server_model::admin::{
 };
 use server_utils::TreeBuilder;
 
+use super::sys_role_service::find_inherited_role_ids;
 use crate::{admin::sys_menu_error::MenuError, helper::db_helper};
 
 #[async_trait]
@@ -55,7 +56,7 @@ impl SysMenuService {
             pid: menu.pid.clone(),
             menu_type: menu.menu_type.clone(),
             menu_name: menu.menu_name.clone(),
-            icon_type: menu.icon_type.clone(),
+            icon_type: menu.icon_type,
             icon: menu.icon.clone(),
             route_name: menu.route_name.clone(),
             route_path: menu.route_path.clone(),
@@ -277,10 +278,13 @@ impl TMenuService for SysMenuService {
     ) -> Result<Vec<i32>, AppError> {
         let db = db_helper::get_db_connection().await?;
 
+        // 包含从上级角色继承的菜单
+        let role_ids = find_inherited_role_ids(&[role_id]).await?;
+
         let role_menus = SysRoleMenu::find()
             .filter(
                 Condition::all()
-                    .add(SysRoleMenuColumn::RoleId.eq(role_id))
+                    .add(SysRoleMenuColumn::RoleId.is_in(role_ids))
                     .add(SysRoleMenuColumn::Domain.eq(domain)),
             )
             .all(db.as_ref())
@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use chrono::Local;
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use server_constant::definition::consts::TokenStatus;
+use server_core::web::{error::AppError, page::PaginatedData};
+use server_model::admin::{
+    entities::sys_tokens::{Column as SysTokensColumn, Entity as SysTokensEntity},
+    input::OnlineSessionPageRequest,
+    output::OnlineSessionOutput,
+};
+
+use super::sys_auth_service::{revoke_tokens, session_ttl};
+use crate::{admin::sys_token_error::TokenError, helper::db_helper};
+
+#[async_trait]
+pub trait TOnlineSessionService {
+    /// 分页查询指定域下的在线会话，登录或最近一次续期已超过刷新令牌有效期的会话不在其中
+    async fn find_paginated_sessions(
+        &self,
+        domain: &str,
+        params: OnlineSessionPageRequest,
+    ) -> Result<PaginatedData<OnlineSessionOutput>, AppError>;
+
+    /// 踢出单个会话
+    async fn kick_session(&self, domain: &str, id: &str) -> Result<(), AppError>;
+
+    /// 踢出用户的所有会话，返回被踢出的会话数量
+    async fn kick_user_sessions(&self, domain: &str, user_id: &str) -> Result<u64, AppError>;
+}
+
+pub struct SysOnlineSessionService;
+
+#[async_trait]
+impl TOnlineSessionService for SysOnlineSessionService {
+    async fn find_paginated_sessions(
+        &self,
+        domain: &str,
+        params: OnlineSessionPageRequest,
+    ) -> Result<PaginatedData<OnlineSessionOutput>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let online_since = Local::now().naive_local() - session_ttl().await;
+        let mut query = SysTokensEntity::find()
+            .select_only()
+            .columns([
+                SysTokensColumn::Id,
+                SysTokensColumn::UserId,
+                SysTokensColumn::Username,
+                SysTokensColumn::Domain,
+                SysTokensColumn::LoginTime,
+                SysTokensColumn::Ip,
+                SysTokensColumn::Port,
+                SysTokensColumn::Address,
+                SysTokensColumn::UserAgent,
+                SysTokensColumn::Type,
+            ])
+            .filter(SysTokensColumn::Domain.eq(domain))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .filter(SysTokensColumn::LoginTime.gt(online_since));
+
+        if let Some(ref keywords) = params.keywords {
+            let condition = Condition::any()
+                .add(SysTokensColumn::Username.contains(keywords))
+                .add(SysTokensColumn::Ip.contains(keywords))
+                .add(SysTokensColumn::Address.contains(keywords))
+                .add(SysTokensColumn::UserAgent.contains(keywords));
+            query = query.filter(condition);
+        }
+
+        query = query.order_by_desc(SysTokensColumn::LoginTime);
+
+        let total = query
+            .clone()
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let paginator = query
+            .into_model::<OnlineSessionOutput>()
+            .paginate(db.as_ref(), params.page_details.size);
+        let records = paginator
+            .fetch_page(params.page_details.current - 1)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(PaginatedData {
+            current: params.page_details.current,
+            size: params.page_details.size,
+            total,
+            records,
+        })
+    }
+
+    async fn kick_session(&self, domain: &str, id: &str) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let token = SysTokensEntity::find_by_id(id)
+            .filter(SysTokensColumn::Domain.eq(domain))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::from(TokenError::SessionNotFound))?;
+
+        revoke_tokens(db.as_ref(), vec![token]).await?;
+
+        Ok(())
+    }
+
+    async fn kick_user_sessions(&self, domain: &str, user_id: &str) -> Result<u64, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let tokens = SysTokensEntity::find()
+            .filter(SysTokensColumn::Domain.eq(domain))
+            .filter(SysTokensColumn::UserId.eq(user_id))
+            .filter(SysTokensColumn::Status.eq(TokenStatus::Active.to_string()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        revoke_tokens(db.as_ref(), tokens).await
+    }
+}
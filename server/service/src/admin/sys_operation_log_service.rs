@@ -1,58 +1,119 @@
-use std::any::Any;
-
 use async_trait::async_trait;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    Set,
+    ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Select,
 };
-use server_core::web::{error::AppError, page::PaginatedData};
-use server_global::{global::OperationLogContext, project_error};
+use server_core::web::{auth::User, error::AppError, export::ExportFile, page::PaginatedData};
+use server_global::global::{self, AuditLogMetricsSnapshot};
 use server_model::admin::{
     entities::{
         prelude::SysOperationLog,
-        sys_operation_log::{
-            ActiveModel as SysOperationLogActiveModel, Column as SysOperationLogColumn,
-            Model as SysOperationLogModel,
-        },
+        sys_operation_log::{Column as SysOperationLogColumn, Model as SysOperationLogModel},
     },
-    input::OperationLogPageRequest,
+    input::{AuditLogType, OperationLogExportRequest, OperationLogPageRequest},
+    output::ExportJobOutput,
 };
-use tracing::instrument;
-use ulid::Ulid;
 
-use crate::helper::db_helper;
+use crate::helper::{data_scope_helper::DataScopeFilter, db_helper, export_helper};
+
+/// 导出的列，按导出顺序排列
+const EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "domain",
+    "userId",
+    "username",
+    "moduleName",
+    "description",
+    "requestId",
+    "method",
+    "url",
+    "ip",
+    "userAgent",
+    "headers",
+    "params",
+    "body",
+    "response",
+    "startTime",
+    "endTime",
+    "duration",
+    "createdAt",
+    "chainSeq",
+    "prevHash",
+    "hash",
+];
 
 #[async_trait]
 pub trait TOperationLogService {
+    /// 分页查询操作日志，按当前用户角色的数据范围过滤
     async fn find_paginated_operation_logs(
         &self,
         params: OperationLogPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<SysOperationLogModel>, AppError>;
 
-    async fn handle_operation_log_event(event: &OperationLogContext) -> Result<(), AppError>;
+    /// 获取操作日志写入队列的指标
+    async fn get_audit_log_metrics(&self) -> AuditLogMetricsSnapshot;
+
+    /// 按创建时间顺序流式导出符合条件的操作日志，按当前用户角色的数据范围过滤
+    async fn export_operation_logs(
+        &self,
+        params: OperationLogExportRequest,
+        user: &User,
+    ) -> Result<ExportFile, AppError>;
+
+    /// 创建操作日志的异步导出任务，数据范围在创建时确定
+    async fn create_operation_log_export_job(
+        &self,
+        params: OperationLogExportRequest,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError>;
+
+    /// 查询当前用户创建的操作日志导出任务
+    async fn get_operation_log_export_job(
+        &self,
+        id: &str,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError>;
 }
 
 pub struct SysOperationLogService;
 
+/// 分页查询与导出共用的过滤条件
+async fn filtered_query(
+    keywords: Option<&str>,
+    user: &User,
+) -> Result<Select<SysOperationLog>, AppError> {
+    let mut query = SysOperationLog::find();
+
+    if let Some(condition) = DataScopeFilter::resolve(user)
+        .await?
+        .condition(SysOperationLogColumn::Domain, SysOperationLogColumn::UserId)
+    {
+        query = query.filter(condition);
+    }
+
+    if let Some(keywords) = keywords {
+        let condition = Condition::any()
+            .add(SysOperationLogColumn::Domain.contains(keywords))
+            .add(SysOperationLogColumn::Username.contains(keywords))
+            .add(SysOperationLogColumn::Ip.contains(keywords))
+            .add(SysOperationLogColumn::UserAgent.contains(keywords));
+        query = query.filter(condition);
+    }
+
+    Ok(query)
+}
+
 #[async_trait]
 impl TOperationLogService for SysOperationLogService {
     async fn find_paginated_operation_logs(
         &self,
         params: OperationLogPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<SysOperationLogModel>, AppError> {
         let db = db_helper::get_db_connection().await?;
-        let mut query = SysOperationLog::find();
-
-        if let Some(ref keywords) = params.keywords {
-            let condition = Condition::any()
-                .add(SysOperationLogColumn::Domain.contains(keywords))
-                .add(SysOperationLogColumn::Username.contains(keywords))
-                .add(SysOperationLogColumn::Ip.contains(keywords))
-                .add(SysOperationLogColumn::UserAgent.contains(keywords));
-            query = query.filter(condition);
-        }
-
-        query = query.order_by_desc(SysOperationLogColumn::CreatedAt);
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_desc(SysOperationLogColumn::CreatedAt);
 
         let total = query
             .clone()
@@ -74,50 +135,51 @@ impl TOperationLogService for SysOperationLogService {
         })
     }
 
-    async fn handle_operation_log_event(event: &OperationLogContext) -> Result<(), AppError> {
-        let db = db_helper::get_db_connection().await?;
+    async fn get_audit_log_metrics(&self) -> AuditLogMetricsSnapshot {
+        global::audit_log_metrics().await
+    }
 
-        SysOperationLogActiveModel {
-            id: Set(Ulid::new().to_string()),
-            user_id: Set(event.user_id.clone().unwrap_or_default()),
-            username: Set(event.username.clone().unwrap_or_default()),
-            domain: Set(event.domain.clone().unwrap_or_default()),
-            module_name: Set(event.module_name.clone()),
-            description: Set(event.description.clone()),
-            request_id: Set(event.request_id.clone()),
-            method: Set(event.method.clone()),
-            url: Set(event.url.clone()),
-            ip: Set(event.ip.clone()),
-            user_agent: Set(event.user_agent.clone()),
-            params: Set(event.params.clone()),
-            body: Set(event.body.clone()),
-            response: Set(event.response.clone()),
-            start_time: Set(event.start_time),
-            end_time: Set(event.end_time),
-            duration: Set(event.duration),
-            created_at: Set(event.created_at),
-        }
-        .insert(db.as_ref())
-        .await
-        .map_err(AppError::from)?;
+    async fn export_operation_logs(
+        &self,
+        params: OperationLogExportRequest,
+        user: &User,
+    ) -> Result<ExportFile, AppError> {
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_asc(SysOperationLogColumn::CreatedAt);
 
-        Ok(())
+        Ok(export_helper::export_file(
+            query,
+            AuditLogType::Operation,
+            params.format,
+            EXPORT_COLUMNS,
+        ))
     }
-}
 
-#[instrument(skip(rx))]
-pub async fn sys_operation_log_listener(
-    mut rx: tokio::sync::mpsc::UnboundedReceiver<Box<dyn Any + Send>>,
-) {
-    while let Some(event) = rx.recv().await {
-        if let Some(operation_log_context) = event.downcast_ref::<OperationLogContext>() {
-            if let Err(e) =
-                SysOperationLogService::handle_operation_log_event(operation_log_context).await
-            {
-                project_error!("Failed to handle operation log event: {:?}", e);
-            }
-        } else {
-            project_error!("Received unknown event type in operation log listener");
-        }
+    async fn create_operation_log_export_job(
+        &self,
+        params: OperationLogExportRequest,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError> {
+        let query = filtered_query(params.keywords.as_deref(), user)
+            .await?
+            .order_by_asc(SysOperationLogColumn::CreatedAt);
+
+        export_helper::create_export_job(
+            query,
+            AuditLogType::Operation,
+            params.format,
+            EXPORT_COLUMNS,
+            &user.user_id(),
+        )
+        .await
+    }
+
+    async fn get_operation_log_export_job(
+        &self,
+        id: &str,
+        user: &User,
+    ) -> Result<ExportJobOutput, AppError> {
+        export_helper::get_export_job(id, AuditLogType::Operation, &user.user_id()).await
     }
 }
@@ -1,26 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
 use async_trait::async_trait;
-use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter};
-use server_core::web::{error::AppError, page::PaginatedData};
+use chrono::Local;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
+    QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData};
 use server_model::admin::{
     entities::{
-        prelude::SysOrganization,
-        sys_organization::{Column as SysOrganizationColumn, Model as SysOrganizationModel},
+        prelude::{SysOrganization, SysUser, SysUserOrganization},
+        sys_organization::{
+            ActiveModel as SysOrganizationActiveModel, Column as SysOrganizationColumn,
+            Model as SysOrganizationModel,
+        },
+        sys_user::{Column as SysUserColumn, Relation as SysUserRelation},
+        sys_user_organization::{
+            ActiveModel as SysUserOrganizationActiveModel, Column as SysUserOrganizationColumn,
+        },
     },
-    input::OrganizationPageRequest,
+    input::{
+        AssignOrganizationUserInput, CreateOrganizationInput, MoveOrganizationInput,
+        OrganizationPageRequest, UpdateOrganizationInput,
+    },
+    output::{OrganizationTree, UserWithoutPassword},
 };
+use server_utils::TreeBuilder;
+use ulid::Ulid;
 
+use super::sys_organization_error::OrganizationError;
 use crate::helper::db_helper;
 
+/// 顶级组织的上级 ID
+const ROOT_PID: &str = "0";
+
 #[async_trait]
 pub trait TOrganizationService {
     async fn find_paginated_organizations(
         &self,
         params: OrganizationPageRequest,
     ) -> Result<PaginatedData<SysOrganizationModel>, AppError>;
+
+    async fn tree_organizations(&self) -> Result<Vec<OrganizationTree>, AppError>;
+
+    async fn create_organization(
+        &self,
+        input: CreateOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError>;
+    async fn get_organization(&self, id: &str) -> Result<SysOrganizationModel, AppError>;
+    async fn update_organization(
+        &self,
+        input: UpdateOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError>;
+    /// 调整组织的上级组织，不能移动到自身或其下级组织之下
+    async fn move_organization(
+        &self,
+        input: MoveOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError>;
+    /// 删除组织，存在下级组织时不允许删除，组织成员随之移出
+    async fn delete_organization(&self, id: &str) -> Result<(), AppError>;
+
+    async fn get_organization_users(&self, id: &str) -> Result<Vec<UserWithoutPassword>, AppError>;
+    /// 设置组织成员，用户只能归属一个组织，已归属其他组织的用户会被移入该组织
+    async fn assign_organization_users(
+        &self,
+        id: &str,
+        input: AssignOrganizationUserInput,
+    ) -> Result<(), AppError>;
 }
 
+#[derive(Clone)]
 pub struct SysOrganizationService;
 
+impl SysOrganizationService {
+    async fn check_organization_exists(
+        &self,
+        id: Option<&str>,
+        code: &str,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let mut query = SysOrganization::find().filter(SysOrganizationColumn::Code.eq(code));
+
+        if let Some(id) = id {
+            query = query.filter(SysOrganizationColumn::Id.ne(id));
+        }
+
+        let existing = query.one(db.as_ref()).await.map_err(AppError::from)?;
+
+        if existing.is_some() {
+            return Err(OrganizationError::DuplicateOrganizationCode.into());
+        }
+
+        Ok(())
+    }
+
+    /// 校验上级组织存在，且修改已有组织时不会形成环
+    async fn check_parent(&self, id: Option<&str>, pid: &str) -> Result<(), AppError> {
+        if pid == ROOT_PID {
+            return Ok(());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let parents: HashMap<String, String> = SysOrganization::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|org| (org.id, org.pid))
+            .collect();
+
+        if !parents.contains_key(pid) {
+            return Err(OrganizationError::ParentNotFound.into());
+        }
+
+        if let Some(id) = id {
+            // 从新的上级组织向上查找，途经自身即说明新的上级是自身或其下级
+            let mut visited = HashSet::new();
+            let mut current = Some(pid);
+            while let Some(node) = current.filter(|node| *node != ROOT_PID) {
+                if node == id {
+                    return Err(OrganizationError::CyclicParent.into());
+                }
+                if !visited.insert(node) {
+                    break;
+                }
+                current = parents.get(node).map(String::as_str);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl TOrganizationService for SysOrganizationService {
     async fn find_paginated_organizations(
@@ -57,4 +171,250 @@ impl TOrganizationService for SysOrganizationService {
             records,
         })
     }
+
+    async fn tree_organizations(&self) -> Result<Vec<OrganizationTree>, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let organizations = SysOrganization::find()
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let nodes: Vec<OrganizationTree> = organizations
+            .into_iter()
+            .map(OrganizationTree::from)
+            .collect();
+
+        Ok(TreeBuilder::build(
+            nodes,
+            |node| node.id.clone(),
+            |node| {
+                if node.pid == ROOT_PID {
+                    None
+                } else {
+                    Some(node.pid.clone())
+                }
+            },
+            |node| node.code.clone(),
+            |node, children| node.children = Some(children),
+        ))
+    }
+
+    async fn create_organization(
+        &self,
+        input: CreateOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError> {
+        self.check_organization_exists(None, &input.code).await?;
+        self.check_parent(None, &input.pid).await?;
+
+        let db = db_helper::get_db_connection().await?;
+
+        let organization = SysOrganizationActiveModel {
+            id: Set(Ulid::new().to_string()),
+            pid: Set(input.pid),
+            code: Set(input.code),
+            name: Set(input.name),
+            description: Set(input.description),
+            status: Set(input.status),
+            created_at: Set(Local::now().naive_local()),
+            created_by: Set(user.user_id()),
+            ..Default::default()
+        };
+
+        let result = organization
+            .insert(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(result)
+    }
+
+    async fn get_organization(&self, id: &str) -> Result<SysOrganizationModel, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        SysOrganization::find_by_id(id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| OrganizationError::OrganizationNotFound.into())
+    }
+
+    async fn update_organization(
+        &self,
+        input: UpdateOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError> {
+        let existing = self.get_organization(&input.id).await?;
+
+        self.check_organization_exists(Some(&input.id), &input.organization.code)
+            .await?;
+        self.check_parent(Some(&input.id), &input.organization.pid)
+            .await?;
+
+        let db = db_helper::get_db_connection().await?;
+
+        let mut organization: SysOrganizationActiveModel = existing.into();
+        organization.pid = Set(input.organization.pid);
+        organization.code = Set(input.organization.code);
+        organization.name = Set(input.organization.name);
+        organization.description = Set(input.organization.description);
+        organization.status = Set(input.organization.status);
+
+        organization.updated_at = Set(Some(Local::now().naive_local()));
+        organization.updated_by = Set(Some(user.user_id()));
+
+        let updated = organization
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(updated)
+    }
+
+    async fn move_organization(
+        &self,
+        input: MoveOrganizationInput,
+        user: User,
+    ) -> Result<SysOrganizationModel, AppError> {
+        let existing = self.get_organization(&input.id).await?;
+
+        self.check_parent(Some(&input.id), &input.pid).await?;
+
+        let db = db_helper::get_db_connection().await?;
+
+        let mut organization: SysOrganizationActiveModel = existing.into();
+        organization.pid = Set(input.pid);
+        organization.updated_at = Set(Some(Local::now().naive_local()));
+        organization.updated_by = Set(Some(user.user_id()));
+
+        let updated = organization
+            .update(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        Ok(updated)
+    }
+
+    async fn delete_organization(&self, id: &str) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let has_children = SysOrganization::find()
+            .filter(SysOrganizationColumn::Pid.eq(id))
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .is_some();
+        if has_children {
+            return Err(OrganizationError::HasChildren.into());
+        }
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+
+        SysUserOrganization::delete_many()
+            .filter(SysUserOrganizationColumn::OrgId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+        let result = SysOrganization::delete_by_id(id)
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+        if result.rows_affected == 0 {
+            return Err(OrganizationError::OrganizationNotFound.into());
+        }
+
+        txn.commit().await.map_err(AppError::from)?;
+        Ok(())
+    }
+
+    async fn get_organization_users(&self, id: &str) -> Result<Vec<UserWithoutPassword>, AppError> {
+        self.get_organization(id).await?;
+
+        let db = db_helper::get_db_connection().await?;
+        let users = SysUser::find()
+            .join(
+                JoinType::InnerJoin,
+                SysUserRelation::SysUserOrganization.def(),
+            )
+            .filter(SysUserOrganizationColumn::OrgId.eq(id))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(users.into_iter().map(UserWithoutPassword::from).collect())
+    }
+
+    async fn assign_organization_users(
+        &self,
+        id: &str,
+        input: AssignOrganizationUserInput,
+    ) -> Result<(), AppError> {
+        self.get_organization(id).await?;
+
+        let user_ids: Vec<String> = input
+            .user_ids
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let db = db_helper::get_db_connection().await?;
+        if !user_ids.is_empty() {
+            let found = SysUser::find()
+                .filter(SysUserColumn::Id.is_in(user_ids.clone()))
+                .count(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            if found != user_ids.len() as u64 {
+                return Err(OrganizationError::UserNotFound.into());
+            }
+        }
+
+        let txn = db.begin().await.map_err(AppError::from)?;
+
+        let members: HashSet<String> = SysUserOrganization::find()
+            .filter(SysUserOrganizationColumn::OrgId.eq(id))
+            .all(&txn)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|link| link.user_id)
+            .collect();
+
+        // 移出未列出的现有成员，并解除新成员与其他组织的关联
+        SysUserOrganization::delete_many()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(SysUserOrganizationColumn::OrgId.eq(id))
+                            .add(SysUserOrganizationColumn::UserId.is_not_in(user_ids.clone())),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(SysUserOrganizationColumn::OrgId.ne(id))
+                            .add(SysUserOrganizationColumn::UserId.is_in(user_ids.clone())),
+                    ),
+            )
+            .exec(&txn)
+            .await
+            .map_err(AppError::from)?;
+
+        let now = Local::now().naive_local();
+        let links: Vec<SysUserOrganizationActiveModel> = user_ids
+            .into_iter()
+            .filter(|user_id| !members.contains(user_id))
+            .map(|user_id| SysUserOrganizationActiveModel {
+                user_id: Set(user_id),
+                org_id: Set(id.to_string()),
+                created_at: Set(now),
+            })
+            .collect();
+        if !links.is_empty() {
+            SysUserOrganization::insert_many(links)
+                .exec(&txn)
+                .await
+                .map_err(AppError::from)?;
+        }
+
+        txn.commit().await.map_err(AppError::from)?;
+        Ok(())
+    }
 }
@@ -1,23 +1,42 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
 use async_trait::async_trait;
+use axum_casbin::casbin::{CoreApi, EventData, MgmtApi, RbacApi};
 use chrono::Local;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, Set,
+    sea_query::Query, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, Set, TransactionTrait,
 };
 use server_core::web::{error::AppError, page::PaginatedData};
 use server_model::admin::{
     entities::{
-        prelude::SysRole,
+        casbin_rule::Column as CasbinRuleColumn,
+        prelude::{CasbinRule, SysOrganization, SysRole, SysRoleDataScope, SysRoleMenu},
+        sea_orm_active_enums::DataScope,
+        sys_organization::Column as SysOrganizationColumn,
         sys_role::{
             ActiveModel as SysRoleActiveModel, Column as SysRoleColumn, Model as SysRoleModel,
         },
+        sys_role_data_scope::{
+            ActiveModel as SysRoleDataScopeActiveModel, Column as SysRoleDataScopeColumn,
+        },
+        sys_role_menu::Column as SysRoleMenuColumn,
     },
     input::{CreateRoleInput, RolePageRequest, UpdateRoleInput},
+    output::{EffectiveMenu, EffectivePermission, RoleEffectivePermissionsOutput},
 };
+use tokio::sync::RwLock;
 
 use super::sys_role_error::RoleError;
 use crate::helper::db_helper;
 use ulid::Ulid;
 
+/// 顶级角色的上级 ID
+pub(crate) const ROOT_PID: &str = "0";
+
 #[async_trait]
 pub trait TRoleService {
     async fn find_paginated_roles(
@@ -25,10 +44,32 @@ pub trait TRoleService {
         params: RolePageRequest,
     ) -> Result<PaginatedData<SysRoleModel>, AppError>;
 
-    async fn create_role(&self, input: CreateRoleInput) -> Result<SysRoleModel, AppError>;
+    async fn create_role(
+        &self,
+        input: CreateRoleInput,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<SysRoleModel, AppError>;
     async fn get_role(&self, id: &str) -> Result<SysRoleModel, AppError>;
-    async fn update_role(&self, input: UpdateRoleInput) -> Result<SysRoleModel, AppError>;
-    async fn delete_role(&self, id: &str) -> Result<(), AppError>;
+    async fn update_role(
+        &self,
+        input: UpdateRoleInput,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<SysRoleModel, AppError>;
+    async fn delete_role(
+        &self,
+        id: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<(), AppError>;
+
+    /// 获取角色在域内的有效权限及其来源角色
+    async fn get_effective_permissions(
+        &self,
+        id: &str,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<RoleEffectivePermissionsOutput, AppError>;
 }
 
 #[derive(Clone)]
@@ -51,6 +92,166 @@ impl SysRoleService {
 
         Ok(())
     }
+
+    /// 校验上级角色存在，且不是角色自身或其下级角色，返回上级角色
+    async fn check_parent(
+        &self,
+        id: Option<&str>,
+        pid: &str,
+    ) -> Result<Option<SysRoleModel>, AppError> {
+        if pid == ROOT_PID {
+            return Ok(None);
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let roles = load_roles(db.as_ref()).await?;
+        let parent = roles
+            .get(pid)
+            .cloned()
+            .ok_or(RoleError::ParentRoleNotFound)?;
+
+        if let Some(id) = id {
+            // 新的上级角色的继承链途经自身即说明会形成循环继承
+            if role_lineage(&roles, pid).iter().any(|role| role.id == id) {
+                return Err(RoleError::CyclicParent.into());
+            }
+        }
+
+        Ok(Some(parent))
+    }
+
+    /// 同步角色的继承关系（Casbin 分组策略 `g, 角色, 上级角色, 域`）
+    ///
+    /// 角色已有的继承关系会按新的上级角色在原有的各个域中重建，并补充当前域；
+    /// 角色编码变更时，下级角色的继承关系改为指向新编码。已有的继承关系从数据库读取，
+    /// 策略按域按需加载时未加载的域同样会被同步。
+    async fn sync_role_inheritance(
+        previous_code: &str,
+        role_code: &str,
+        parent_code: Option<&str>,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<(), AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let existing_links = find_grouping_links(db.as_ref(), 0, previous_code).await?;
+
+        let mut enforcer_write = enforcer.write().await;
+        let mut domains: Vec<String> = existing_links
+            .iter()
+            .filter_map(|link| link.get(2).cloned())
+            .collect();
+        if !domains.iter().any(|d| d == domain) {
+            domains.push(domain.to_string());
+        }
+
+        let mut links_to_remove = existing_links;
+        let mut links_to_add: Vec<Vec<String>> = parent_code
+            .map(|parent_code| {
+                domains
+                    .iter()
+                    .map(|d| vec![role_code.to_string(), parent_code.to_string(), d.clone()])
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if previous_code != role_code {
+            let child_links = find_grouping_links(db.as_ref(), 1, previous_code).await?;
+            links_to_add.extend(child_links.iter().map(|link| {
+                let mut link = link.clone();
+                link[1] = role_code.to_string();
+                link
+            }));
+            links_to_remove.extend(child_links);
+        }
+
+        let links_to_keep: Vec<Vec<String>> = links_to_remove
+            .iter()
+            .filter(|link| links_to_add.contains(link))
+            .cloned()
+            .collect();
+        links_to_remove.retain(|link| !links_to_keep.contains(link));
+        links_to_add.retain(|link| !links_to_keep.contains(link));
+
+        if !links_to_remove.is_empty() {
+            remove_grouping_links(&mut *enforcer_write, links_to_remove)
+                .await
+                .map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+        }
+
+        if !links_to_add.is_empty() {
+            enforcer_write
+                .add_grouping_policies(links_to_add)
+                .await
+                .map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// 校验自定义数据范围的组织均存在，返回去重后的组织 ID
+    async fn check_data_scope_orgs(
+        &self,
+        data_scope: DataScope,
+        org_ids: Vec<String>,
+    ) -> Result<Vec<String>, AppError> {
+        if data_scope != DataScope::Custom {
+            return Ok(vec![]);
+        }
+
+        let mut org_ids = org_ids;
+        org_ids.sort();
+        org_ids.dedup();
+        if org_ids.is_empty() {
+            return Ok(org_ids);
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let found = SysOrganization::find()
+            .filter(SysOrganizationColumn::Id.is_in(org_ids.clone()))
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+        if found != org_ids.len() as u64 {
+            return Err(RoleError::DataScopeOrganizationNotFound.into());
+        }
+
+        Ok(org_ids)
+    }
+
+    /// 覆盖角色的自定义数据范围组织
+    async fn replace_data_scope_orgs<C: ConnectionTrait>(
+        db: &C,
+        role_id: &str,
+        org_ids: Vec<String>,
+    ) -> Result<(), AppError> {
+        SysRoleDataScope::delete_many()
+            .filter(SysRoleDataScopeColumn::RoleId.eq(role_id))
+            .exec(db)
+            .await
+            .map_err(AppError::from)?;
+
+        if org_ids.is_empty() {
+            return Ok(());
+        }
+
+        SysRoleDataScope::insert_many(org_ids.into_iter().map(|org_id| {
+            SysRoleDataScopeActiveModel {
+                role_id: Set(role_id.to_string()),
+                org_id: Set(org_id),
+            }
+        }))
+        .exec(db)
+        .await
+        .map_err(AppError::from)?;
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -87,10 +288,21 @@ impl TRoleService for SysRoleService {
         })
     }
 
-    async fn create_role(&self, input: CreateRoleInput) -> Result<SysRoleModel, AppError> {
+    async fn create_role(
+        &self,
+        input: CreateRoleInput,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<SysRoleModel, AppError> {
         let db = db_helper::get_db_connection().await?;
 
         self.check_role_exists(None, &input.code).await?;
+        let parent = self.check_parent(None, &input.pid).await?;
+        let org_ids = self
+            .check_data_scope_orgs(input.data_scope, input.data_scope_org_ids)
+            .await?;
+
+        let txn = db.begin().await.map_err(AppError::from)?;
 
         let role = SysRoleActiveModel {
             id: Set(Ulid::new().to_string()),
@@ -99,12 +311,26 @@ impl TRoleService for SysRoleService {
             name: Set(input.name),
             status: Set(input.status),
             description: Set(input.description),
+            data_scope: Set(input.data_scope),
             created_at: Set(Local::now().naive_local()),
             created_by: Set("TODO".to_string()),
             ..Default::default()
         };
 
-        let result = role.insert(db.as_ref()).await.map_err(AppError::from)?;
+        let result = role.insert(&txn).await.map_err(AppError::from)?;
+        Self::replace_data_scope_orgs(&txn, &result.id, org_ids).await?;
+
+        txn.commit().await.map_err(AppError::from)?;
+
+        Self::sync_role_inheritance(
+            &result.code,
+            &result.code,
+            parent.as_ref().map(|parent| parent.code.as_str()),
+            domain,
+            enforcer,
+        )
+        .await?;
+
         Ok(result)
     }
 
@@ -117,18 +343,28 @@ impl TRoleService for SysRoleService {
             .ok_or_else(|| RoleError::RoleNotFound.into())
     }
 
-    async fn update_role(&self, input: UpdateRoleInput) -> Result<SysRoleModel, AppError> {
+    async fn update_role(
+        &self,
+        input: UpdateRoleInput,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<SysRoleModel, AppError> {
         let db = db_helper::get_db_connection().await?;
 
         self.check_role_exists(Some(&input.id), &input.role.code)
             .await?;
+        let parent = self.check_parent(Some(&input.id), &input.role.pid).await?;
+        let org_ids = self
+            .check_data_scope_orgs(input.role.data_scope, input.role.data_scope_org_ids)
+            .await?;
 
-        let role: SysRoleActiveModel = SysRole::find_by_id(&input.id)
+        let existing_role = SysRole::find_by_id(&input.id)
             .one(db.as_ref())
             .await
             .map_err(AppError::from)?
-            .ok_or_else(|| AppError::from(RoleError::RoleNotFound))?
-            .into();
+            .ok_or_else(|| AppError::from(RoleError::RoleNotFound))?;
+        let previous_code = existing_role.code.clone();
+        let role: SysRoleActiveModel = existing_role.into();
 
         let role = SysRoleActiveModel {
             id: Set(input.id.clone()),
@@ -136,21 +372,305 @@ impl TRoleService for SysRoleService {
             code: Set(input.role.code),
             name: Set(input.role.name),
             description: Set(input.role.description),
+            data_scope: Set(input.role.data_scope),
 
             updated_at: Set(Some(Local::now().naive_local())),
             ..role
         };
 
-        let updated_role = role.update(db.as_ref()).await.map_err(AppError::from)?;
+        let txn = db.begin().await.map_err(AppError::from)?;
+        let updated_role = role.update(&txn).await.map_err(AppError::from)?;
+        Self::replace_data_scope_orgs(&txn, &updated_role.id, org_ids).await?;
+
+        txn.commit().await.map_err(AppError::from)?;
+
+        Self::sync_role_inheritance(
+            &previous_code,
+            &updated_role.code,
+            parent.as_ref().map(|parent| parent.code.as_str()),
+            domain,
+            enforcer,
+        )
+        .await?;
+
         Ok(updated_role)
     }
 
-    async fn delete_role(&self, id: &str) -> Result<(), AppError> {
+    async fn delete_role(
+        &self,
+        id: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<(), AppError> {
         let db = db_helper::get_db_connection().await?;
+
+        let role = SysRole::find_by_id(id)
+            .one(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            .ok_or(RoleError::RoleNotFound)?;
+
+        let has_children = SysRole::find()
+            .filter(SysRoleColumn::Pid.eq(id))
+            .count(db.as_ref())
+            .await
+            .map_err(AppError::from)?
+            > 0;
+        if has_children {
+            return Err(RoleError::HasChildren.into());
+        }
+
         SysRole::delete_by_id(id)
             .exec(db.as_ref())
             .await
             .map_err(AppError::from)?;
+
+        // 移除角色作为下级或上级的全部继承关系
+        let mut enforcer_write = enforcer.write().await;
+        for field_index in [0, 1] {
+            enforcer_write
+                .remove_filtered_grouping_policy(field_index, vec![role.code.clone()])
+                .await
+                .map_err(|e| AppError {
+                    code: 500,
+                    message: e.to_string(),
+                })?;
+        }
+
         Ok(())
     }
+
+    async fn get_effective_permissions(
+        &self,
+        id: &str,
+        domain: &str,
+        enforcer: Arc<RwLock<impl CoreApi + RbacApi>>,
+    ) -> Result<RoleEffectivePermissionsOutput, AppError> {
+        let db = db_helper::get_db_connection().await?;
+        let roles = load_roles(db.as_ref()).await?;
+        let lineage = role_lineage(&roles, id);
+        let role = lineage.first().ok_or(RoleError::RoleNotFound)?;
+
+        // 继承链由近及远，同一权限以最近的授予角色为来源
+        let mut permissions: Vec<EffectivePermission> = Vec::new();
+        {
+            let enforcer_read = enforcer.read().await;
+            for source in &lineage {
+                for policy in enforcer_read
+                    .get_filtered_policy(0, vec![source.code.clone(), domain.to_string()])
+                {
+                    let (Some(path), Some(method)) = (policy.get(2), policy.get(3)) else {
+                        continue;
+                    };
+                    if permissions
+                        .iter()
+                        .any(|p| &p.path == path && &p.method == method)
+                    {
+                        continue;
+                    }
+                    permissions.push(EffectivePermission {
+                        path: path.clone(),
+                        method: method.clone(),
+                        source_role: source.code.clone(),
+                        inherited: source.id != role.id,
+                        condition: policy.get(4).cloned().unwrap_or_default(),
+                    });
+                }
+            }
+        }
+
+        let role_menus = SysRoleMenu::find()
+            .filter(SysRoleMenuColumn::RoleId.is_in(lineage.iter().map(|source| source.id.clone())))
+            .filter(SysRoleMenuColumn::Domain.eq(domain))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let mut menus: Vec<EffectiveMenu> = Vec::new();
+        for source in &lineage {
+            for role_menu in role_menus.iter().filter(|rm| rm.role_id == source.id) {
+                if menus.iter().any(|m| m.menu_id == role_menu.menu_id) {
+                    continue;
+                }
+                menus.push(EffectiveMenu {
+                    menu_id: role_menu.menu_id,
+                    source_role: source.code.clone(),
+                    inherited: source.id != role.id,
+                });
+            }
+        }
+
+        Ok(RoleEffectivePermissionsOutput {
+            role_id: role.id.clone(),
+            role_code: role.code.clone(),
+            domain: domain.to_string(),
+            inherited_roles: lineage[1..]
+                .iter()
+                .map(|source| source.code.clone())
+                .collect(),
+            permissions,
+            menus,
+        })
+    }
+}
+
+async fn load_roles(db: &DatabaseConnection) -> Result<HashMap<String, SysRoleModel>, AppError> {
+    Ok(SysRole::find()
+        .all(db)
+        .await
+        .map_err(AppError::from)?
+        .into_iter()
+        .map(|role| (role.id.clone(), role))
+        .collect())
+}
+
+/// 沿 `pid` 向上查找角色的继承链，首个元素为角色自身，其后由近及远为各级上级角色
+fn role_lineage<'a>(roles: &'a HashMap<String, SysRoleModel>, id: &str) -> Vec<&'a SysRoleModel> {
+    let mut lineage = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = roles.get(id);
+    while let Some(role) = current {
+        if !visited.insert(role.id.as_str()) {
+            break;
+        }
+        lineage.push(role);
+        current = roles.get(&role.pid);
+    }
+    lineage
+}
+
+/// 查找角色及其全部上级角色的 ID
+pub(crate) async fn find_inherited_role_ids(role_ids: &[String]) -> Result<Vec<String>, AppError> {
+    let db = db_helper::get_db_connection().await?;
+    let roles = load_roles(db.as_ref()).await?;
+
+    let mut inherited: Vec<String> = Vec::new();
+    for role_id in role_ids {
+        for role in role_lineage(&roles, role_id) {
+            if !inherited.contains(&role.id) {
+                inherited.push(role.id.clone());
+            }
+        }
+    }
+    Ok(inherited)
+}
+
+/// 查找角色及其全部上级角色的编码
+pub(crate) async fn find_inherited_role_codes(
+    role_codes: &[String],
+) -> Result<Vec<String>, AppError> {
+    let db = db_helper::get_db_connection().await?;
+    let roles = load_roles(db.as_ref()).await?;
+
+    let mut inherited: Vec<String> = Vec::new();
+    for role in roles
+        .values()
+        .filter(|role| role_codes.contains(&role.code))
+    {
+        for ancestor in role_lineage(&roles, &role.id) {
+            if !inherited.contains(&ancestor.code) {
+                inherited.push(ancestor.code.clone());
+            }
+        }
+    }
+    Ok(inherited)
+}
+
+/// 从数据库读取第 `field_index` 列为 `role_code` 的继承关系 `[角色, 上级角色, 域]`
+async fn find_grouping_links(
+    db: &DatabaseConnection,
+    field_index: usize,
+    role_code: &str,
+) -> Result<Vec<Vec<String>>, AppError> {
+    let column = if field_index == 0 {
+        CasbinRuleColumn::V0
+    } else {
+        CasbinRuleColumn::V1
+    };
+    let rules = CasbinRule::find()
+        .filter(CasbinRuleColumn::Ptype.eq("g"))
+        .filter(column.eq(role_code))
+        .all(db)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(rules
+        .into_iter()
+        .map(|rule| {
+            vec![
+                rule.v0.unwrap_or_default(),
+                rule.v1.unwrap_or_default(),
+                rule.v2.unwrap_or_default(),
+            ]
+        })
+        .collect())
+}
+
+/// 删除继承关系
+///
+/// 执行器只会删除全部位于内存模型中的规则，所在域未加载的规则直接从适配器删除，
+/// 并通知其他实例。
+async fn remove_grouping_links(
+    enforcer: &mut (impl CoreApi + MgmtApi),
+    links: Vec<Vec<String>>,
+) -> axum_casbin::casbin::Result<()> {
+    let (loaded, unloaded): (Vec<_>, Vec<_>) = links
+        .into_iter()
+        .partition(|link| enforcer.has_grouping_policy(link.clone()));
+
+    if !loaded.is_empty() {
+        enforcer.remove_grouping_policies(loaded).await?;
+    }
+
+    if !unloaded.is_empty() {
+        enforcer
+            .get_mut_adapter()
+            .remove_policies("g", "g", unloaded.clone())
+            .await?;
+        if let Some(watcher) = enforcer.get_mut_watcher() {
+            watcher.update(EventData::RemovePolicies(
+                "g".to_string(),
+                "g".to_string(),
+                unloaded,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 在域内获得授权的角色
+///
+/// 角色本身不归属于某个域，角色在域内的菜单（`sys_role_menu`）与策略
+/// （`p, 角色, 域, ...`、`g, 角色, 上级角色, 域`）决定了它在该域中是否可用。
+pub(crate) fn granted_in_domain(domain: &str) -> Condition {
+    Condition::any()
+        .add(
+            SysRoleColumn::Id.in_subquery(
+                Query::select()
+                    .column(SysRoleMenuColumn::RoleId)
+                    .from(SysRoleMenu)
+                    .and_where(SysRoleMenuColumn::Domain.eq(domain))
+                    .to_owned(),
+            ),
+        )
+        .add(
+            SysRoleColumn::Code.in_subquery(
+                Query::select()
+                    .column(CasbinRuleColumn::V0)
+                    .from(CasbinRule)
+                    .cond_where(
+                        Condition::any()
+                            .add(
+                                CasbinRuleColumn::Ptype
+                                    .eq("p")
+                                    .and(CasbinRuleColumn::V1.eq(domain)),
+                            )
+                            .add(
+                                CasbinRuleColumn::Ptype
+                                    .eq("g")
+                                    .and(CasbinRuleColumn::V2.eq(domain)),
+                            ),
+                    )
+                    .to_owned(),
+            ),
+        )
 }
@@ -1,38 +1,70 @@
 use async_trait::async_trait;
 use chrono::Local;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, PaginatorTrait,
-    QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
 };
-use server_core::web::{error::AppError, page::PaginatedData};
+use server_config::{PasswordPolicy, PasswordPolicyConfig};
+use server_core::web::{auth::User, error::AppError, page::PaginatedData};
+use server_global::global;
 use server_model::admin::{
     entities::{
-        prelude::SysUser,
+        prelude::{SysUser, SysUserPasswordHistory},
         sys_user::{
             ActiveModel as SysUserActiveModel, Column as SysUserColumn, Model as SysUserModel,
         },
+        sys_user_password_history::{
+            ActiveModel as SysUserPasswordHistoryActiveModel,
+            Column as SysUserPasswordHistoryColumn,
+        },
+    },
+    input::{
+        ChangePasswordInput, CreateUserInput, ResetPasswordInput, UpdateUserInput, UserPageRequest,
     },
-    input::{CreateUserInput, UpdateUserInput, UserPageRequest},
     output::UserWithoutPassword,
 };
 use server_utils::SecureUtil;
 use ulid::Ulid;
 
-use super::sys_user_error::UserError;
-use crate::helper::db_helper;
+use super::{check_domain_access, sys_auth_service::revoke_user_tokens, sys_user_error::UserError};
+use crate::helper::{data_scope_helper::DataScopeFilter, db_helper};
 
 #[async_trait]
 pub trait TUserService {
     async fn find_all(&self) -> Result<Vec<UserWithoutPassword>, AppError>;
+    /// 分页查询用户，按当前用户角色的数据范围过滤
     async fn find_paginated_users(
         &self,
         params: UserPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<UserWithoutPassword>, AppError>;
 
     async fn create_user(&self, input: CreateUserInput) -> Result<UserWithoutPassword, AppError>;
     async fn get_user(&self, id: &str) -> Result<UserWithoutPassword, AppError>;
     async fn update_user(&self, input: UpdateUserInput) -> Result<UserWithoutPassword, AppError>;
     async fn delete_user(&self, id: &str) -> Result<(), AppError>;
+
+    /// 强制用户下线，吊销该用户所有有效令牌，返回被吊销的令牌数量
+    ///
+    /// 只能操作操作人所在域的用户，内置域的操作人不受限制。
+    async fn force_logout(&self, id: &str, operator: &User) -> Result<u64, AppError>;
+
+    /// 用户修改自己的密码，需校验旧密码
+    async fn change_password(
+        &self,
+        user_id: &str,
+        input: ChangePasswordInput,
+    ) -> Result<(), AppError>;
+
+    /// 管理员重置用户密码，用户下次登录后需自行修改，同时吊销该用户所有令牌
+    ///
+    /// 只能操作操作人所在域的用户，内置域的操作人不受限制。
+    async fn reset_password(
+        &self,
+        id: &str,
+        input: ResetPasswordInput,
+        operator: &User,
+    ) -> Result<(), AppError>;
 }
 
 #[derive(Clone)]
@@ -77,10 +109,18 @@ impl TUserService for SysUserService {
     async fn find_paginated_users(
         &self,
         params: UserPageRequest,
+        user: &User,
     ) -> Result<PaginatedData<UserWithoutPassword>, AppError> {
         let db = db_helper::get_db_connection().await?;
         let mut query = SysUser::find();
 
+        if let Some(condition) = DataScopeFilter::resolve(user)
+            .await?
+            .condition(SysUserColumn::Domain, SysUserColumn::Id)
+        {
+            query = query.filter(condition);
+        }
+
         if let Some(ref keywords) = params.keywords {
             let condition = Condition::any().add(SysUserColumn::Username.contains(keywords));
             query = query.filter(condition);
@@ -112,6 +152,12 @@ impl TUserService for SysUserService {
     async fn create_user(&self, input: CreateUserInput) -> Result<UserWithoutPassword, AppError> {
         self.check_username_unique(&input.username).await?;
 
+        let policy = password_policy(&input.domain).await;
+        policy
+            .validate(&input.password, &input.username)
+            .map_err(UserError::PasswordPolicyViolation)?;
+
+        let now = Local::now().naive_local();
         let db = db_helper::get_db_connection().await?;
         let user = SysUserActiveModel {
             id: Set(Ulid::new().to_string()),
@@ -124,8 +170,10 @@ impl TUserService for SysUserService {
             email: Set(input.email),
             phone_number: Set(input.phone_number),
             status: Set(input.status),
-            created_at: Set(Local::now().naive_local()),
+            created_at: Set(now),
             created_by: Set("TODO".to_string()),
+            password_changed_at: Set(Some(now)),
+            must_change_password: Set(false),
             ..Default::default()
         };
 
@@ -152,7 +200,7 @@ impl TUserService for SysUserService {
 
         user.domain = Set(input.user.domain);
         user.username = Set(input.user.username);
-        user.password = Set(input.user.password); // TODO: Note: In a real application, you should hash the password
+        // 密码通过修改密码或重置密码接口单独维护，这里不做更新
         user.nick_name = Set(input.user.nick_name);
         user.avatar = Set(input.user.avatar);
         user.email = Set(input.user.email);
@@ -178,4 +226,169 @@ impl TUserService for SysUserService {
 
         Ok(())
     }
+
+    async fn force_logout(&self, id: &str, operator: &User) -> Result<u64, AppError> {
+        let user = self.get_user_by_id(id.to_string()).await?;
+        check_domain_access(operator, &user.domain)?;
+
+        let db = db_helper::get_db_connection().await?;
+        revoke_user_tokens(db.as_ref(), &user.id).await
+    }
+
+    async fn change_password(
+        &self,
+        user_id: &str,
+        input: ChangePasswordInput,
+    ) -> Result<(), AppError> {
+        let user = self.get_user_by_id(user_id.to_string()).await?;
+
+        if !SecureUtil::verify_password(input.old_password.as_bytes(), &user.password)
+            .unwrap_or(false)
+        {
+            return Err(UserError::WrongPassword.into());
+        }
+
+        let db = db_helper::get_db_connection().await?;
+        let txn = db.begin().await.map_err(AppError::from)?;
+        update_password(&txn, user, &input.new_password, false).await?;
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(())
+    }
+
+    async fn reset_password(
+        &self,
+        id: &str,
+        input: ResetPasswordInput,
+        operator: &User,
+    ) -> Result<(), AppError> {
+        let user = self.get_user_by_id(id.to_string()).await?;
+        check_domain_access(operator, &user.domain)?;
+
+        let db = db_helper::get_db_connection().await?;
+        let txn = db.begin().await.map_err(AppError::from)?;
+        let user_id = user.id.clone();
+        update_password(&txn, user, &input.password, true).await?;
+        revoke_user_tokens(&txn, &user_id).await?;
+        txn.commit().await.map_err(AppError::from)?;
+
+        Ok(())
+    }
+}
+
+async fn password_policy(domain: &str) -> PasswordPolicy {
+    global::get_config::<PasswordPolicyConfig>()
+        .await
+        .unwrap_or_default()
+        .policy_for(domain)
+        .clone()
+}
+
+/// 按所在域的密码策略校验并更新密码，旧密码哈希写入历史记录
+async fn update_password<C: ConnectionTrait>(
+    db: &C,
+    user: SysUserModel,
+    new_password: &str,
+    must_change_password: bool,
+) -> Result<(), AppError> {
+    let policy = password_policy(&user.domain).await;
+    policy
+        .validate(new_password, &user.username)
+        .map_err(UserError::PasswordPolicyViolation)?;
+
+    if policy.history_size > 0 {
+        let history = SysUserPasswordHistory::find()
+            .filter(SysUserPasswordHistoryColumn::UserId.eq(&user.id))
+            .order_by_desc(SysUserPasswordHistoryColumn::CreatedAt)
+            .limit(policy.history_size as u64)
+            .all(db)
+            .await
+            .map_err(AppError::from)?;
+
+        let reused = std::iter::once(user.password.as_str())
+            .chain(history.iter().map(|entry| entry.password_hash.as_str()))
+            .any(|hash| {
+                SecureUtil::verify_password(new_password.as_bytes(), hash).unwrap_or(false)
+            });
+        if reused {
+            return Err(UserError::PasswordReused.into());
+        }
+    }
+
+    let password_hash =
+        SecureUtil::hash_password(new_password.as_bytes()).map_err(|e| AppError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+    let now = Local::now().naive_local();
+    let user_id = user.id.clone();
+    let old_hash = user.password.clone();
+
+    let mut active = user.into_active_model();
+    active.password = Set(password_hash);
+    active.password_changed_at = Set(Some(now));
+    active.must_change_password = Set(must_change_password);
+    active.updated_at = Set(Some(now));
+    active.update(db).await.map_err(AppError::from)?;
+
+    if policy.history_size > 0 {
+        SysUserPasswordHistoryActiveModel {
+            id: Set(Ulid::new().to_string()),
+            user_id: Set(user_id.clone()),
+            password_hash: Set(old_hash),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .map_err(AppError::from)?;
+
+        // 只保留最近 N 条历史记录
+        let expired: Vec<String> = SysUserPasswordHistory::find()
+            .filter(SysUserPasswordHistoryColumn::UserId.eq(&user_id))
+            .order_by_desc(SysUserPasswordHistoryColumn::CreatedAt)
+            .offset(policy.history_size as u64)
+            .all(db)
+            .await
+            .map_err(AppError::from)?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+
+        if !expired.is_empty() {
+            SysUserPasswordHistory::delete_many()
+                .filter(SysUserPasswordHistoryColumn::Id.is_in(expired))
+                .exec(db)
+                .await
+                .map_err(AppError::from)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use server_core::web::auth::Claims;
+
+    use super::*;
+
+    fn operator(domain: &str) -> User {
+        User::from(Claims::new(
+            "operator".to_string(),
+            "test-aud".to_string(),
+            "operator".to_string(),
+            vec!["ROLE_ADMIN".to_string()],
+            domain.to_string(),
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_tenant_operator_cannot_manage_built_in_user() {
+        let error = check_domain_access(&operator("tenant-a"), "built-in").unwrap_err();
+        assert_eq!(error.code, 403);
+
+        assert!(check_domain_access(&operator("tenant-a"), "tenant-a").is_ok());
+        assert!(check_domain_access(&operator("built-in"), "tenant-a").is_ok());
+    }
 }
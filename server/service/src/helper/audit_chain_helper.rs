@@ -0,0 +1,262 @@
+//! 审计日志哈希链
+//!
+//! 每个域的操作日志与登录日志各组成一条链，记录保存链序号、上一条记录的哈希与自身的哈希。
+//! 各链最新的序号与哈希缓存在内存中，首次使用或写入失败后从主数据库重新读取。
+//! 多个实例写入同一条链时，链序号冲突的记录会被唯一索引拒绝，重新读取链头并入链后再写入。
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use once_cell::sync::Lazy;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use serde_json::Value;
+use server_config::AuditChainConfig;
+use server_core::{
+    sign::{chain_hash, GENESIS_HASH},
+    web::error::AppError,
+};
+use server_global::global;
+use server_model::admin::{
+    entities::{
+        prelude::{SysLoginLog, SysOperationLog},
+        sys_login_log, sys_operation_log,
+    },
+    input::AuditLogType,
+};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::helper::db_helper;
+
+/// 组成哈希链的日志表
+pub trait ChainedLog: EntityTrait<Model: Serialize + Send + Sync> {
+    const LOG_TYPE: AuditLogType;
+
+    fn domain_column() -> Self::Column;
+    fn chain_seq_column() -> Self::Column;
+    fn created_at_column() -> Self::Column;
+
+    fn domain(model: &Self::Model) -> &str;
+    fn set_link(model: &mut Self::Model, chain_seq: i64, prev_hash: String, hash: String);
+    fn clear_link(model: &mut Self::Model);
+}
+
+impl ChainedLog for SysOperationLog {
+    const LOG_TYPE: AuditLogType = AuditLogType::Operation;
+
+    fn domain_column() -> Self::Column {
+        sys_operation_log::Column::Domain
+    }
+
+    fn chain_seq_column() -> Self::Column {
+        sys_operation_log::Column::ChainSeq
+    }
+
+    fn created_at_column() -> Self::Column {
+        sys_operation_log::Column::CreatedAt
+    }
+
+    fn domain(model: &Self::Model) -> &str {
+        &model.domain
+    }
+
+    fn set_link(model: &mut Self::Model, chain_seq: i64, prev_hash: String, hash: String) {
+        model.chain_seq = Some(chain_seq);
+        model.prev_hash = Some(prev_hash);
+        model.hash = Some(hash);
+    }
+
+    fn clear_link(model: &mut Self::Model) {
+        model.chain_seq = None;
+        model.prev_hash = None;
+        model.hash = None;
+    }
+}
+
+impl ChainedLog for SysLoginLog {
+    const LOG_TYPE: AuditLogType = AuditLogType::Login;
+
+    fn domain_column() -> Self::Column {
+        sys_login_log::Column::Domain
+    }
+
+    fn chain_seq_column() -> Self::Column {
+        sys_login_log::Column::ChainSeq
+    }
+
+    fn created_at_column() -> Self::Column {
+        sys_login_log::Column::CreatedAt
+    }
+
+    fn domain(model: &Self::Model) -> &str {
+        &model.domain
+    }
+
+    fn set_link(model: &mut Self::Model, chain_seq: i64, prev_hash: String, hash: String) {
+        model.chain_seq = Some(chain_seq);
+        model.prev_hash = Some(prev_hash);
+        model.hash = Some(hash);
+    }
+
+    fn clear_link(model: &mut Self::Model) {
+        model.chain_seq = None;
+        model.prev_hash = None;
+        model.hash = None;
+    }
+}
+
+/// 链上的一条记录
+#[derive(Debug, Clone)]
+pub struct ChainLink {
+    pub id: String,
+    pub chain_seq: i64,
+    pub prev_hash: String,
+    pub hash: String,
+    /// 参与哈希计算的内容，不含两个哈希字段
+    pub content: Value,
+}
+
+impl ChainLink {
+    /// 从记录中取出链信息，未入链的记录返回 `None`
+    pub fn from_model<T: Serialize>(model: &T) -> Option<Self> {
+        let mut content = serde_json::to_value(model).ok()?;
+        let map = content.as_object_mut()?;
+        let prev_hash = map.remove("prevHash")?.as_str()?.to_string();
+        let hash = map.remove("hash")?.as_str()?.to_string();
+        let chain_seq = map.get("chainSeq")?.as_i64()?;
+        let id = map.get("id")?.as_str()?.to_string();
+
+        Some(Self {
+            id,
+            chain_seq,
+            prev_hash,
+            hash,
+            content,
+        })
+    }
+
+    /// 按保存的上一条哈希重新计算的哈希
+    pub fn computed_hash(&self) -> String {
+        chain_hash(&self.prev_hash, &self.content)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ChainHead {
+    chain_seq: i64,
+    hash: String,
+}
+
+type HeadMap = HashMap<String, ChainHead>;
+
+static OPERATION_HEADS: Lazy<Mutex<HeadMap>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static LOGIN_HEADS: Lazy<Mutex<HeadMap>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 是否启用哈希链
+pub async fn is_enabled() -> bool {
+    global::get_config::<AuditChainConfig>()
+        .await
+        .is_some_and(|config| config.enabled)
+}
+
+/// 独占一类日志各域的链头
+///
+/// 入链后应持有到记录写入完成，避免后续记录接在未写入的记录之后。
+pub async fn lock_heads<E: ChainedLog>() -> ChainHeads<E> {
+    let heads = match E::LOG_TYPE {
+        AuditLogType::Operation => &OPERATION_HEADS,
+        AuditLogType::Login => &LOGIN_HEADS,
+    };
+    ChainHeads {
+        heads: heads.lock().await,
+        log: PhantomData,
+    }
+}
+
+/// 一类日志各域的链头，释放前同类日志无法入链
+pub struct ChainHeads<E: ChainedLog> {
+    heads: MutexGuard<'static, HeadMap>,
+    log: PhantomData<E>,
+}
+
+impl<E: ChainedLog> ChainHeads<E> {
+    /// 按记录的顺序将其接入各自域的链
+    ///
+    /// 所有域的链头读取成功后才修改记录，失败时记录保持原样。
+    pub async fn link(&mut self, records: &mut [E::Model]) -> Result<(), AppError> {
+        let domains: HashSet<String> = records
+            .iter()
+            .map(|record| E::domain(record).to_string())
+            .filter(|domain| !self.heads.contains_key(domain))
+            .collect();
+        for domain in domains {
+            let head = match latest_link::<E>(&domain).await? {
+                Some(link) => ChainHead {
+                    chain_seq: link.chain_seq,
+                    hash: link.hash,
+                },
+                None => ChainHead {
+                    chain_seq: 0,
+                    hash: GENESIS_HASH.to_string(),
+                },
+            };
+            self.heads.insert(domain, head);
+        }
+
+        for record in records {
+            let Some(head) = self.heads.get_mut(E::domain(record)) else {
+                continue;
+            };
+            let chain_seq = head.chain_seq + 1;
+            let prev_hash = std::mem::take(&mut head.hash);
+
+            E::set_link(record, chain_seq, prev_hash.clone(), String::new());
+            let hash = ChainLink::from_model(record)
+                .map(|link| link.computed_hash())
+                .unwrap_or_default();
+            E::set_link(record, chain_seq, prev_hash, hash.clone());
+
+            *head = ChainHead { chain_seq, hash };
+        }
+
+        Ok(())
+    }
+
+    /// 丢弃记录所在域的链头，从主数据库重新读取后将记录重新入链
+    ///
+    /// 记录写入主数据库失败后、重试前调用，使记录接在已保存的最后一条记录之后，
+    /// 其他实例已写入相同链序号时也不会再次冲突。读取失败时记录保持未入链。
+    pub async fn relink(&mut self, records: &mut [E::Model]) -> Result<(), AppError> {
+        for record in records.iter_mut() {
+            self.heads.remove(E::domain(record));
+            E::clear_link(record);
+        }
+        self.link(records).await
+    }
+
+    /// 丢弃记录所在域的链头，下次入链时从主数据库重新读取
+    ///
+    /// 记录最终写入失败后调用，使后续记录接在已保存的最后一条记录之后。
+    pub fn reset(&mut self, records: &[E::Model]) {
+        for record in records {
+            self.heads.remove(E::domain(record));
+        }
+    }
+}
+
+/// 主数据库中一个域的链上最新记录
+pub async fn latest_link<E: ChainedLog>(domain: &str) -> Result<Option<ChainLink>, AppError> {
+    let db = db_helper::get_db_connection().await?;
+
+    let record = E::find()
+        .filter(E::domain_column().eq(domain))
+        .filter(E::chain_seq_column().is_not_null())
+        .order_by_desc(E::chain_seq_column())
+        .one(db.as_ref())
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(record.as_ref().and_then(ChainLink::from_model))
+}
@@ -0,0 +1,181 @@
+//! 数据权限（行级数据范围）
+//!
+//! Casbin 只控制角色能否访问接口，返回哪些数据行由角色的数据范围决定。
+//! 用户拥有多个角色时取各角色数据范围的并集。
+
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::{
+    sea_query::{Expr, Query},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use server_core::web::{auth::User, error::AppError};
+use server_model::admin::entities::{
+    prelude::{SysOrganization, SysRole, SysRoleDataScope, SysUserOrganization},
+    sea_orm_active_enums::{DataScope, Status},
+    sys_role::Column as SysRoleColumn,
+    sys_role_data_scope::Column as SysRoleDataScopeColumn,
+    sys_user_organization::Column as SysUserOrganizationColumn,
+};
+
+use super::db_helper;
+use crate::admin::granted_in_domain;
+
+/// 当前用户可访问的数据范围
+#[derive(Debug, Default)]
+pub struct DataScopeFilter {
+    /// 可访问全部数据
+    all: bool,
+    /// 可访问该域的数据
+    domain: Option<String>,
+    /// 可访问归属这些组织的用户的数据
+    org_ids: HashSet<String>,
+    /// 可访问本人的数据
+    user_id: Option<String>,
+}
+
+impl DataScopeFilter {
+    /// 根据用户的角色解析数据范围，没有有效角色时仅可访问本人数据
+    ///
+    /// 只有在用户所在域获得授权的角色参与计算。
+    pub async fn resolve(user: &User) -> Result<Self, AppError> {
+        let db = db_helper::get_db_connection().await?;
+
+        let roles = SysRole::find()
+            .filter(SysRoleColumn::Code.is_in(user.subject()))
+            .filter(SysRoleColumn::Status.eq(Status::ENABLED))
+            .filter(granted_in_domain(&user.domain()))
+            .all(db.as_ref())
+            .await
+            .map_err(AppError::from)?;
+
+        let mut filter = DataScopeFilter::default();
+        if roles.is_empty() {
+            filter.user_id = Some(user.user_id());
+            return Ok(filter);
+        }
+
+        let scopes: HashSet<DataScope> = roles.iter().map(|role| role.data_scope).collect();
+        if scopes.contains(&DataScope::All) {
+            filter.all = true;
+            return Ok(filter);
+        }
+
+        if scopes.contains(&DataScope::Domain) {
+            filter.domain = Some(user.domain());
+        }
+
+        if scopes.contains(&DataScope::SelfOnly) {
+            filter.user_id = Some(user.user_id());
+        }
+
+        if scopes.contains(&DataScope::Org) || scopes.contains(&DataScope::OrgAndChildren) {
+            if let Some(org_id) = find_user_org_id(db.as_ref(), &user.user_id()).await? {
+                if scopes.contains(&DataScope::OrgAndChildren) {
+                    filter
+                        .org_ids
+                        .extend(find_descendant_org_ids(db.as_ref(), &org_id).await?);
+                }
+                filter.org_ids.insert(org_id);
+            }
+        }
+
+        let custom_role_ids: Vec<String> = roles
+            .iter()
+            .filter(|role| role.data_scope == DataScope::Custom)
+            .map(|role| role.id.clone())
+            .collect();
+        if !custom_role_ids.is_empty() {
+            let custom_orgs = SysRoleDataScope::find()
+                .filter(SysRoleDataScopeColumn::RoleId.is_in(custom_role_ids))
+                .all(db.as_ref())
+                .await
+                .map_err(AppError::from)?;
+            filter
+                .org_ids
+                .extend(custom_orgs.into_iter().map(|scope| scope.org_id));
+        }
+
+        Ok(filter)
+    }
+
+    /// 生成过滤条件，可访问全部数据时返回 `None`
+    ///
+    /// `domain_column` 为数据所属域的列，`user_id_column` 为数据所属用户 ID 的列。
+    pub fn condition<D, U>(&self, domain_column: D, user_id_column: U) -> Option<Condition>
+    where
+        D: ColumnTrait,
+        U: ColumnTrait,
+    {
+        if self.all {
+            return None;
+        }
+
+        let mut condition = Condition::any();
+        if let Some(domain) = &self.domain {
+            condition = condition.add(domain_column.eq(domain.as_str()));
+        }
+        if !self.org_ids.is_empty() {
+            condition = condition.add(
+                user_id_column.in_subquery(
+                    Query::select()
+                        .column(SysUserOrganizationColumn::UserId)
+                        .from(SysUserOrganization)
+                        .and_where(
+                            SysUserOrganizationColumn::OrgId.is_in(self.org_ids.iter().cloned()),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+        if let Some(user_id) = &self.user_id {
+            condition = condition.add(user_id_column.eq(user_id.as_str()));
+        }
+
+        // 空的 any 条件不会产生任何过滤，需显式排除所有数据
+        if condition.is_empty() {
+            condition = condition.add(Expr::val(1).eq(0));
+        }
+
+        Some(condition)
+    }
+}
+
+async fn find_user_org_id(
+    db: &DatabaseConnection,
+    user_id: &str,
+) -> Result<Option<String>, AppError> {
+    SysUserOrganization::find_by_id(user_id)
+        .one(db)
+        .await
+        .map(|link| link.map(|link| link.org_id))
+        .map_err(AppError::from)
+}
+
+/// 查找组织的全部下级组织，不含自身
+async fn find_descendant_org_ids(
+    db: &DatabaseConnection,
+    org_id: &str,
+) -> Result<HashSet<String>, AppError> {
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for org in SysOrganization::find()
+        .all(db)
+        .await
+        .map_err(AppError::from)?
+    {
+        children.entry(org.pid).or_default().push(org.id);
+    }
+
+    let mut descendants = HashSet::new();
+    let mut pending = vec![org_id.to_string()];
+    while let Some(current) = pending.pop() {
+        for child in children.get(&current).into_iter().flatten() {
+            if descendants.insert(child.clone()) {
+                pending.push(child.clone());
+            }
+        }
+    }
+    descendants.remove(org_id);
+
+    Ok(descendants)
+}
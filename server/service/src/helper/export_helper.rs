@@ -0,0 +1,477 @@
+//! 日志导出
+//!
+//! 记录在后台任务中从数据库游标逐条读取并编码，编码后的内容经有界通道交给响应体或上传任务，
+//! 内存占用与导出的记录数无关。异步导出任务保存在内存中，文件分段上传到 S3，
+//! 服务重启后未完成的任务与任务记录都会丢失。
+
+use std::{collections::HashMap, io, sync::Arc, time::Duration};
+
+use aws_sdk_s3::{
+    error::DisplayErrorContext,
+    presigning::PresigningConfig,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
+    Client as S3Client,
+};
+use bytes::Bytes;
+use chrono::{Local, TimeDelta};
+use futures::{stream, StreamExt};
+use once_cell::sync::Lazy;
+use sea_orm::{EntityTrait, Select};
+use serde::Serialize;
+use server_config::ExportConfig;
+use server_core::web::{
+    error::AppError,
+    export::{ExportEncoder, ExportFile, ExportFormat, ExportStream},
+};
+use server_global::{global, project_error, project_info};
+use server_model::admin::{
+    input::AuditLogType,
+    output::{ExportJobOutput, ExportJobStatus},
+};
+use tokio::{
+    sync::{mpsc, Mutex, OnceCell, Semaphore},
+    task::JoinHandle,
+};
+use ulid::Ulid;
+
+use crate::helper::{db_helper, s3_helper};
+
+/// 每次发送的数据块大小
+const CHUNK_SIZE: usize = 64 * 1024;
+/// 通道中缓冲的数据块数
+const CHANNEL_CAPACITY: usize = 8;
+/// 分段上传的分段大小，S3 要求除最后一段外不小于 5 MiB
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+struct ExportJob {
+    owner: String,
+    bucket: String,
+    key: String,
+    filename: String,
+    output: ExportJobOutput,
+}
+
+static EXPORT_JOBS: Lazy<Mutex<HashMap<String, ExportJob>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static JOB_PERMITS: OnceCell<Semaphore> = OnceCell::const_new();
+
+/// 以流的形式导出查询结果
+pub fn export_file<E>(
+    query: Select<E>,
+    log_type: AuditLogType,
+    format: ExportFormat,
+    columns: &'static [&'static str],
+) -> ExportFile
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+{
+    let (stream, _) = spawn_export(query, format, columns);
+    ExportFile {
+        filename: export_filename(log_type),
+        format,
+        stream,
+    }
+}
+
+/// 创建异步导出任务，导出的文件在后台上传到 S3
+pub async fn create_export_job<E>(
+    query: Select<E>,
+    log_type: AuditLogType,
+    format: ExportFormat,
+    columns: &'static [&'static str],
+    owner: &str,
+) -> Result<ExportJobOutput, AppError>
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+{
+    let config = export_config().await;
+    let bucket = config.bucket.clone().ok_or_else(|| AppError {
+        code: 400,
+        message: "Export jobs are not enabled, export.bucket is not configured".to_string(),
+    })?;
+    let client = s3_helper::get_client(config.s3_instance.as_deref()).await?;
+
+    let id = Ulid::new().to_string();
+    let key = format!(
+        "{}{}/{}.{}",
+        config.key_prefix,
+        log_type.as_str(),
+        id,
+        format.extension()
+    );
+    let output = ExportJobOutput {
+        id: id.clone(),
+        log_type,
+        format,
+        status: ExportJobStatus::Pending,
+        rows: 0,
+        size: 0,
+        download_url: None,
+        expires_at: None,
+        error: None,
+        created_at: Local::now().naive_local(),
+        finished_at: None,
+    };
+
+    {
+        let mut jobs = EXPORT_JOBS.lock().await;
+        purge_expired(&mut jobs, config.job_retention_secs);
+        jobs.insert(
+            id.clone(),
+            ExportJob {
+                owner: owner.to_string(),
+                bucket: bucket.clone(),
+                key: key.clone(),
+                filename: export_filename(log_type),
+                output: output.clone(),
+            },
+        );
+    }
+
+    tokio::spawn(async move {
+        let permits = JOB_PERMITS
+            .get_or_init(|| async { Semaphore::new(config.max_running_jobs.max(1)) })
+            .await;
+        let _permit = permits.acquire().await;
+        update_job(&id, |job| job.status = ExportJobStatus::Running).await;
+
+        let (stream, handle) = spawn_export(query, format, columns);
+        let uploaded = upload(&client, &bucket, &key, format, stream, &id).await;
+        let encoded = handle.await.unwrap_or_else(|e| {
+            Err(AppError {
+                code: 500,
+                message: e.to_string(),
+            })
+        });
+
+        // 上传失败时编码任务只会报告数据流已关闭，优先保留上传的错误
+        match (uploaded, encoded) {
+            (Ok(size), Ok(rows)) => {
+                project_info!("Export job {} uploaded {} rows to {}", id, rows, key);
+                update_job(&id, |job| {
+                    job.status = ExportJobStatus::Completed;
+                    job.rows = rows;
+                    job.size = size;
+                    job.finished_at = Some(Local::now().naive_local());
+                })
+                .await;
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                project_error!("Export job {} failed: {}", id, e.message);
+                update_job(&id, |job| {
+                    job.status = ExportJobStatus::Failed;
+                    job.error = Some(e.message);
+                    job.finished_at = Some(Local::now().naive_local());
+                })
+                .await;
+            },
+        }
+    });
+
+    Ok(output)
+}
+
+/// 查询异步导出任务，只能查询自己创建的任务
+///
+/// 任务完成后每次查询都签发新的下载链接。
+pub async fn get_export_job(
+    id: &str,
+    log_type: AuditLogType,
+    owner: &str,
+) -> Result<ExportJobOutput, AppError> {
+    let config = export_config().await;
+
+    let (mut output, bucket, key, filename) = {
+        let mut jobs = EXPORT_JOBS.lock().await;
+        purge_expired(&mut jobs, config.job_retention_secs);
+        let job = jobs
+            .get(id)
+            .filter(|job| job.owner == owner && job.output.log_type == log_type)
+            .ok_or_else(|| AppError {
+                code: 404,
+                message: "Export job not found".to_string(),
+            })?;
+        (
+            job.output.clone(),
+            job.bucket.clone(),
+            job.key.clone(),
+            job.filename.clone(),
+        )
+    };
+
+    if output.status == ExportJobStatus::Completed {
+        let client = s3_helper::get_client(config.s3_instance.as_deref()).await?;
+        let presigning =
+            PresigningConfig::expires_in(Duration::from_secs(config.link_expires_secs))
+                .map_err(s3_error)?;
+        let request = client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .response_content_disposition(format!(
+                "attachment; filename=\"{}.{}\"",
+                filename,
+                output.format.extension()
+            ))
+            .presigned(presigning)
+            .await
+            .map_err(s3_error)?;
+
+        output.download_url = Some(request.uri().to_string());
+        output.expires_at =
+            Some(Local::now().naive_local() + TimeDelta::seconds(config.link_expires_secs as i64));
+    }
+
+    Ok(output)
+}
+
+/// 导出的文件名，不含扩展名
+fn export_filename(log_type: AuditLogType) -> String {
+    format!(
+        "{}-logs-{}",
+        log_type.as_str(),
+        Local::now().format("%Y%m%d%H%M%S")
+    )
+}
+
+async fn export_config() -> Arc<ExportConfig> {
+    global::get_config::<ExportConfig>()
+        .await
+        .unwrap_or_default()
+}
+
+/// 在后台任务中编码查询结果，返回编码后的数据流与导出记录数的句柄
+///
+/// 数据流被丢弃时，任务在下一次发送时停止读取。
+fn spawn_export<E>(
+    query: Select<E>,
+    format: ExportFormat,
+    columns: &'static [&'static str],
+) -> (ExportStream, JoinHandle<Result<u64, AppError>>)
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+{
+    let (tx, rx) = mpsc::channel::<Result<Bytes, io::Error>>(CHANNEL_CAPACITY);
+
+    let handle = tokio::spawn(async move {
+        let result = encode_rows(query, format, columns, &tx).await;
+        if let Err(ref e) = result {
+            let _ = tx.send(Err(io::Error::other(e.message.clone()))).await;
+        }
+        result
+    });
+
+    let stream = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+    (Box::pin(stream), handle)
+}
+
+async fn encode_rows<E>(
+    query: Select<E>,
+    format: ExportFormat,
+    columns: &'static [&'static str],
+    tx: &mpsc::Sender<Result<Bytes, io::Error>>,
+) -> Result<u64, AppError>
+where
+    E: EntityTrait,
+    E::Model: Serialize + Sync,
+{
+    let db = db_helper::get_db_connection().await?;
+    let mut rows = Box::pin(query.stream(db.as_ref()).await.map_err(AppError::from)?);
+
+    let mut encoder = ExportEncoder::new(format, columns);
+    let mut buffer = Vec::with_capacity(CHUNK_SIZE);
+    let mut count = 0;
+
+    while let Some(row) = rows.next().await {
+        let row = row.map_err(AppError::from)?;
+        let value = serde_json::to_value(&row).map_err(|e| AppError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+        encoder.row(&value, &mut buffer)?;
+        count += 1;
+
+        if buffer.len() >= CHUNK_SIZE {
+            send_chunk(tx, &mut buffer).await?;
+        }
+    }
+
+    buffer.extend(encoder.finish()?);
+    send_chunk(tx, &mut buffer).await?;
+    Ok(count)
+}
+
+async fn send_chunk(
+    tx: &mpsc::Sender<Result<Bytes, io::Error>>,
+    buffer: &mut Vec<u8>,
+) -> Result<(), AppError> {
+    let chunk = Bytes::from(std::mem::replace(buffer, Vec::with_capacity(CHUNK_SIZE)));
+    tx.send(Ok(chunk)).await.map_err(|_| AppError {
+        code: 500,
+        message: "Export stream closed".to_string(),
+    })
+}
+
+/// 将数据流上传到 S3，返回文件大小
+///
+/// 内容不足一个分段时直接上传对象，否则使用分段上传，失败时取消分段上传。
+async fn upload(
+    client: &S3Client,
+    bucket: &str,
+    key: &str,
+    format: ExportFormat,
+    stream: ExportStream,
+    job_id: &str,
+) -> Result<u64, AppError> {
+    let mut upload_id = None;
+    let result = upload_parts(client, bucket, key, format, stream, job_id, &mut upload_id).await;
+
+    if let (Err(_), Some(upload_id)) = (&result, upload_id) {
+        if let Err(e) = client
+            .abort_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .send()
+            .await
+        {
+            project_error!(
+                "Failed to abort multipart upload of {}: {}",
+                key,
+                DisplayErrorContext(e)
+            );
+        }
+    }
+
+    result
+}
+
+async fn upload_parts(
+    client: &S3Client,
+    bucket: &str,
+    key: &str,
+    format: ExportFormat,
+    mut stream: ExportStream,
+    job_id: &str,
+    upload_id: &mut Option<String>,
+) -> Result<u64, AppError> {
+    let mut part = Vec::with_capacity(PART_SIZE);
+    let mut parts = Vec::new();
+    let mut size = 0;
+
+    loop {
+        let chunk = stream.next().await.transpose().map_err(|e| AppError {
+            code: 500,
+            message: e.to_string(),
+        })?;
+        let finished = chunk.is_none();
+        if let Some(chunk) = chunk {
+            part.extend_from_slice(&chunk);
+        }
+
+        if finished && upload_id.is_none() {
+            size = part.len() as u64;
+            client
+                .put_object()
+                .bucket(bucket)
+                .key(key)
+                .content_type(format.content_type())
+                .body(ByteStream::from(part))
+                .send()
+                .await
+                .map_err(s3_error)?;
+            return Ok(size);
+        }
+
+        if part.len() >= PART_SIZE || (finished && !part.is_empty()) {
+            let id = match upload_id {
+                Some(id) => id.clone(),
+                None => {
+                    let created = client
+                        .create_multipart_upload()
+                        .bucket(bucket)
+                        .key(key)
+                        .content_type(format.content_type())
+                        .send()
+                        .await
+                        .map_err(s3_error)?;
+                    let id = created.upload_id().unwrap_or_default().to_string();
+                    upload_id.insert(id).clone()
+                },
+            };
+
+            let part_number = parts.len() as i32 + 1;
+            size += part.len() as u64;
+            let uploaded = client
+                .upload_part()
+                .bucket(bucket)
+                .key(key)
+                .upload_id(id)
+                .part_number(part_number)
+                .body(ByteStream::from(std::mem::replace(
+                    &mut part,
+                    Vec::with_capacity(PART_SIZE),
+                )))
+                .send()
+                .await
+                .map_err(s3_error)?;
+            parts.push(
+                CompletedPart::builder()
+                    .set_e_tag(uploaded.e_tag().map(ToString::to_string))
+                    .part_number(part_number)
+                    .build(),
+            );
+            update_job(job_id, |job| job.size = size).await;
+        }
+
+        if finished {
+            break;
+        }
+    }
+
+    client
+        .complete_multipart_upload()
+        .bucket(bucket)
+        .key(key)
+        .set_upload_id(upload_id.clone())
+        .multipart_upload(
+            CompletedMultipartUpload::builder()
+                .set_parts(Some(parts))
+                .build(),
+        )
+        .send()
+        .await
+        .map_err(s3_error)?;
+
+    Ok(size)
+}
+
+async fn update_job(id: &str, update: impl FnOnce(&mut ExportJobOutput)) {
+    if let Some(job) = EXPORT_JOBS.lock().await.get_mut(id) {
+        update(&mut job.output);
+    }
+}
+
+/// 移除结束时间超过保留时间的任务记录，已上传的文件由存储桶的生命周期规则清理
+fn purge_expired(jobs: &mut HashMap<String, ExportJob>, retention_secs: u64) {
+    let deadline = Local::now().naive_local() - TimeDelta::seconds(retention_secs as i64);
+    jobs.retain(|_, job| {
+        job.output
+            .finished_at
+            .is_none_or(|finished_at| finished_at > deadline)
+    });
+}
+
+fn s3_error<E: std::error::Error + 'static>(e: E) -> AppError {
+    AppError {
+        code: 500,
+        message: format!("S3 request failed: {}", DisplayErrorContext(e)),
+    }
+}
@@ -1,3 +1,8 @@
+pub mod audit_chain_helper;
+pub mod data_scope_helper;
 pub mod db_helper;
+pub mod export_helper;
 pub mod mongo_helper;
+pub mod policy_csv_helper;
 pub mod redis_helper;
+pub mod s3_helper;
@@ -0,0 +1,74 @@
+//! Casbin CSV 策略格式
+//!
+//! 每行一条规则，首列为规则类型（`p` 或 `g`），列间以逗号分隔。包含逗号的值以双引号
+//! 包裹，与 Casbin 文件适配器的解析方式一致。空行与 `#` 开头的注释行会被忽略。
+
+use server_model::admin::output::AuthorizationBundle;
+
+/// 将数据包中的 p 与 g 规则输出为 CSV
+pub fn to_csv(bundle: &AuthorizationBundle) -> String {
+    let lines = bundle
+        .policies
+        .iter()
+        .map(|rule| ("p", rule))
+        .chain(bundle.groupings.iter().map(|rule| ("g", rule)));
+
+    let mut csv = String::new();
+    for (ptype, rule) in lines {
+        csv.push_str(ptype);
+        for value in rule {
+            csv.push_str(", ");
+            if value.contains(',') {
+                csv.push('"');
+                csv.push_str(value);
+                csv.push('"');
+            } else {
+                csv.push_str(value);
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// 解析 CSV 中的 p 与 g 规则，其他规则类型视为错误
+pub fn from_csv(content: &str) -> Result<AuthorizationBundle, String> {
+    let mut bundle = AuthorizationBundle::default();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut columns = parse_line(line).into_iter();
+        let ptype = columns.next().unwrap_or_default();
+        let rule: Vec<String> = columns.collect();
+        match ptype.as_str() {
+            "p" => bundle.policies.push(rule),
+            "g" => bundle.groupings.push(rule),
+            other => {
+                return Err(format!(
+                    "unsupported policy type `{}` on line {}",
+                    other,
+                    index + 1
+                ))
+            },
+        }
+    }
+    Ok(bundle)
+}
+
+fn parse_line(line: &str) -> Vec<String> {
+    let mut columns = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => columns.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    columns.push(current.trim().to_string());
+    columns
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use aws_sdk_s3::Client as S3Client;
+use server_core::web::error::AppError;
+use server_global::global::{GLOBAL_PRIMARY_S3, GLOBAL_S3_POOL};
+
+/// 获取主 S3 客户端
+pub async fn get_primary_client() -> Result<Arc<S3Client>, AppError> {
+    GLOBAL_PRIMARY_S3
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| AppError {
+            code: 500,
+            message: "Primary S3 client not initialized".to_string(),
+        })
+}
+
+/// 获取命名 S3 客户端
+pub async fn get_named_client(name: &str) -> Result<Arc<S3Client>, AppError> {
+    let pools = GLOBAL_S3_POOL.read().await;
+    pools.get(name).cloned().ok_or_else(|| AppError {
+        code: 500,
+        message: format!("S3 client '{}' not found", name),
+    })
+}
+
+/// 获取命名 S3 客户端，未指定名称时获取主 S3 客户端
+pub async fn get_client(name: Option<&str>) -> Result<Arc<S3Client>, AppError> {
+    match name {
+        Some(name) => get_named_client(name).await,
+        None => get_primary_client().await,
+    }
+}
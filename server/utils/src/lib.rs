@@ -1,5 +1,7 @@
 mod secure_util;
+mod totp_util;
 mod tree_util;
 
 pub use secure_util::*;
+pub use totp_util::*;
 pub use tree_util::*;
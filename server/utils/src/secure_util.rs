@@ -1,32 +1,113 @@
-use std::error::Error;
+use std::{error::Error, sync::RwLock};
 
 use argon2::{
-    password_hash::{rand_core::OsRng, SaltString},
-    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{self, rand_core::OsRng, SaltString},
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
 };
 use lazy_static::lazy_static;
+use md5::{Digest, Md5};
 
 lazy_static! {
-    static ref ARGON2: Argon2<'static> = Argon2::default();
+    static ref ARGON2: RwLock<Argon2<'static>> = RwLock::new(Argon2::default());
 }
 
+/// 加盐 MD5 哈希的前缀，格式为 `$md5$<salt>$<hex(md5(salt + password))>`
+const SALTED_MD5_PREFIX: &str = "$md5$";
+
 pub struct SecureUtil;
 
 impl SecureUtil {
+    /// 设置 Argon2id 参数，影响之后生成的所有哈希
+    pub fn configure_argon2(
+        memory_cost: u32,
+        iterations: u32,
+        parallelism: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let params = Params::new(memory_cost, iterations, parallelism, None)?;
+        let mut argon2 = ARGON2.write().map_err(|e| e.to_string())?;
+        *argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        Ok(())
+    }
+
+    fn argon2() -> Argon2<'static> {
+        ARGON2
+            .read()
+            .map(|argon2| argon2.clone())
+            .unwrap_or_default()
+    }
+
     pub fn hash_password(password: &[u8]) -> Result<String, Box<dyn Error>> {
         let salt = SaltString::generate(&mut OsRng);
-        let password_hash = ARGON2.hash_password(password, &salt)?.to_string();
+        let password_hash = Self::argon2().hash_password(password, &salt)?.to_string();
         Ok(password_hash)
     }
 
+    /// 校验密码
+    ///
+    /// 支持 PHC 格式的 Argon2 哈希、bcrypt（`$2a$`/`$2b$`/`$2y$`）以及加盐 MD5，
+    /// 后两者用于兼容从旧系统迁移的用户。密码不匹配时返回错误。
     pub fn verify_password(password: &[u8], password_hash: &str) -> Result<bool, Box<dyn Error>> {
+        if Self::is_bcrypt(password_hash) {
+            return match bcrypt::verify(password, password_hash)? {
+                true => Ok(true),
+                false => Err(Box::new(password_hash::Error::Password)),
+            };
+        }
+
+        if let Some(rest) = password_hash.strip_prefix(SALTED_MD5_PREFIX) {
+            let (salt, expected) = rest
+                .split_once('$')
+                .ok_or(password_hash::Error::PhcStringField)?;
+            let mut hasher = Md5::new();
+            hasher.update(salt.as_bytes());
+            hasher.update(password);
+            let actual = hex::encode(hasher.finalize());
+
+            return match constant_time_eq(actual.as_bytes(), expected.to_lowercase().as_bytes()) {
+                true => Ok(true),
+                false => Err(Box::new(password_hash::Error::Password)),
+            };
+        }
+
         let parsed_hash = PasswordHash::new(password_hash)?;
 
-        match ARGON2.verify_password(password, &parsed_hash) {
+        match Self::argon2().verify_password(password, &parsed_hash) {
             Ok(_) => Ok(true),
             Err(e) => Err(Box::new(e)),
         }
     }
+
+    /// 判断哈希是否需要按当前配置重新生成
+    ///
+    /// 非 Argon2id 哈希或参数与当前配置不一致时返回 `true`。
+    pub fn needs_rehash(password_hash: &str) -> bool {
+        let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+            return true;
+        };
+        if parsed_hash.algorithm != Algorithm::Argon2id.ident() {
+            return true;
+        }
+
+        let Ok(params) = Params::try_from(&parsed_hash) else {
+            return true;
+        };
+        let current = Self::argon2();
+        let current = current.params();
+
+        params.m_cost() != current.m_cost()
+            || params.t_cost() != current.t_cost()
+            || params.p_cost() != current.p_cost()
+    }
+
+    fn is_bcrypt(password_hash: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| password_hash.starts_with(prefix))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
 
 #[cfg(test)]
@@ -59,4 +140,43 @@ mod tests {
 
         println!("Hashed password for '123456': {}", password_hash);
     }
+
+    #[test]
+    fn test_verify_bcrypt_password() {
+        let password_hash = bcrypt::hash("legacy_password", 4).unwrap();
+        assert!(password_hash.starts_with("$2b$"));
+
+        assert!(SecureUtil::verify_password(b"legacy_password", &password_hash).unwrap());
+        assert!(SecureUtil::verify_password(b"wrong_password", &password_hash).is_err());
+        assert!(SecureUtil::needs_rehash(&password_hash));
+    }
+
+    #[test]
+    fn test_verify_salted_md5_password() {
+        // md5("salt" + "123456")
+        let password_hash = "$md5$salt$f51703256a38e6bab3d9410a070c32ea";
+
+        assert!(SecureUtil::verify_password(b"123456", password_hash).unwrap());
+        assert!(SecureUtil::verify_password(b"654321", password_hash).is_err());
+        assert!(SecureUtil::verify_password(b"123456", "$md5$malformed").is_err());
+        assert!(SecureUtil::needs_rehash(password_hash));
+    }
+
+    #[test]
+    fn test_needs_rehash_for_argon2_params() {
+        let password_hash = SecureUtil::hash_password(b"example_password").unwrap();
+        assert!(!SecureUtil::needs_rehash(&password_hash));
+
+        // 参数不同的 Argon2 哈希需要重新生成
+        let weaker = Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            Params::new(8 * 1024, 1, 1, None).unwrap(),
+        )
+        .hash_password(b"example_password", &SaltString::generate(&mut OsRng))
+        .unwrap()
+        .to_string();
+        assert!(SecureUtil::verify_password(b"example_password", &weaker).unwrap());
+        assert!(SecureUtil::needs_rehash(&weaker));
+    }
 }
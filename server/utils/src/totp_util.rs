@@ -0,0 +1,250 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use ring::hmac;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 6238 TOTP 工具
+///
+/// 使用 HMAC-SHA1、6 位验证码、30 秒时间步长，与主流认证器应用兼容。
+/// 所有校验函数都显式接收 Unix 时间戳，便于使用固定时钟测试。
+pub struct TotpUtil;
+
+impl TotpUtil {
+    /// 验证码位数
+    pub const DIGITS: u32 = 6;
+    /// 时间步长（秒）
+    pub const PERIOD: u64 = 30;
+
+    /// 生成 160 位随机密钥，返回 Base32 编码（无填充）
+    pub fn generate_secret() -> String {
+        let mut bytes = [0u8; 20];
+        OsRng.fill_bytes(&mut bytes);
+        base32_encode(&bytes)
+    }
+
+    /// 计算时间戳所在时间步
+    pub fn time_step(unix_time: u64) -> u64 {
+        unix_time / Self::PERIOD
+    }
+
+    /// 计算指定时间步的验证码，密钥不是合法的 Base32 时返回 `None`
+    pub fn code_at_step(secret: &str, step: u64) -> Option<String> {
+        let key = base32_decode(secret)?;
+        Some(hotp(&key, step, Self::DIGITS))
+    }
+
+    /// 计算指定时间戳的验证码
+    pub fn generate_code(secret: &str, unix_time: u64) -> Option<String> {
+        Self::code_at_step(secret, Self::time_step(unix_time))
+    }
+
+    /// 校验验证码，允许前后 `skew` 个时间步的时钟偏差
+    ///
+    /// 校验通过时返回匹配的时间步，调用方可据此拒绝重放。
+    pub fn verify_code(secret: &str, code: &str, unix_time: u64, skew: u64) -> Option<u64> {
+        let code = code.trim();
+        if code.len() != Self::DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let key = base32_decode(secret)?;
+        let current = Self::time_step(unix_time);
+        (current.saturating_sub(skew)..=current + skew).find(|step| {
+            constant_time_eq(hotp(&key, *step, Self::DIGITS).as_bytes(), code.as_bytes())
+        })
+    }
+
+    /// 生成认证器应用可识别的 `otpauth://` URI
+    pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+        let issuer = urlencoding::encode(issuer);
+        let account = urlencoding::encode(account);
+        format!(
+            "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={}&period={}",
+            Self::DIGITS,
+            Self::PERIOD
+        )
+    }
+
+    /// 生成一次性恢复码，格式为 `XXXXX-XXXXX`
+    pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| {
+                let mut bytes = [0u8; 7];
+                OsRng.fill_bytes(&mut bytes);
+                let code = base32_encode(&bytes);
+                format!("{}-{}", &code[..5], &code[5..10])
+            })
+            .collect()
+    }
+
+    /// 规范化用户输入的恢复码：去除分隔符与空白并转为大写
+    pub fn normalize_recovery_code(code: &str) -> String {
+        code.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+}
+
+fn hotp(key: &[u8], counter: u64, digits: u32) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+    let tag = hmac::sign(&key, &counter.to_be_bytes());
+    let hash = tag.as_ref();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(digits),
+        width = digits as usize
+    )
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !matches!(c, ' ' | '-' | '=')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    if output.is_empty() {
+        None
+    } else {
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 附录 B 中 SHA1 测试密钥 "12345678901234567890" 的 Base32 编码
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+
+        for (time, expected) in vectors {
+            assert_eq!(
+                TotpUtil::generate_code(RFC_SECRET, time).as_deref(),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_code_with_skew() {
+        let step = TotpUtil::time_step(1111111111);
+        assert_eq!(
+            TotpUtil::verify_code(RFC_SECRET, "050471", 1111111111, 0),
+            Some(step)
+        );
+
+        // 上一个时间步的验证码在允许偏差内有效
+        let previous = TotpUtil::code_at_step(RFC_SECRET, step - 1).unwrap();
+        assert_eq!(
+            TotpUtil::verify_code(RFC_SECRET, &previous, 1111111111, 1),
+            Some(step - 1)
+        );
+        assert_eq!(
+            TotpUtil::verify_code(RFC_SECRET, &previous, 1111111111, 0),
+            None
+        );
+
+        assert_eq!(
+            TotpUtil::verify_code(RFC_SECRET, "12345", 1111111111, 1),
+            None
+        );
+        assert_eq!(
+            TotpUtil::verify_code(RFC_SECRET, "abcdef", 1111111111, 1),
+            None
+        );
+        assert_eq!(TotpUtil::verify_code("!!", "050471", 1111111111, 1), None);
+    }
+
+    #[test]
+    fn test_base32_roundtrip() {
+        assert_eq!(base32_encode(b"12345678901234567890"), RFC_SECRET);
+        assert_eq!(
+            base32_decode(RFC_SECRET).unwrap(),
+            b"12345678901234567890".to_vec()
+        );
+
+        let secret = TotpUtil::generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = TotpUtil::otpauth_uri("Soybean Admin", "admin@built-in", RFC_SECRET);
+        assert_eq!(
+            uri,
+            format!(
+                "otpauth://totp/Soybean%20Admin:admin%40built-in?secret={}&issuer=Soybean%20Admin&algorithm=SHA1&digits=6&period=30",
+                RFC_SECRET
+            )
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes() {
+        let codes = TotpUtil::generate_recovery_codes(10);
+        assert_eq!(codes.len(), 10);
+        assert!(codes
+            .iter()
+            .all(|code| code.len() == 11 && &code[5..6] == "-"));
+        assert_eq!(
+            TotpUtil::normalize_recovery_code(" abcde-23456 "),
+            "ABCDE23456"
+        );
+    }
+}
@@ -29,7 +29,7 @@ impl TreeBuilder {
     /// - `Order`: 排序键类型，必须可比较
     ///
     /// # 示例
-    /// ```rust
+    /// ```rust,ignore
     /// let tree = TreeBuilder::build(
     ///     nodes,
     ///     |node| node.id,                            // ID获取器
@@ -111,7 +111,7 @@ impl TreeBuilder {
     /// - 追求最高性能
     ///
     /// # 示例
-    /// ```rust
+    /// ```rust,ignore
     /// let tree = TreeBuilder::build_fast(
     ///     nodes,
     ///     |node| node.id,
@@ -177,7 +177,7 @@ impl TreeBuilder {
     /// 递归构建树结构
     #[inline]
     fn attach_children<T, Id, F1, F2>(
-        nodes: &mut Vec<T>,
+        nodes: &mut [T],
         child_map: &mut HashMap<Id, Vec<T>>,
         id_fn: &F1,
         set_children_fn: &mut F2,